@@ -16,7 +16,7 @@ use ort::{
 };
 use rand::Rng;
 use tokenizers::Tokenizer;
-use tokio::{net::TcpListener, sync::Mutex};
+use tokio::net::TcpListener;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
 // Include common code for `ort` examples that allows using the various feature flags to enable different EPs and
@@ -53,7 +53,7 @@ async fn main() -> anyhow::Result<()> {
 	.unwrap();
 
 	let app_state = AppState {
-		session: Arc::new(Mutex::new(session)),
+		session: Arc::new(session),
 		tokenizer: Arc::new(tokenizer)
 	};
 
@@ -68,13 +68,13 @@ async fn main() -> anyhow::Result<()> {
 
 #[derive(Clone)]
 struct AppState {
-	session: Arc<Mutex<Session>>,
+	session: Arc<Session>,
 	tokenizer: Arc<Tokenizer>
 }
 
 fn generate_stream(
 	tokenizer: Arc<Tokenizer>,
-	session: Arc<Mutex<Session>>,
+	session: Arc<Session>,
 	mut tokens: Vec<i64>,
 	gen_tokens: usize
 ) -> impl Stream<Item = ort::Result<Event>> + Send {
@@ -82,7 +82,6 @@ fn generate_stream(
 		for _ in 0..gen_tokens {
 			let input = TensorRef::from_array_view((vec![1, 1, tokens.len() as i64], tokens.as_slice()))?;
 			let probabilities = {
-				let mut session = session.lock().await;
 				let options = RunOptions::new()?;
 				let outputs = session.run_async(ort::inputs![input], &options)?.await?;
 				let (dim, probabilities) = outputs["output1"].try_extract_tensor()?;
@@ -109,7 +108,7 @@ fn generate_stream(
 	})
 }
 
-impl FromRef<AppState> for Arc<Mutex<Session>> {
+impl FromRef<AppState> for Arc<Session> {
 	fn from_ref(input: &AppState) -> Self {
 		Arc::clone(&input.session)
 	}
@@ -120,6 +119,6 @@ impl FromRef<AppState> for Arc<Tokenizer> {
 	}
 }
 
-async fn generate(State(session): State<Arc<Mutex<Session>>>, State(tokenizer): State<Arc<Tokenizer>>) -> Sse<impl Stream<Item = ort::Result<Event>>> {
+async fn generate(State(session): State<Arc<Session>>, State(tokenizer): State<Arc<Tokenizer>>) -> Sse<impl Stream<Item = ort::Result<Event>>> {
 	Sse::new(generate_stream(tokenizer, session, vec![0], 50)).keep_alive(KeepAlive::new())
 }
@@ -4,11 +4,11 @@ use std::{
 };
 
 use ort::{
+	generate::{Sampler, last_token_logits},
 	inputs,
 	session::{Session, builder::GraphOptimizationLevel},
 	value::TensorRef
 };
-use rand::Rng;
 use tokenizers::Tokenizer;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
@@ -34,10 +34,10 @@ fn main() -> ort::Result<()> {
 	common::init()?;
 
 	let mut stdout: io::Stdout = io::stdout();
-	let mut rng = rand::rng();
+	let mut sampler = Sampler::random().with_top_k(TOP_K);
 
 	// Load our model
-	let mut session = Session::builder()?
+	let session = Session::builder()?
 		.with_optimization_level(GraphOptimizationLevel::Level1)?
 		.with_intra_threads(1)?
 		.commit_from_url("https://cdn.pyke.io/0/pyke:ort-rs/example-models@0.0.0/gpt2.onnx")?;
@@ -55,20 +55,14 @@ fn main() -> ort::Result<()> {
 		// The model expects our input to have shape [B, _, S]
 		let input = TensorRef::from_array_view((vec![1, 1, tokens.len() as i64], tokens.as_slice()))?;
 		let outputs = session.run(inputs![input])?;
-		let (dim, mut probabilities) = outputs["output1"].try_extract_tensor()?;
 
 		// The output tensor will have shape [B, _, S, V]
-		// We want only the probabilities for the last token in this sequence, which will be the next most likely token
+		// We want only the logits for the last token in this sequence, which will be the next most likely token
 		// according to the model
-		let (seq_len, vocab_size) = (dim[2] as usize, dim[3] as usize);
-		probabilities = &probabilities[(seq_len - 1) * vocab_size..];
-
-		// Sort each token by probability
-		let mut probabilities: Vec<(usize, f32)> = probabilities.iter().copied().enumerate().collect();
-		probabilities.sort_unstable_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Less));
+		let logits = last_token_logits(&outputs["output1"], 0)?;
 
 		// Sample using top-k sampling
-		let token = probabilities[rng.random_range(0..=TOP_K)].0 as i64;
+		let token = sampler.sample(&tokens, logits);
 
 		// Add our generated token to the input sequence
 		tokens.push(token);
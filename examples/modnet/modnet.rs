@@ -1,10 +1,9 @@
 #![allow(clippy::manual_retain)]
 
-use std::{ops::Mul, path::Path};
+use std::path::Path;
 
-use image::{GenericImageView, ImageBuffer, Rgba, imageops::FilterType};
-use ndarray::Array;
-use ort::{execution_providers::CUDAExecutionProvider, inputs, session::Session, value::TensorRef};
+use image::{GenericImageView, imageops::FilterType};
+use ort::{execution_providers::CUDAExecutionProvider, inputs, session::Session, tensor::ImageOptions, value::Tensor};
 use show_image::{AsImageView, WindowOptions, event};
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
@@ -24,35 +23,18 @@ fn main() -> ort::Result<()> {
 	// Register EPs based on feature flags - this isn't crucial for usage and can be removed.
 	common::init()?;
 
-	let mut session =
+	let session =
 		Session::builder()?.commit_from_url("https://cdn.pyke.io/0/pyke:ort-rs/example-models@0.0.0/modnet_photographic_portrait_matting.onnx")?;
 
 	let original_img = image::open(Path::new(env!("CARGO_MANIFEST_DIR")).join("data").join("photo.jpg")).unwrap();
 	let (img_width, img_height) = (original_img.width(), original_img.height());
 	let img = original_img.resize_exact(512, 512, FilterType::Triangle);
-	let mut input = Array::zeros((1, 3, 512, 512));
-	for pixel in img.pixels() {
-		let x = pixel.0 as _;
-		let y = pixel.1 as _;
-		let [r, g, b, _] = pixel.2.0;
-		input[[0, 0, y, x]] = (r as f32 - 127.5) / 127.5;
-		input[[0, 1, y, x]] = (g as f32 - 127.5) / 127.5;
-		input[[0, 2, y, x]] = (b as f32 - 127.5) / 127.5;
-	}
-
-	let outputs = session.run(inputs!["input" => TensorRef::from_array_view(input.view())?])?;
+	let input = Tensor::<f32>::from_dynamic_image(&img, &ImageOptions::new().with_normalization([0.5], [0.5]))?;
 
-	let output = outputs["output"].try_extract_array::<f32>()?;
+	let outputs = session.run(inputs!["input" => input])?;
 
-	// convert to 8-bit
-	let output = output.mul(255.0).map(|x| *x as u8);
-	let (output, _) = output.into_raw_vec_and_offset();
-
-	// change rgb to rgba
-	let output_img = ImageBuffer::from_fn(512, 512, |x, y| {
-		let i = (x + y * 512) as usize;
-		Rgba([output[i], output[i], output[i], 255])
-	});
+	// the output is a [1, 1, 512, 512] matte, which converts to a grayscale image
+	let output_img = outputs["output"].try_extract_image(&ImageOptions::new())?.to_rgba8();
 
 	let mut output = image::imageops::resize(&output_img, img_width, img_height, FilterType::Triangle);
 	output.enumerate_pixels_mut().for_each(|(x, y, pixel)| {
@@ -9,7 +9,8 @@
 //! to be used with the Phi-3 vision model, adapting the original Python code to Rust.
 use anyhow::Result;
 use image::{DynamicImage, GenericImageView, ImageBuffer};
-use ndarray::{Array2, Array4, Array5, Axis, s};
+use ndarray::{Array2, Array4, Array5, Axis, Ix4, s};
+use ort::{tensor::ImageOptions, value::Tensor};
 
 /// see https://huggingface.co/microsoft/Phi-3-vision-128k-instruct-onnx-cpu/blob/main/cpu-int4-rtn-block-32-acc-level-4/processor_config.json
 /// NOTE: The default setting in processor_config.json is num_crops = 16,
@@ -56,7 +57,7 @@ impl Phi3VImageProcessor {
 
 		let num_img_tokens = self.calc_num_image_tokens_from_image_size(width, height);
 
-		let normalized = self.normalize_image(&transformed);
+		let normalized = self.normalize_image(&transformed)?;
 		let global_image = self.create_global_image(&normalized);
 		let local_patches = self.create_local_patches(&normalized);
 
@@ -129,17 +130,10 @@ impl Phi3VImageProcessor {
 		(width, target_height)
 	}
 
-	fn normalize_image(&self, image: &DynamicImage) -> Array4<f32> {
-		let (width, height) = image.dimensions();
-		let mut normalized = Array4::<f32>::zeros((1, 3, height as usize, width as usize));
-
-		for (x, y, pixel) in image.pixels() {
-			for c in 0..3 {
-				normalized[[0, c, y as usize, x as usize]] = (pixel[c] as f32 / 255.0 - self.image_mean[c]) / self.image_std[c];
-			}
-		}
-
-		normalized
+	fn normalize_image(&self, image: &DynamicImage) -> Result<Array4<f32>> {
+		let options = ImageOptions::new().with_normalization(&self.image_mean, &self.image_std);
+		let tensor = Tensor::<f32>::from_dynamic_image(image, &options)?;
+		Ok(tensor.try_extract_array::<f32>()?.into_dimensionality::<Ix4>()?.to_owned())
 	}
 
 	fn create_global_image(&self, _image: &Array4<f32>) -> Array4<f32> {
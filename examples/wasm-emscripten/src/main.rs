@@ -66,7 +66,7 @@ pub extern "C" fn detect_objects(ptr: *const u8, width: u32, height: u32) {
 		}
 	}
 
-	let mut session = builder
+	let session = builder
 		.commit_from_memory(include_bytes!("../yolov8m.onnx"))
 		.expect("Cannot commit model.");
 
@@ -3,11 +3,12 @@
 use std::path::Path;
 
 use image::{GenericImageView, imageops::FilterType};
-use ndarray::{Array, Axis, s};
+use ndarray::{Axis, s};
 use ort::{
 	inputs,
 	session::{Session, SessionOutputs},
-	value::TensorRef
+	tensor::ImageOptions,
+	value::Tensor
 };
 use raqote::{DrawOptions, DrawTarget, LineJoin, PathBuilder, SolidSource, Source, StrokeStyle};
 use show_image::{AsImageView, WindowOptions, event};
@@ -62,20 +63,12 @@ fn main() -> ort::Result<()> {
 	let original_img = image::open(Path::new(env!("CARGO_MANIFEST_DIR")).join("data").join("baseball.jpg")).unwrap();
 	let (img_width, img_height) = (original_img.width(), original_img.height());
 	let img = original_img.resize_exact(640, 640, FilterType::CatmullRom);
-	let mut input = Array::zeros((1, 3, 640, 640));
-	for pixel in img.pixels() {
-		let x = pixel.0 as _;
-		let y = pixel.1 as _;
-		let [r, g, b, _] = pixel.2.0;
-		input[[0, 0, y, x]] = (r as f32) / 255.;
-		input[[0, 1, y, x]] = (g as f32) / 255.;
-		input[[0, 2, y, x]] = (b as f32) / 255.;
-	}
+	let input = Tensor::<f32>::from_dynamic_image(&img, &ImageOptions::new())?;
 
-	let mut model = Session::builder()?.commit_from_url(YOLOV8M_URL)?;
+	let model = Session::builder()?.commit_from_url(YOLOV8M_URL)?;
 
 	// Run YOLOv8 inference
-	let outputs: SessionOutputs = model.run(inputs!["images" => TensorRef::from_array_view(&input)?])?;
+	let outputs: SessionOutputs = model.run(inputs!["images" => input])?;
 	let output = outputs["output0"].try_extract_array::<f32>()?.t().into_owned();
 
 	let mut boxes = Vec::new();
@@ -0,0 +1,170 @@
+//! Derive macros for [`ort`](https://docs.rs/ort). These are re-exported by `ort` with the `derive` feature enabled;
+//! you shouldn't need to depend on this crate directly.
+
+use proc_macro::TokenStream;
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::quote;
+use syn::{Data, DeriveInput, Error, Fields, GenericParam, LitInt, LitStr, Result, Type, parse_macro_input};
+
+/// Derives `ort::session::typed::ModelInputs` for a struct whose fields are tensors.
+///
+/// See the documentation of `ort::session::typed` for usage details.
+#[proc_macro_derive(Inputs, attributes(ort))]
+pub fn derive_inputs(input: TokenStream) -> TokenStream {
+	let input = parse_macro_input!(input as DeriveInput);
+	expand(&input, Kind::Inputs).unwrap_or_else(Error::into_compile_error).into()
+}
+
+/// Derives `ort::session::typed::ModelOutputs` for a struct whose fields are tensors.
+///
+/// See the documentation of `ort::session::typed` for usage details.
+#[proc_macro_derive(Outputs, attributes(ort))]
+pub fn derive_outputs(input: TokenStream) -> TokenStream {
+	let input = parse_macro_input!(input as DeriveInput);
+	expand(&input, Kind::Outputs).unwrap_or_else(Error::into_compile_error).into()
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Kind {
+	Inputs,
+	Outputs
+}
+
+struct Field<'a> {
+	ident: &'a syn::Ident,
+	ty: &'a Type,
+	name: LitStr,
+	rank: Option<LitInt>
+}
+
+fn parse_fields(input: &DeriveInput) -> Result<Vec<Field<'_>>> {
+	let Data::Struct(data) = &input.data else {
+		return Err(Error::new(Span::call_site(), "`ort` derive macros can only be used on structs"));
+	};
+	let Fields::Named(fields) = &data.fields else {
+		return Err(Error::new(Span::call_site(), "`ort` derive macros can only be used on structs with named fields"));
+	};
+
+	let mut out = Vec::with_capacity(fields.named.len());
+	for field in &fields.named {
+		let ident = field.ident.as_ref().expect("named fields should have an identifier");
+		let mut name = None;
+		let mut rank = None;
+		for attr in field.attrs.iter().filter(|attr| attr.path().is_ident("ort")) {
+			attr.parse_nested_meta(|meta| {
+				if meta.path.is_ident("name") {
+					name = Some(meta.value()?.parse::<LitStr>()?);
+					Ok(())
+				} else if meta.path.is_ident("rank") {
+					let lit = meta.value()?.parse::<LitInt>()?;
+					lit.base10_parse::<usize>()?;
+					rank = Some(lit);
+					Ok(())
+				} else {
+					Err(meta.error("unknown `ort` attribute; expected `name` or `rank`"))
+				}
+			})?;
+		}
+
+		let name = name.unwrap_or_else(|| {
+			let ident = ident.to_string();
+			LitStr::new(ident.strip_prefix("r#").unwrap_or(&ident), ident_span(field))
+		});
+		out.push(Field { ident, ty: &field.ty, name, rank });
+	}
+	Ok(out)
+}
+
+fn ident_span(field: &syn::Field) -> Span {
+	field.ident.as_ref().map_or_else(Span::call_site, syn::Ident::span)
+}
+
+fn expand(input: &DeriveInput, kind: Kind) -> Result<TokenStream2> {
+	let fields = parse_fields(input)?;
+	let ident = &input.ident;
+	let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+	let descriptors = fields.iter().map(|field| {
+		let Field { ty, name, rank, .. } = field;
+		let rank = match rank {
+			Some(rank) => quote!(::ort::__private::core::option::Option::Some(#rank)),
+			None => quote!(::ort::__private::core::option::Option::None)
+		};
+		quote! {
+			::ort::session::typed::FieldDescriptor {
+				name: #name,
+				element_type: <#ty as ::ort::session::typed::TypedField>::element_type(),
+				rank: #rank
+			}
+		}
+	});
+	let fields_fn = quote! {
+		fn fields() -> ::ort::__private::alloc::vec::Vec<::ort::session::typed::FieldDescriptor> {
+			::ort::__private::alloc::vec![#(#descriptors),*]
+		}
+	};
+
+	Ok(match kind {
+		Kind::Inputs => {
+			let mut lifetimes = 0;
+			let rebound_args = input.generics.params.iter().map(|param| match param {
+				GenericParam::Lifetime(_) => {
+					lifetimes += 1;
+					quote!('__ort_v)
+				}
+				GenericParam::Type(ty) => {
+					let ident = &ty.ident;
+					quote!(#ident)
+				}
+				GenericParam::Const(c) => {
+					let ident = &c.ident;
+					quote!(#ident)
+				}
+			});
+			let rebound_args: Vec<_> = rebound_args.collect();
+			if lifetimes > 1 {
+				return Err(Error::new_spanned(&input.generics, "`ort::Inputs` can only be derived for structs with at most one lifetime parameter"));
+			}
+
+			// `WithLifetime<'__ort_v>` must outlive `'__ort_v`, so any type parameters (which may not be `'static`) must too
+			let type_params = input.generics.type_params().map(|param| &param.ident);
+			let rebound_where_clause = quote!(where Self: '__ort_v, #(#type_params: '__ort_v),*);
+
+			let values = fields
+				.iter()
+				.map(|Field { ident, .. }| quote!(::ort::__private::core::convert::Into::<::ort::session::SessionInputValue<'__ort_v>>::into(self.#ident)));
+			quote! {
+				impl #impl_generics ::ort::session::typed::ModelInputs for #ident #ty_generics #where_clause {
+					type WithLifetime<'__ort_v> = #ident<#(#rebound_args),*> #rebound_where_clause;
+
+					#fields_fn
+
+					fn into_values<'__ort_v>(self) -> ::ort::__private::alloc::vec::Vec<::ort::session::SessionInputValue<'__ort_v>>
+					where
+						Self: '__ort_v
+					{
+						::ort::__private::alloc::vec![#(#values),*]
+					}
+				}
+			}
+		}
+		Kind::Outputs => {
+			let idents = fields.iter().map(|Field { ident, .. }| ident);
+			let values = fields.iter().map(
+				|Field { ty, .. }| quote!(<#ty as ::ort::session::typed::OutputField>::from_value(values.next().expect("session should return one value per field"))?)
+			);
+			quote! {
+				impl #impl_generics ::ort::session::typed::ModelOutputs for #ident #ty_generics #where_clause {
+					#fields_fn
+
+					fn from_values(values: ::ort::__private::alloc::vec::Vec<::ort::value::DynValue>) -> ::ort::Result<Self> {
+						let mut values = values.into_iter();
+						::ort::__private::core::result::Result::Ok(Self {
+							#(#idents: #values),*
+						})
+					}
+				}
+			}
+		}
+	})
+}
@@ -1,10 +1,16 @@
 use alloc::{
+	boxed::Box,
 	format,
 	string::{String, ToString}
 };
 use core::{convert::Infallible, ffi::c_char, fmt, ptr};
 
-use crate::{char_p_to_string, ortsys, util::with_cstr};
+use crate::{
+	char_p_to_string, ortsys,
+	tensor::{Shape, TensorElementType},
+	util::with_cstr,
+	value::ValueType
+};
 
 /// Type alias for the Result type returned by ORT functions.
 pub type Result<T, E = Error> = core::result::Result<T, E>;
@@ -27,7 +33,8 @@ impl<T> IntoStatus for Result<T, Error> {
 #[derive(Debug)]
 pub struct Error {
 	code: ErrorCode,
-	msg: String
+	msg: String,
+	input_mismatch: Option<Box<InputMismatch>>
 }
 
 impl Error {
@@ -39,7 +46,8 @@ impl Error {
 	pub fn wrap<T: std::error::Error + Send + Sync + 'static>(err: T) -> Self {
 		Error {
 			code: ErrorCode::GenericFailure,
-			msg: err.to_string()
+			msg: err.to_string(),
+			input_mismatch: None
 		}
 	}
 
@@ -51,7 +59,8 @@ impl Error {
 	pub fn wrap<T: core::fmt::Display + Send + Sync + 'static>(err: T) -> Self {
 		Error {
 			code: ErrorCode::GenericFailure,
-			msg: err.to_string()
+			msg: err.to_string(),
+			input_mismatch: None
 		}
 	}
 
@@ -59,13 +68,26 @@ impl Error {
 	pub fn new(msg: impl Into<String>) -> Self {
 		Error {
 			code: ErrorCode::GenericFailure,
-			msg: msg.into()
+			msg: msg.into(),
+			input_mismatch: None
 		}
 	}
 
 	/// Creates a custom [`Error`] with the given [`ErrorCode`] and message.
 	pub fn new_with_code(code: ErrorCode, msg: impl Into<String>) -> Self {
-		Error { code, msg: msg.into() }
+		Error {
+			code,
+			msg: msg.into(),
+			input_mismatch: None
+		}
+	}
+
+	pub(crate) fn from_input_mismatch(mismatch: InputMismatch) -> Self {
+		Error {
+			code: ErrorCode::InputMismatch,
+			msg: mismatch.to_string(),
+			input_mismatch: Some(Box::new(mismatch))
+		}
 	}
 
 	pub fn code(&self) -> ErrorCode {
@@ -75,6 +97,13 @@ impl Error {
 	pub fn message(&self) -> &str {
 		self.msg.as_str()
 	}
+
+	/// If this error was caused by an input failing validation (see
+	/// [`SessionBuilder::with_input_validation`](crate::session::builder::SessionBuilder::with_input_validation)),
+	/// returns the details of the mismatch.
+	pub fn input_mismatch(&self) -> Option<&InputMismatch> {
+		self.input_mismatch.as_deref()
+	}
 }
 
 impl fmt::Display for Error {
@@ -91,7 +120,8 @@ impl From<Box<dyn std::error::Error + Send + Sync + 'static>> for Error {
 	fn from(err: Box<dyn std::error::Error + Send + Sync + 'static>) -> Self {
 		Error {
 			code: ErrorCode::GenericFailure,
-			msg: err.to_string()
+			msg: err.to_string(),
+			input_mismatch: None
 		}
 	}
 }
@@ -146,7 +176,12 @@ pub enum ErrorCode {
 	ModelLoaded,
 	NotImplemented,
 	InvalidGraph,
-	ExecutionProviderFailure
+	ExecutionProviderFailure,
+	/// An input to a session failed validation; see [`Error::input_mismatch`].
+	InputMismatch,
+	/// A session run was terminated because it exceeded its deadline; see
+	/// [`Session::run_with_timeout`](crate::session::Session::run_with_timeout).
+	Timeout
 }
 
 impl From<ort_sys::OrtErrorCode> for ErrorCode {
@@ -184,7 +219,67 @@ impl From<ErrorCode> for ort_sys::OrtErrorCode {
 			ErrorCode::ModelLoaded => ort_sys::OrtErrorCode::ORT_MODEL_LOADED,
 			ErrorCode::NotImplemented => ort_sys::OrtErrorCode::ORT_NOT_IMPLEMENTED,
 			ErrorCode::InvalidGraph => ort_sys::OrtErrorCode::ORT_INVALID_GRAPH,
-			ErrorCode::ExecutionProviderFailure => ort_sys::OrtErrorCode::ORT_EP_FAIL
+			ErrorCode::ExecutionProviderFailure => ort_sys::OrtErrorCode::ORT_EP_FAIL,
+			ErrorCode::InputMismatch => ort_sys::OrtErrorCode::ORT_INVALID_ARGUMENT,
+			ErrorCode::Timeout => ort_sys::OrtErrorCode::ORT_FAIL
+		}
+	}
+}
+
+/// Describes an input to a [`Session`](crate::session::Session) run which failed validation.
+///
+/// Input validation is enabled with
+/// [`SessionBuilder::with_input_validation`](crate::session::builder::SessionBuilder::with_input_validation). The
+/// details of a validation failure can be obtained from an [`Error`] via [`Error::input_mismatch`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InputMismatch {
+	/// The name of the offending input.
+	pub input: String,
+	/// What was wrong with the input.
+	pub kind: InputMismatchKind
+}
+
+/// The kind of an [`InputMismatch`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum InputMismatchKind {
+	/// The model has an input with this name, but no value was provided for it.
+	Missing,
+	/// A value was provided for an input which the model does not have.
+	Unknown,
+	/// The value is a different kind of type than expected, e.g. a sequence was provided for a tensor input.
+	Type { expected: Box<ValueType>, actual: Box<ValueType> },
+	/// The tensor has a different element type than expected.
+	ElementType { expected: TensorElementType, actual: TensorElementType },
+	/// The tensor has a different rank than expected, or one of its fixed dimensions has a different size. Dynamic
+	/// dimensions in `expected` are `-1`.
+	Shape { expected: Shape, actual: Shape },
+	/// A named symbolic dimension (e.g. `batch_size`) has a different size in this input than it did in
+	/// `first_input`.
+	SymbolicDimension {
+		symbol: String,
+		first_input: String,
+		expected: i64,
+		actual: i64
+	}
+}
+
+impl fmt::Display for InputMismatch {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match &self.kind {
+			InputMismatchKind::Missing => write!(f, "Missing input '{}'", self.input),
+			InputMismatchKind::Unknown => write!(f, "Unknown input '{}'; the model has no input with this name", self.input),
+			InputMismatchKind::Type { expected, actual } => write!(f, "Input '{}' should be of type {expected}, got {actual}", self.input),
+			InputMismatchKind::ElementType { expected, actual } => {
+				write!(f, "Input '{}' should be a tensor of {expected}, got a tensor of {actual}", self.input)
+			}
+			InputMismatchKind::Shape { expected, actual } => write!(f, "Input '{}' should have shape {expected}, got {actual}", self.input),
+			InputMismatchKind::SymbolicDimension {
+				symbol,
+				first_input,
+				expected,
+				actual
+			} => write!(f, "Dimension '{symbol}' of input '{}' has size {actual}, but has size {expected} in input '{first_input}'", self.input)
 		}
 	}
 }
@@ -206,13 +301,14 @@ pub unsafe fn status_to_result(status: ort_sys::OrtStatusPtr) -> Result<(), Erro
 		match char_p_to_string(raw) {
 			Ok(msg) => {
 				ortsys![unsafe ReleaseStatus(status)];
-				Err(Error { code, msg })
+				Err(Error { code, msg, input_mismatch: None })
 			}
 			Err(err) => {
 				ortsys![unsafe ReleaseStatus(status)];
 				Err(Error {
 					code,
-					msg: format!("(failed to convert UTF-8: {err})")
+					msg: format!("(failed to convert UTF-8: {err})"),
+					input_mismatch: None
 				})
 			}
 		}
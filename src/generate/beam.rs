@@ -0,0 +1,256 @@
+use alloc::{boxed::Box, format, vec::Vec};
+use core::fmt;
+
+use super::LogitsProcessor;
+use crate::error::{Error, ErrorCode, Result};
+
+/// A candidate sequence in a [`BeamSearch`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Beam {
+	/// The tokens of the sequence, including the prompt.
+	pub tokens: Vec<i64>,
+	/// The sum of the log-probabilities of the generated tokens.
+	pub score: f32
+}
+
+/// Beam search, which keeps track of the `num_beams` most likely sequences at each step rather than committing to a
+/// single token.
+///
+/// Beam search is driven step by step: at each step, run the model on every sequence in [`BeamSearch::beams`] (e.g. as
+/// one batch) and pass the logits of each beam's next token to [`BeamSearch::step`].
+///
+/// ```
+/// # use ort::generate::BeamSearch;
+/// # fn main() -> ort::Result<()> {
+/// # fn model(tokens: &[i64]) -> Vec<f32> { if tokens.len() < 3 { vec![0.0, 1.0, 0.5] } else { vec![2.0, 0.0, 0.0] } }
+/// let mut search = BeamSearch::new(2, [1]).with_eos_tokens([0]).with_max_length(8);
+/// while !search.is_done() {
+/// 	let logits: Vec<Vec<f32>> = search.beams().iter().map(|beam| model(&beam.tokens)).collect();
+/// 	search.step(&logits)?;
+/// 	// When using a KV cache, the caches must be reordered according to `search.parents()` at this point.
+/// }
+/// let best = &search.finish()[0];
+/// assert_eq!(best.tokens.last(), Some(&0));
+/// # 	Ok(())
+/// # }
+/// ```
+pub struct BeamSearch {
+	num_beams: usize,
+	length_penalty: f32,
+	eos_tokens: Vec<i64>,
+	max_length: Option<usize>,
+	processors: Vec<Box<dyn LogitsProcessor>>,
+	beams: Vec<Beam>,
+	parents: Vec<usize>,
+	finished: Vec<Beam>,
+	done: bool,
+	scratch: Vec<f32>
+}
+
+impl BeamSearch {
+	/// Creates a beam search which keeps `num_beams` beams, starting from a single beam containing `prompt`.
+	///
+	/// # Panics
+	/// Panics if `num_beams` is 0.
+	pub fn new(num_beams: usize, prompt: impl Into<Vec<i64>>) -> Self {
+		assert!(num_beams > 0, "beam search requires at least 1 beam");
+		Self {
+			num_beams,
+			length_penalty: 1.0,
+			eos_tokens: Vec::new(),
+			max_length: None,
+			processors: Vec::new(),
+			beams: alloc::vec![Beam { tokens: prompt.into(), score: 0.0 }],
+			parents: alloc::vec![0],
+			finished: Vec::new(),
+			done: false,
+			scratch: Vec::new()
+		}
+	}
+
+	/// Sets the exponent applied to the sequence length when comparing finished sequences. Values above `0.0` favor
+	/// longer sequences, and values below `0.0` favor shorter ones. Defaults to `1.0`.
+	pub fn with_length_penalty(mut self, length_penalty: f32) -> Self {
+		self.length_penalty = length_penalty;
+		self
+	}
+
+	/// Sets the tokens which end a sequence.
+	pub fn with_eos_tokens(mut self, eos_tokens: impl IntoIterator<Item = i64>) -> Self {
+		self.eos_tokens = eos_tokens.into_iter().collect();
+		self
+	}
+
+	/// Stops the search once sequences reach `max_length` tokens (including the prompt).
+	pub fn with_max_length(mut self, max_length: usize) -> Self {
+		self.max_length = Some(max_length);
+		self
+	}
+
+	/// Adds a [`LogitsProcessor`] which is applied to each beam's logits before they are scored.
+	pub fn with_processor(mut self, processor: impl LogitsProcessor + 'static) -> Self {
+		self.processors.push(Box::new(processor));
+		self
+	}
+
+	/// Returns the beams which should be run through the model for the next step.
+	///
+	/// Before the first step, this is a single beam containing the prompt.
+	pub fn beams(&self) -> &[Beam] {
+		&self.beams
+	}
+
+	/// For each beam in [`BeamSearch::beams`], returns the index of the beam it extends from the previous step. This
+	/// can be used to reorder per-beam state, like a KV cache, after each step.
+	pub fn parents(&self) -> &[usize] {
+		&self.parents
+	}
+
+	/// Returns `true` once the search has finished; see [`BeamSearch::finish`].
+	pub fn is_done(&self) -> bool {
+		self.done
+	}
+
+	/// Advances the search given the logits of the next token for each beam in [`BeamSearch::beams`].
+	pub fn step<L: AsRef<[f32]>>(&mut self, logits: &[L]) -> Result<()> {
+		if self.done {
+			return Err(Error::new_with_code(ErrorCode::InvalidArgument, "Beam search has already finished"));
+		}
+		if logits.len() != self.beams.len() {
+			return Err(Error::new_with_code(ErrorCode::InvalidArgument, format!("Expected logits for {} beams, got {}", self.beams.len(), logits.len())));
+		}
+
+		// (score, beam, token)
+		let mut candidates: Vec<(f32, usize, usize)> = Vec::new();
+		let n_candidates = 2 * self.num_beams;
+		for (b, (beam, logits)) in self.beams.iter().zip(logits.iter()).enumerate() {
+			self.scratch.clear();
+			self.scratch.extend_from_slice(logits.as_ref());
+			for processor in &mut self.processors {
+				processor.process(&beam.tokens, &mut self.scratch);
+			}
+			super::log_softmax(&mut self.scratch);
+			candidates.extend(
+				self.scratch
+					.iter()
+					.enumerate()
+					.filter(|(_, lp)| lp.is_finite())
+					.map(|(t, lp)| (beam.score + lp, b, t))
+			);
+			// keep the candidate list small
+			if candidates.len() > n_candidates {
+				candidates.select_nth_unstable_by(n_candidates - 1, |a, b| b.0.total_cmp(&a.0));
+				candidates.truncate(n_candidates);
+			}
+		}
+		candidates.sort_unstable_by(|a, b| b.0.total_cmp(&a.0));
+
+		let mut beams = Vec::with_capacity(self.num_beams);
+		let mut parents = Vec::with_capacity(self.num_beams);
+		for (rank, &(score, b, token)) in candidates.iter().enumerate() {
+			let token = token as i64;
+			let mut tokens = Vec::with_capacity(self.beams[b].tokens.len() + 1);
+			tokens.extend_from_slice(&self.beams[b].tokens);
+			tokens.push(token);
+			if self.eos_tokens.contains(&token) {
+				// only accept finished sequences which would have made the cut as beams
+				if rank < self.num_beams {
+					self.finished.push(Beam { tokens, score });
+				}
+			} else {
+				beams.push(Beam { tokens, score });
+				parents.push(b);
+				if beams.len() == self.num_beams {
+					break;
+				}
+			}
+		}
+		self.beams = beams;
+		self.parents = parents;
+
+		let reached_max_length = self
+			.max_length
+			.is_some_and(|max| self.beams.first().is_some_and(|beam| beam.tokens.len() >= max));
+		if self.beams.is_empty() || self.finished.len() >= self.num_beams || reached_max_length {
+			self.done = true;
+		}
+		Ok(())
+	}
+
+	fn normalized_score(&self, beam: &Beam) -> f32 {
+		beam.score / (beam.tokens.len() as f32).powf(self.length_penalty)
+	}
+
+	/// Returns the best `num_beams` sequences found, sorted from best to worst.
+	///
+	/// If fewer than `num_beams` sequences have finished (i.e. ended with an end-of-sequence token), the best
+	/// unfinished beams are included too.
+	pub fn finish(mut self) -> Vec<Beam> {
+		let mut results = core::mem::take(&mut self.finished);
+		if results.len() < self.num_beams {
+			results.append(&mut self.beams);
+		}
+		results.sort_by(|a, b| self.normalized_score(b).total_cmp(&self.normalized_score(a)));
+		results.truncate(self.num_beams);
+		results
+	}
+}
+
+impl fmt::Debug for BeamSearch {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("BeamSearch")
+			.field("num_beams", &self.num_beams)
+			.field("length_penalty", &self.length_penalty)
+			.field("eos_tokens", &self.eos_tokens)
+			.field("max_length", &self.max_length)
+			.field("beams", &self.beams)
+			.field("finished", &self.finished)
+			.field("done", &self.done)
+			.finish_non_exhaustive()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use alloc::{vec, vec::Vec};
+
+	use super::BeamSearch;
+
+	#[test]
+	fn test_beam_search_finds_better_sequence() -> crate::Result<()> {
+		// Greedy decoding picks token 1 first (p=0.6), but the sequence starting with token 2 (p=0.4) is followed by a
+		// near-certain token, making it more likely overall.
+		fn model(tokens: &[i64]) -> Vec<f32> {
+			let p: [f32; 4] = match tokens {
+				[0] => [0.0, 0.6, 0.4, 0.0],
+				[0, 1] => [0.25; 4],
+				[0, 2] => [0.0, 0.0, 0.0, 1.0],
+				_ => [1.0, 0.0, 0.0, 0.0]
+			};
+			p.iter().map(|p| p.ln()).collect()
+		}
+
+		let mut search = BeamSearch::new(2, [0]).with_eos_tokens([3]).with_max_length(4);
+		while !search.is_done() {
+			let logits: Vec<Vec<f32>> = search.beams().iter().map(|beam| model(&beam.tokens)).collect();
+			search.step(&logits)?;
+		}
+		let results = search.finish();
+		assert_eq!(results[0].tokens, vec![0, 2, 3]);
+		Ok(())
+	}
+
+	#[test]
+	fn test_parents() -> crate::Result<()> {
+		let mut search = BeamSearch::new(2, [0]);
+		search.step(&[[0.0, 1.0, 2.0]])?;
+		assert_eq!(search.parents(), &[0, 0]);
+		assert_eq!(search.beams()[0].tokens, vec![0, 2]);
+
+		// beam 1 (ending in token 1) is almost certainly followed by token 0, overtaking beam 0
+		search.step(&[[0.0, 0.0, 0.0], [9.0, 0.0, 0.0]])?;
+		assert_eq!(search.parents(), &[1, 0]);
+		assert_eq!(search.beams()[0].tokens, vec![0, 1, 0]);
+		Ok(())
+	}
+}
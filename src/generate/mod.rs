@@ -0,0 +1,144 @@
+//! Utilities for generating sequences from language models: sampling strategies, logits processors, stopping
+//! criteria, and beam search.
+//!
+//! These operate on plain logits slices, which can be obtained from a model's output with [`last_token_logits`]. A
+//! [`Sampler`] combines a chain of [`LogitsProcessor`]s with a sampling strategy to pick the next token:
+//!
+//! ```no_run
+//! # use ort::{generate::{last_token_logits, MaxLength, Sampler, StopTokens, StoppingCriteria}, session::Session, value::TensorRef};
+//! # fn main() -> ort::Result<()> {
+//! # let session = Session::builder()?.commit_from_file("gpt2.onnx")?;
+//! # let eos_token = 50256;
+//! let mut tokens: Vec<i64> = vec![464, 1893, 318];
+//! let mut sampler = Sampler::random()
+//! 	.with_seed(42)
+//! 	.with_repetition_penalty(1.2)
+//! 	.with_temperature(0.8)
+//! 	.with_top_k(50)
+//! 	.with_top_p(0.95);
+//! let stop = (StopTokens::new([eos_token]), MaxLength(128));
+//!
+//! while !stop.should_stop(&tokens) {
+//! 	let input = TensorRef::from_array_view(([1, tokens.len()], tokens.as_slice()))?;
+//! 	let outputs = session.run(ort::inputs![input])?;
+//! 	let logits = last_token_logits(&outputs[0], 0)?;
+//! 	tokens.push(sampler.sample(&tokens, logits));
+//! }
+//! # 	Ok(())
+//! # }
+//! ```
+//!
+//! For beam search, see [`BeamSearch`].
+
+mod beam;
+mod processors;
+mod rng;
+mod sampler;
+mod stopping;
+
+use alloc::format;
+
+pub use self::{
+	beam::{Beam, BeamSearch},
+	processors::{LogitsProcessor, MinLength, PresencePenalty, RepetitionPenalty, Temperature, TopK, TopP},
+	rng::Rng,
+	sampler::Sampler,
+	stopping::{MaxLength, StopSequences, StopTokens, StoppingCriteria}
+};
+use crate::{
+	error::{Error, ErrorCode, Result},
+	value::DynValue
+};
+
+/// Returns the logits for the last position in the sequence of batch item `batch_index`.
+///
+/// `logits` should be an `f32` tensor whose last dimension is the vocabulary. If the tensor has 3 or more dimensions,
+/// the second-to-last dimension is treated as the sequence, and all leading dimensions as the batch; i.e. both
+/// `[batch, sequence, vocab]` and `[batch, 1, sequence, vocab]` are supported. A 2-dimensional tensor is treated as
+/// `[batch, vocab]`.
+pub fn last_token_logits(logits: &DynValue, batch_index: usize) -> Result<&[f32]> {
+	let (shape, data) = logits.try_extract_tensor::<f32>()?;
+	let (vocab_size, sequence_length) = match **shape {
+		[.., sequence_length, vocab_size] if shape.len() >= 3 => (vocab_size as usize, sequence_length as usize),
+		[_, vocab_size] => (vocab_size as usize, 1),
+		_ => {
+			return Err(Error::new_with_code(ErrorCode::InvalidArgument, format!("Expected logits to have at least 2 dimensions, got shape {shape}")));
+		}
+	};
+
+	let batch_stride = vocab_size * sequence_length;
+	let batch_size = data.len().checked_div(batch_stride).unwrap_or(0);
+	if batch_index >= batch_size {
+		return Err(Error::new_with_code(ErrorCode::InvalidArgument, format!("Batch index {batch_index} is out of bounds for logits of shape {shape}")));
+	}
+	if sequence_length == 0 {
+		return Err(Error::new_with_code(ErrorCode::InvalidArgument, "Logits have an empty sequence dimension"));
+	}
+
+	let start = batch_index * batch_stride + (sequence_length - 1) * vocab_size;
+	Ok(&data[start..start + vocab_size])
+}
+
+/// Returns the index of the largest logit, ignoring `NaN`s.
+pub fn argmax(logits: &[f32]) -> Option<usize> {
+	logits
+		.iter()
+		.enumerate()
+		.filter(|(_, x)| !x.is_nan())
+		.max_by(|(_, a), (_, b)| a.total_cmp(b))
+		.map(|(i, _)| i)
+}
+
+/// Computes the log of the softmax of `logits` in place.
+pub fn log_softmax(logits: &mut [f32]) {
+	let max = logits.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+	if max == f32::NEG_INFINITY {
+		return;
+	}
+	let log_sum = logits.iter().map(|x| (x - max).exp()).sum::<f32>().ln();
+	for x in logits.iter_mut() {
+		*x = *x - max - log_sum;
+	}
+}
+
+/// Computes the softmax of `logits` in place.
+pub fn softmax(logits: &mut [f32]) {
+	let max = logits.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+	if max == f32::NEG_INFINITY {
+		return;
+	}
+	let mut sum = 0.0;
+	for x in logits.iter_mut() {
+		*x = (*x - max).exp();
+		sum += *x;
+	}
+	for x in logits.iter_mut() {
+		*x /= sum;
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{argmax, log_softmax, softmax};
+
+	#[test]
+	fn test_softmax() {
+		let mut x = [1.0, 2.0, 3.0, f32::NEG_INFINITY];
+		softmax(&mut x);
+		assert!((x.iter().sum::<f32>() - 1.0).abs() < 1e-6);
+		assert_eq!(x[3], 0.0);
+		assert!(x[2] > x[1] && x[1] > x[0]);
+
+		let mut y = [1.0, 2.0, 3.0, f32::NEG_INFINITY];
+		log_softmax(&mut y);
+		for (a, b) in x.iter().zip(y.iter()) {
+			assert!((a.ln() - b).abs() < 1e-5 || (*a == 0.0 && *b == f32::NEG_INFINITY));
+		}
+	}
+
+	#[test]
+	fn test_argmax() {
+		assert_eq!(argmax(&[0.5, f32::NAN, 2.0, -1.0]), Some(2));
+		assert_eq!(argmax(&[]), None);
+	}
+}
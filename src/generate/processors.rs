@@ -0,0 +1,243 @@
+use alloc::vec::Vec;
+
+/// Modifies the logits of the next token before it is chosen.
+///
+/// `tokens` contains every token in the sequence so far, including the prompt. Processors can exclude a token from
+/// being chosen by setting its logit to [`f32::NEG_INFINITY`].
+///
+/// Processors can be chained in a [`Sampler`](super::Sampler) or [`BeamSearch`](super::BeamSearch). Closures of the
+/// form `FnMut(&[i64], &mut [f32])` also implement this trait:
+///
+/// ```
+/// # use ort::generate::Sampler;
+/// // never generate token 0
+/// let mut sampler = Sampler::greedy().with_processor(|_: &[i64], logits: &mut [f32]| logits[0] = f32::NEG_INFINITY);
+/// assert_eq!(sampler.sample(&[], &[5.0, 1.0, 2.0]), 2);
+/// ```
+pub trait LogitsProcessor {
+	fn process(&mut self, tokens: &[i64], logits: &mut [f32]);
+}
+
+impl<F: FnMut(&[i64], &mut [f32])> LogitsProcessor for F {
+	fn process(&mut self, tokens: &[i64], logits: &mut [f32]) {
+		self(tokens, logits)
+	}
+}
+
+/// Divides logits by a temperature. Temperatures below `1.0` make sampling more deterministic; temperatures above
+/// `1.0` make it more random.
+#[derive(Debug, Clone, Copy)]
+pub struct Temperature(f32);
+
+impl Temperature {
+	/// # Panics
+	/// Panics if `temperature` is not positive.
+	pub fn new(temperature: f32) -> Self {
+		assert!(temperature > 0.0, "temperature must be positive");
+		Self(temperature)
+	}
+}
+
+impl LogitsProcessor for Temperature {
+	fn process(&mut self, _: &[i64], logits: &mut [f32]) {
+		for x in logits {
+			*x /= self.0;
+		}
+	}
+}
+
+/// Keeps only the `k` most likely tokens.
+#[derive(Debug, Clone)]
+pub struct TopK {
+	k: usize,
+	indices: Vec<usize>
+}
+
+impl TopK {
+	/// # Panics
+	/// Panics if `k` is 0.
+	pub fn new(k: usize) -> Self {
+		assert!(k > 0, "top-k must keep at least 1 token");
+		Self { k, indices: Vec::new() }
+	}
+}
+
+impl LogitsProcessor for TopK {
+	fn process(&mut self, _: &[i64], logits: &mut [f32]) {
+		if self.k >= logits.len() {
+			return;
+		}
+		self.indices.clear();
+		self.indices.extend(0..logits.len());
+		self.indices.select_nth_unstable_by(self.k - 1, |&a, &b| logits[b].total_cmp(&logits[a]));
+		for &i in &self.indices[self.k..] {
+			logits[i] = f32::NEG_INFINITY;
+		}
+	}
+}
+
+/// Nucleus sampling: keeps the smallest set of most likely tokens whose cumulative probability is at least `p`.
+#[derive(Debug, Clone)]
+pub struct TopP {
+	p: f32,
+	indices: Vec<usize>,
+	probabilities: Vec<f32>
+}
+
+impl TopP {
+	/// # Panics
+	/// Panics if `p` is not in `(0, 1]`.
+	pub fn new(p: f32) -> Self {
+		assert!(p > 0.0 && p <= 1.0, "top-p must be in (0, 1]");
+		Self {
+			p,
+			indices: Vec::new(),
+			probabilities: Vec::new()
+		}
+	}
+}
+
+impl LogitsProcessor for TopP {
+	fn process(&mut self, _: &[i64], logits: &mut [f32]) {
+		if self.p >= 1.0 {
+			return;
+		}
+
+		self.probabilities.clear();
+		self.probabilities.extend_from_slice(logits);
+		super::softmax(&mut self.probabilities);
+
+		self.indices.clear();
+		self.indices.extend(0..logits.len());
+		let probabilities = &self.probabilities;
+		self.indices.sort_unstable_by(|&a, &b| probabilities[b].total_cmp(&probabilities[a]));
+
+		let mut cumulative = 0.0;
+		let mut keep = self.indices.len();
+		for (n, &i) in self.indices.iter().enumerate() {
+			cumulative += probabilities[i];
+			if cumulative >= self.p {
+				keep = n + 1;
+				break;
+			}
+		}
+		for &i in &self.indices[keep..] {
+			logits[i] = f32::NEG_INFINITY;
+		}
+	}
+}
+
+/// Penalizes tokens which already appear in the sequence, as described in
+/// [CTRL](https://arxiv.org/abs/1909.05858): positive logits are divided by the penalty, and negative logits are
+/// multiplied by it. A penalty of `1.0` has no effect.
+#[derive(Debug, Clone, Copy)]
+pub struct RepetitionPenalty(pub f32);
+
+impl LogitsProcessor for RepetitionPenalty {
+	fn process(&mut self, tokens: &[i64], logits: &mut [f32]) {
+		for_each_unique(tokens, logits.len(), |token| {
+			let x = &mut logits[token];
+			*x = if *x > 0.0 { *x / self.0 } else { *x * self.0 };
+		});
+	}
+}
+
+/// Subtracts a fixed penalty from the logits of tokens which already appear in the sequence.
+#[derive(Debug, Clone, Copy)]
+pub struct PresencePenalty(pub f32);
+
+impl LogitsProcessor for PresencePenalty {
+	fn process(&mut self, tokens: &[i64], logits: &mut [f32]) {
+		for_each_unique(tokens, logits.len(), |token| logits[token] -= self.0);
+	}
+}
+
+/// Calls `f` once for each distinct in-vocabulary token in `tokens`.
+fn for_each_unique(tokens: &[i64], vocab_size: usize, mut f: impl FnMut(usize)) {
+	let mut seen = Vec::with_capacity(tokens.len());
+	for &token in tokens {
+		if token < 0 || token as usize >= vocab_size || seen.contains(&token) {
+			continue;
+		}
+		seen.push(token);
+		f(token as usize);
+	}
+}
+
+/// Prevents end-of-sequence tokens from being generated until the sequence (including the prompt) has at least
+/// `min_length` tokens.
+#[derive(Debug, Clone)]
+pub struct MinLength {
+	min_length: usize,
+	eos_tokens: Vec<i64>
+}
+
+impl MinLength {
+	pub fn new(min_length: usize, eos_tokens: impl IntoIterator<Item = i64>) -> Self {
+		Self {
+			min_length,
+			eos_tokens: eos_tokens.into_iter().collect()
+		}
+	}
+}
+
+impl LogitsProcessor for MinLength {
+	fn process(&mut self, tokens: &[i64], logits: &mut [f32]) {
+		if tokens.len() >= self.min_length {
+			return;
+		}
+		for &token in &self.eos_tokens {
+			if let Some(x) = usize::try_from(token).ok().and_then(|t| logits.get_mut(t)) {
+				*x = f32::NEG_INFINITY;
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_top_k() {
+		let mut logits = [1.0, 4.0, 3.0, 2.0, 5.0];
+		TopK::new(2).process(&[], &mut logits);
+		assert_eq!(logits, [f32::NEG_INFINITY, 4.0, f32::NEG_INFINITY, f32::NEG_INFINITY, 5.0]);
+	}
+
+	#[test]
+	fn test_top_p() {
+		// probabilities of roughly [0.64, 0.24, 0.09, 0.03]
+		let mut logits = [3.0, 2.0, 1.0, 0.0];
+		TopP::new(0.8).process(&[], &mut logits);
+		assert_eq!(logits, [3.0, 2.0, f32::NEG_INFINITY, f32::NEG_INFINITY]);
+
+		// always keeps the most likely token
+		let mut logits = [3.0, 2.0, 1.0, 0.0];
+		TopP::new(0.01).process(&[], &mut logits);
+		assert_eq!(logits, [3.0, f32::NEG_INFINITY, f32::NEG_INFINITY, f32::NEG_INFINITY]);
+	}
+
+	#[test]
+	fn test_penalties() {
+		let mut logits = [2.0, -2.0, 2.0];
+		RepetitionPenalty(2.0).process(&[0, 1, 1, 7], &mut logits);
+		assert_eq!(logits, [1.0, -4.0, 2.0]);
+
+		let mut logits = [2.0, -2.0, 2.0];
+		PresencePenalty(0.5).process(&[0, 1, 1], &mut logits);
+		assert_eq!(logits, [1.5, -2.5, 2.0]);
+	}
+
+	#[test]
+	fn test_min_length() {
+		let mut processor = MinLength::new(3, [2]);
+		let mut logits = [0.0, 0.0, 1.0];
+		processor.process(&[5, 5], &mut logits);
+		assert_eq!(logits[2], f32::NEG_INFINITY);
+
+		let mut logits = [0.0, 0.0, 1.0];
+		processor.process(&[5, 5, 5], &mut logits);
+		assert_eq!(logits[2], 1.0);
+	}
+}
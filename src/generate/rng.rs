@@ -0,0 +1,72 @@
+use std::{
+	collections::hash_map::RandomState,
+	hash::{BuildHasher, Hasher}
+};
+
+/// A small, fast, seedable pseudo-random number generator (SplitMix64) used for sampling.
+///
+/// This is **not** cryptographically secure. Its only purpose is to make sampling reproducible when seeded with
+/// [`Rng::seed_from_u64`].
+#[derive(Debug, Clone)]
+pub struct Rng {
+	state: u64
+}
+
+impl Rng {
+	/// Creates a generator with a fixed seed. Generators created with the same seed produce the same sequence.
+	pub fn seed_from_u64(seed: u64) -> Self {
+		Self { state: seed }
+	}
+
+	/// Creates a generator seeded from the process's source of randomness.
+	pub fn from_entropy() -> Self {
+		let mut hasher = RandomState::new().build_hasher();
+		hasher.write_u64(0);
+		Self::seed_from_u64(hasher.finish())
+	}
+
+	/// Returns the next random `u64`.
+	pub fn next_u64(&mut self) -> u64 {
+		self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+		let mut z = self.state;
+		z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+		z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+		z ^ (z >> 31)
+	}
+
+	/// Returns a random `f32` uniformly distributed in `[0, 1)`.
+	pub fn next_f32(&mut self) -> f32 {
+		// use the top 24 bits so every value is exactly representable
+		(self.next_u64() >> 40) as f32 / (1u64 << 24) as f32
+	}
+}
+
+impl Default for Rng {
+	fn default() -> Self {
+		Self::from_entropy()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::Rng;
+
+	#[test]
+	fn test_seeded() {
+		let a: Vec<u64> = {
+			let mut rng = Rng::seed_from_u64(7);
+			(0..8).map(|_| rng.next_u64()).collect()
+		};
+		let b: Vec<u64> = {
+			let mut rng = Rng::seed_from_u64(7);
+			(0..8).map(|_| rng.next_u64()).collect()
+		};
+		assert_eq!(a, b);
+
+		let mut rng = Rng::seed_from_u64(7);
+		for _ in 0..1000 {
+			let x = rng.next_f32();
+			assert!((0.0..1.0).contains(&x));
+		}
+	}
+}
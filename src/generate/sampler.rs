@@ -0,0 +1,177 @@
+use alloc::{boxed::Box, vec::Vec};
+use core::fmt;
+
+use super::{LogitsProcessor, MinLength, PresencePenalty, RepetitionPenalty, Rng, Temperature, TopK, TopP};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Strategy {
+	Greedy,
+	Random
+}
+
+/// Chooses the next token from a model's logits.
+///
+/// A sampler applies its [`LogitsProcessor`]s in the order they were added, then either picks the most likely token
+/// ([`Sampler::greedy`]) or samples from the resulting distribution ([`Sampler::random`]).
+///
+/// ```
+/// # use ort::generate::Sampler;
+/// let logits = [0.1, 2.5, 0.3, 2.4];
+///
+/// let mut greedy = Sampler::greedy();
+/// assert_eq!(greedy.sample(&[], &logits), 1);
+///
+/// // Samplers with the same seed make the same choices.
+/// let mut a = Sampler::random().with_seed(1234).with_top_k(2);
+/// let mut b = Sampler::random().with_seed(1234).with_top_k(2);
+/// for _ in 0..16 {
+/// 	let token = a.sample(&[], &logits);
+/// 	assert!(token == 1 || token == 3);
+/// 	assert_eq!(token, b.sample(&[], &logits));
+/// }
+/// ```
+pub struct Sampler {
+	strategy: Strategy,
+	processors: Vec<Box<dyn LogitsProcessor>>,
+	rng: Rng,
+	scratch: Vec<f32>
+}
+
+impl Sampler {
+	/// Creates a sampler which always picks the most likely token.
+	pub fn greedy() -> Self {
+		Self::new(Strategy::Greedy)
+	}
+
+	/// Creates a sampler which samples from the distribution defined by the (processed) logits. The sampler is seeded
+	/// randomly; use [`Sampler::with_seed`] for reproducible results.
+	pub fn random() -> Self {
+		Self::new(Strategy::Random)
+	}
+
+	fn new(strategy: Strategy) -> Self {
+		Self {
+			strategy,
+			processors: Vec::new(),
+			rng: Rng::from_entropy(),
+			scratch: Vec::new()
+		}
+	}
+
+	/// Seeds the sampler's random number generator.
+	pub fn with_seed(mut self, seed: u64) -> Self {
+		self.rng = Rng::seed_from_u64(seed);
+		self
+	}
+
+	/// Adds a custom [`LogitsProcessor`].
+	pub fn with_processor(mut self, processor: impl LogitsProcessor + 'static) -> Self {
+		self.processors.push(Box::new(processor));
+		self
+	}
+
+	/// Adds a [`Temperature`] processor.
+	pub fn with_temperature(self, temperature: f32) -> Self {
+		self.with_processor(Temperature::new(temperature))
+	}
+
+	/// Adds a [`TopK`] processor.
+	pub fn with_top_k(self, k: usize) -> Self {
+		self.with_processor(TopK::new(k))
+	}
+
+	/// Adds a [`TopP`] processor.
+	pub fn with_top_p(self, p: f32) -> Self {
+		self.with_processor(TopP::new(p))
+	}
+
+	/// Adds a [`RepetitionPenalty`] processor.
+	pub fn with_repetition_penalty(self, penalty: f32) -> Self {
+		self.with_processor(RepetitionPenalty(penalty))
+	}
+
+	/// Adds a [`PresencePenalty`] processor.
+	pub fn with_presence_penalty(self, penalty: f32) -> Self {
+		self.with_processor(PresencePenalty(penalty))
+	}
+
+	/// Adds a [`MinLength`] processor.
+	pub fn with_min_length(self, min_length: usize, eos_tokens: impl IntoIterator<Item = i64>) -> Self {
+		self.with_processor(MinLength::new(min_length, eos_tokens))
+	}
+
+	/// Applies this sampler's processors to `logits` in place.
+	pub fn process(&mut self, tokens: &[i64], logits: &mut [f32]) {
+		for processor in &mut self.processors {
+			processor.process(tokens, logits);
+		}
+	}
+
+	/// Chooses the next token given the sequence so far (`tokens`) and the logits of the next token.
+	///
+	/// # Panics
+	/// Panics if `logits` is empty.
+	pub fn sample(&mut self, tokens: &[i64], logits: &[f32]) -> i64 {
+		assert!(!logits.is_empty(), "cannot sample from empty logits");
+
+		let mut scratch = core::mem::take(&mut self.scratch);
+		scratch.clear();
+		scratch.extend_from_slice(logits);
+		self.process(tokens, &mut scratch);
+
+		let token = match self.strategy {
+			Strategy::Greedy => super::argmax(&scratch).unwrap_or(0),
+			Strategy::Random => {
+				super::softmax(&mut scratch);
+				let mut threshold = self.rng.next_f32();
+				let mut token = None;
+				for (i, &p) in scratch.iter().enumerate() {
+					if p > 0.0 {
+						token = Some(i);
+						if threshold < p {
+							break;
+						}
+						threshold -= p;
+					}
+				}
+				// due to rounding, `threshold` can exceed the total probability; fall back to the last possible token
+				token.unwrap_or(0)
+			}
+		};
+		self.scratch = scratch;
+		token as i64
+	}
+}
+
+impl fmt::Debug for Sampler {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("Sampler")
+			.field("strategy", &self.strategy)
+			.field("processors", &self.processors.len())
+			.field("rng", &self.rng)
+			.finish_non_exhaustive()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::Sampler;
+
+	#[test]
+	fn test_distribution() {
+		// with a temperature of 1, samples should roughly follow the softmax of the logits
+		let logits = [0.0, (3.0f32).ln()];
+		let mut sampler = Sampler::random().with_seed(0);
+		let ones = (0..4000).filter(|_| sampler.sample(&[], &logits) == 1).count();
+		assert!((2800..3200).contains(&ones), "{ones}");
+	}
+
+	#[test]
+	fn test_masked() {
+		let logits = [f32::NEG_INFINITY, 1.0, f32::NEG_INFINITY];
+		let mut sampler = Sampler::random().with_seed(0).with_temperature(2.0);
+		for _ in 0..100 {
+			assert_eq!(sampler.sample(&[], &logits), 1);
+		}
+	}
+}
@@ -0,0 +1,116 @@
+use alloc::{boxed::Box, vec::Vec};
+
+/// Determines when generation should stop.
+///
+/// Criteria can be combined: slices, `Vec`s, and tuples of criteria stop when *any* of their criteria would stop.
+///
+/// ```
+/// # use ort::generate::{MaxLength, StopTokens, StoppingCriteria};
+/// let stop = (StopTokens::new([2]), MaxLength(4));
+/// assert!(!stop.should_stop(&[5, 6]));
+/// assert!(stop.should_stop(&[5, 2]));
+/// assert!(stop.should_stop(&[5, 6, 7, 8]));
+/// ```
+pub trait StoppingCriteria {
+	/// Returns `true` if generation should stop given the sequence so far (including the prompt).
+	fn should_stop(&self, tokens: &[i64]) -> bool;
+}
+
+/// Stops when the last token is one of the given (e.g. end-of-sequence) tokens.
+#[derive(Debug, Clone)]
+pub struct StopTokens(Vec<i64>);
+
+impl StopTokens {
+	pub fn new(tokens: impl IntoIterator<Item = i64>) -> Self {
+		Self(tokens.into_iter().collect())
+	}
+}
+
+impl StoppingCriteria for StopTokens {
+	fn should_stop(&self, tokens: &[i64]) -> bool {
+		tokens.last().is_some_and(|t| self.0.contains(t))
+	}
+}
+
+/// Stops when the sequence ends with any of the given token sequences.
+#[derive(Debug, Clone)]
+pub struct StopSequences(Vec<Vec<i64>>);
+
+impl StopSequences {
+	pub fn new<S: Into<Vec<i64>>>(sequences: impl IntoIterator<Item = S>) -> Self {
+		Self(sequences.into_iter().map(Into::into).filter(|s: &Vec<i64>| !s.is_empty()).collect())
+	}
+}
+
+impl StoppingCriteria for StopSequences {
+	fn should_stop(&self, tokens: &[i64]) -> bool {
+		self.0.iter().any(|s| tokens.ends_with(s))
+	}
+}
+
+/// Stops when the sequence (including the prompt) reaches the given number of tokens.
+#[derive(Debug, Clone, Copy)]
+pub struct MaxLength(pub usize);
+
+impl StoppingCriteria for MaxLength {
+	fn should_stop(&self, tokens: &[i64]) -> bool {
+		tokens.len() >= self.0
+	}
+}
+
+impl<C: StoppingCriteria + ?Sized> StoppingCriteria for Box<C> {
+	fn should_stop(&self, tokens: &[i64]) -> bool {
+		(**self).should_stop(tokens)
+	}
+}
+
+impl<C: StoppingCriteria> StoppingCriteria for [C] {
+	fn should_stop(&self, tokens: &[i64]) -> bool {
+		self.iter().any(|c| c.should_stop(tokens))
+	}
+}
+
+impl<C: StoppingCriteria> StoppingCriteria for Vec<C> {
+	fn should_stop(&self, tokens: &[i64]) -> bool {
+		self.as_slice().should_stop(tokens)
+	}
+}
+
+macro_rules! impl_tuple {
+	($($t:ident),+) => {
+		impl<$($t: StoppingCriteria),+> StoppingCriteria for ($($t,)+) {
+			#[allow(non_snake_case)]
+			fn should_stop(&self, tokens: &[i64]) -> bool {
+				let ($($t,)+) = self;
+				$($t.should_stop(tokens))||+
+			}
+		}
+	};
+}
+
+impl_tuple!(A, B);
+impl_tuple!(A, B, C);
+impl_tuple!(A, B, C, D);
+
+#[cfg(test)]
+mod tests {
+	use alloc::{boxed::Box, vec, vec::Vec};
+
+	use super::*;
+
+	#[test]
+	fn test_stop_sequences() {
+		let stop = StopSequences::new([vec![1, 2], vec![3]]);
+		assert!(stop.should_stop(&[0, 1, 2]));
+		assert!(stop.should_stop(&[3]));
+		assert!(!stop.should_stop(&[2, 1]));
+	}
+
+	#[test]
+	fn test_dyn_list() {
+		let stop: Vec<Box<dyn StoppingCriteria>> = vec![Box::new(MaxLength(3)), Box::new(StopTokens::new([9]))];
+		assert!(stop.should_stop(&[1, 9]));
+		assert!(stop.should_stop(&[1, 2, 3]));
+		assert!(!stop.should_stop(&[1, 2]));
+	}
+}
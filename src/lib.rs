@@ -28,6 +28,9 @@ pub mod adapter;
 pub mod environment;
 pub mod error;
 pub mod execution_providers;
+#[cfg(feature = "generate")]
+#[cfg_attr(docsrs, doc(cfg(feature = "generate")))]
+pub mod generate;
 pub mod io_binding;
 pub(crate) mod logging;
 pub mod memory;
@@ -51,6 +54,9 @@ use core::{
 	slice, str
 };
 
+#[cfg(feature = "derive")]
+#[cfg_attr(docsrs, doc(cfg(feature = "derive")))]
+pub use ort_derive::{Inputs, Outputs};
 pub use ort_sys as sys;
 
 #[cfg(feature = "load-dynamic")]
@@ -90,7 +90,7 @@ impl KernelAttributes {
 			return Err(Error::new("input index out of bounds or input is not constant"));
 		}
 
-		unsafe { ValueRef::new(DynValue::from_ptr_nodrop(NonNull::new_unchecked(value_ptr.cast_mut()), None)) }.downcast()
+		unsafe { ValueRef::new(DynValue::from_ptr_nodrop(NonNull::new_unchecked(value_ptr.cast_mut()), None)?) }.downcast()
 	}
 
 	pub fn node_name(&self) -> Result<String> {
@@ -333,7 +333,7 @@ impl<'s, T: DowncastableTarget> FromKernelAttributes<'s> for ValueRef<'s, T> {
 
 		let mut value_ptr: *mut ort_sys::OrtValue = ptr::null_mut();
 		ortsys![unsafe KernelInfoGetAttribute_tensor(info, name, allocator.ptr().cast_mut(), &mut value_ptr)?; nonNull(value_ptr)];
-		unsafe { ValueRef::new(DynValue::from_ptr(NonNull::new_unchecked(value_ptr), None)) }.downcast()
+		unsafe { ValueRef::new(DynValue::from_ptr(NonNull::new_unchecked(value_ptr), None)?) }.downcast()
 	}
 
 	private_impl!();
@@ -385,14 +385,18 @@ impl KernelContext {
 	pub fn input(&self, idx: usize) -> Result<Option<ValueRef<'_>>> {
 		let mut value_ptr: *const ort_sys::OrtValue = ptr::null();
 		ortsys![unsafe KernelContext_GetInput(self.ptr.as_ptr(), idx, &mut value_ptr)?];
-		Ok(NonNull::new(value_ptr.cast_mut()).map(|c| ValueRef::new(unsafe { Value::from_ptr_nodrop(c, None) })))
+		NonNull::new(value_ptr.cast_mut())
+			.map(|c| Ok(ValueRef::new(unsafe { Value::from_ptr_nodrop(c, None) }?)))
+			.transpose()
 	}
 
 	pub fn output(&self, idx: usize, shape: impl Into<Shape>) -> Result<Option<ValueRefMut<'_>>> {
 		let mut value_ptr: *mut ort_sys::OrtValue = ptr::null_mut();
 		let shape = shape.into();
 		ortsys![unsafe KernelContext_GetOutput(self.ptr.as_ptr(), idx, shape.as_ptr(), shape.len(), &mut value_ptr)?];
-		Ok(NonNull::new(value_ptr).map(|c| ValueRefMut::new(unsafe { Value::from_ptr_nodrop(c, None) })))
+		NonNull::new(value_ptr)
+			.map(|c| Ok(ValueRefMut::new(unsafe { Value::from_ptr_nodrop(c, None) }?)))
+			.transpose()
 	}
 
 	pub fn num_inputs(&self) -> Result<usize> {
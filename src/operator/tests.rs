@@ -78,7 +78,7 @@ impl Operator for CustomOpTwo {
 #[test]
 fn test_custom_ops() -> crate::Result<()> {
 	let model = std::fs::read("tests/data/custom_op_test.onnx").expect("");
-	let mut session = Session::builder()?
+	let session = Session::builder()?
 		.with_operators(OperatorDomain::new("test.customop")?.add(CustomOpOne)?.add(CustomOpTwo)?)?
 		.commit_from_memory(&model)?;
 
@@ -0,0 +1,85 @@
+//! Running sessions over [Apache Arrow](https://arrow.apache.org/) `RecordBatch`es, for tabular models which take one
+//! input per column, like those converted from scikit-learn or LightGBM.
+
+use alloc::{borrow::Cow, format, sync::Arc, vec::Vec};
+
+use arrow_array::RecordBatch;
+use arrow_schema::{Field, Schema};
+
+use super::{Session, SessionInputs, SessionOutputs};
+use crate::{
+	error::{Error, ErrorCode, Result},
+	tensor::{Shape, array_to_value, value_to_array},
+	value::ValueType
+};
+
+impl Session {
+	/// Maps the columns of an Arrow [`RecordBatch`] to this session's inputs by name.
+	///
+	/// Each input must have a column of the same name; any other columns are ignored. Columns become tensors of shape
+	/// `[N]` or `[N, 1]` (where `N` is the number of rows), depending on the rank the input expects. Primitive columns
+	/// without nulls are passed to the session without copying; nulls in floating-point columns are replaced with NaN,
+	/// which most tabular models treat as a missing value. Nulls in other columns are an error.
+	///
+	/// ```no_run
+	/// # use std::sync::Arc;
+	/// # use arrow_array::{Float32Array, RecordBatch, StringArray};
+	/// # use ort::session::Session;
+	/// # fn main() -> ort::Result<()> {
+	/// let session = Session::builder()?.commit_from_file("tests/data/arrow_tabular.onnx")?;
+	/// let batch = RecordBatch::try_from_iter([
+	/// 	("age", Arc::new(Float32Array::from(vec![31.0, 54.0])) as _),
+	/// 	("income", Arc::new(Float32Array::from(vec![Some(52_000.0), None])) as _),
+	/// 	("city", Arc::new(StringArray::from(vec!["Lisbon", "Oslo"])) as _)
+	/// ])
+	/// .unwrap();
+	///
+	/// let outputs = session.run(session.inputs_from_record_batch(&batch)?)?;
+	/// let predictions = outputs.to_record_batch()?;
+	/// # 	Ok(())
+	/// # }
+	/// ```
+	pub fn inputs_from_record_batch<'s, 'b>(&'s self, batch: &'b RecordBatch) -> Result<SessionInputs<'s, 'b>> {
+		let rows = batch.num_rows();
+		let mut inputs = Vec::with_capacity(self.inputs.len());
+		for input in &self.inputs {
+			let column = batch
+				.column_by_name(&input.name)
+				.ok_or_else(|| Error::new_with_code(ErrorCode::InvalidArgument, format!("Record batch has no column for input `{}`", input.name)))?;
+			let shape = match &input.input_type {
+				ValueType::Tensor { shape, .. } if shape.len() == 1 => Shape::from([rows]),
+				ValueType::Tensor { shape, .. } if shape.len() == 2 && matches!(shape[1], -1 | 1) => Shape::from([rows, 1]),
+				t => {
+					return Err(Error::new_with_code(
+						ErrorCode::InvalidArgument,
+						format!("Input `{}` of type {t} cannot be created from a single column", input.name)
+					));
+				}
+			};
+			inputs.push((Cow::Borrowed(input.name.as_str()), array_to_value(column.as_ref(), shape)?));
+		}
+		Ok(SessionInputs::ValueMap(inputs))
+	}
+}
+
+impl SessionOutputs<'_, '_> {
+	/// Converts these outputs to an Arrow [`RecordBatch`] with one column per output, named after the output.
+	///
+	/// - Tensors of shape `[N]` or `[N, 1]` become primitive, boolean, or UTF-8 columns.
+	/// - Tensors of shape `[N, K]` become fixed-size list columns of `K` elements, like the probabilities output by
+	///   classifiers.
+	/// - Sequences of maps, like the probabilities output by scikit-learn classifiers through `ZipMap`, become map
+	///   columns.
+	///
+	/// Every output must have the same number of rows `N`.
+	pub fn to_record_batch(&self) -> Result<RecordBatch> {
+		let mut fields = Vec::with_capacity(self.len());
+		let mut columns = Vec::with_capacity(self.len());
+		for (name, value) in self {
+			let column = value_to_array(&value)?;
+			fields.push(Field::new(name, column.data_type().clone(), false));
+			columns.push(column);
+		}
+		RecordBatch::try_new(Arc::new(Schema::new(fields)), columns).map_err(Error::wrap)
+	}
+}
@@ -8,28 +8,32 @@ use core::{
 	ptr::NonNull,
 	task::{Context, Poll, Waker}
 };
-use std::sync::Mutex;
+use std::sync::{Condvar, Mutex};
 
 use smallvec::SmallVec;
 
 use crate::{
 	error::Result,
-	session::{SessionOutputs, SharedSessionInner, run_options::UntypedRunOptions},
+	session::{SessionOutputs, SharedSessionInner, run_options::UntypedRunOptions, watchdog::Watchdog},
 	util::{STACK_SESSION_INPUTS, STACK_SESSION_OUTPUTS},
-	value::{Value, ValueInner}
+	value::{DynValue, Value, ValueInner, ValueType}
 };
 
 #[derive(Debug)]
 pub(crate) struct InferenceFutInner<'r, 's> {
 	value: UnsafeCell<Option<Result<SessionOutputs<'r, 's>>>>,
-	waker: Mutex<Option<Waker>>
+	waker: Mutex<Option<Waker>>,
+	completed: Mutex<bool>,
+	completed_cond: Condvar
 }
 
 impl<'r, 's> InferenceFutInner<'r, 's> {
 	pub(crate) fn new() -> Self {
 		InferenceFutInner {
 			waker: Mutex::new(None),
-			value: UnsafeCell::new(None)
+			value: UnsafeCell::new(None),
+			completed: Mutex::new(false),
+			completed_cond: Condvar::new()
 		}
 	}
 
@@ -50,14 +54,41 @@ impl<'r, 's> InferenceFutInner<'r, 's> {
 			waker.wake();
 		}
 	}
+
+	/// Signals that ONNX Runtime is done with the run, and thus no longer needs any of its borrowed data.
+	pub(crate) fn complete(&self) {
+		*self.completed.lock().expect("Poisoned completion mutex") = true;
+		self.completed_cond.notify_all();
+	}
+
+	pub(crate) fn wait_for_completion(&self) {
+		let mut completed = self.completed.lock().expect("Poisoned completion mutex");
+		while !*completed {
+			completed = self.completed_cond.wait(completed).expect("Poisoned completion mutex");
+		}
+	}
 }
 
 unsafe impl Send for InferenceFutInner<'_, '_> {}
 unsafe impl Sync for InferenceFutInner<'_, '_> {}
 
+/// A future which resolves to the outputs of an asynchronous session run; see
+/// [`Session::run_async`](crate::session::Session::run_async).
+///
+/// # Blocking on drop
+/// Dropping the future before it resolves terminates the run, then **blocks the dropping thread** until ONNX Runtime
+/// acknowledges the termination. ONNX Runtime only checks for termination between nodes, so this can take as long as
+/// the currently executing node does. The run borrows its inputs, run options, and output names, so the future cannot
+/// return before ONNX Runtime has stopped using them.
+///
+/// When a future is cancelled on an async executor - for example, by losing a `select!` or having its task aborted -
+/// this blocks one of the executor's worker threads. Models whose individual nodes take a long time to execute should
+/// be run via [`Session::run`](crate::session::Session::run) on a blocking thread (e.g. `tokio::task::spawn_blocking`)
+/// instead.
 pub struct InferenceFut<'s, 'r, 'v> {
 	inner: Arc<InferenceFutInner<'r, 's>>,
 	run_options: &'r UntypedRunOptions,
+	watchdog: Option<Watchdog>,
 	did_receive: bool,
 	_inputs: PhantomData<&'v ()>
 }
@@ -69,10 +100,16 @@ impl<'s, 'r> InferenceFut<'s, 'r, '_> {
 		Self {
 			inner,
 			run_options,
+			watchdog: None,
 			did_receive: false,
 			_inputs: PhantomData
 		}
 	}
+
+	pub(crate) fn with_watchdog(mut self, watchdog: Watchdog) -> Self {
+		self.watchdog = Some(watchdog);
+		self
+	}
 }
 
 impl<'s, 'r> Future for InferenceFut<'s, 'r, '_> {
@@ -83,7 +120,10 @@ impl<'s, 'r> Future for InferenceFut<'s, 'r, '_> {
 
 		if let Some(v) = this.inner.try_take() {
 			this.did_receive = true;
-			return Poll::Ready(v);
+			return Poll::Ready(match this.watchdog.take() {
+				Some(watchdog) => watchdog.finish(v),
+				None => v
+			});
 		}
 
 		this.inner.set_waker(Some(cx.waker()));
@@ -96,6 +136,9 @@ impl Drop for InferenceFut<'_, '_, '_> {
 		if !self.did_receive {
 			let _ = self.run_options.terminate();
 			self.inner.set_waker(None);
+			// ONNX Runtime may still be using the run options & output names we borrow; wait for it to acknowledge the
+			// termination before releasing them.
+			self.inner.wait_for_completion();
 		}
 	}
 }
@@ -106,14 +149,20 @@ pub(crate) struct AsyncInferenceContext<'r, 's> {
 	pub(crate) _input_inner_holders: SmallVec<Arc<ValueInner>, { STACK_SESSION_INPUTS }>,
 	pub(crate) input_name_ptrs: SmallVec<*const c_char, { STACK_SESSION_INPUTS }>,
 	pub(crate) output_name_ptrs: SmallVec<*const c_char, { STACK_SESSION_OUTPUTS }>,
-	pub(crate) session_inner: &'s Arc<SharedSessionInner>,
+	pub(crate) session_inner: Arc<SharedSessionInner>,
 	pub(crate) output_names: SmallVec<&'r str, { STACK_SESSION_OUTPUTS }>,
+	pub(crate) output_types: SmallVec<Option<ValueType>, { STACK_SESSION_OUTPUTS }>,
 	pub(crate) output_value_ptrs: SmallVec<*mut ort_sys::OrtValue, { STACK_SESSION_OUTPUTS }>
 }
 
 pub(crate) extern "system" fn async_callback(user_data: *mut c_void, _: *mut *mut ort_sys::OrtValue, _: usize, status: ort_sys::OrtStatusPtr) {
 	let ctx = unsafe { Box::from_raw(user_data.cast::<AsyncInferenceContext<'_, '_>>()) };
+	let inner = Arc::clone(&ctx.inner);
+	complete_run(*ctx, status);
+	inner.complete();
+}
 
+fn complete_run(ctx: AsyncInferenceContext<'_, '_>, status: ort_sys::OrtStatusPtr) {
 	// Reconvert name ptrs to CString so drop impl is called and memory is freed
 	for p in ctx.input_name_ptrs {
 		drop(unsafe { CString::from_raw(p.cast_mut().cast()) });
@@ -128,11 +177,20 @@ pub(crate) extern "system" fn async_callback(user_data: *mut c_void, _: *mut *mu
 	let outputs = ctx
 		.output_value_ptrs
 		.into_iter()
-		.map(|tensor_ptr| unsafe {
-			Value::from_ptr(NonNull::new(tensor_ptr).expect("OrtValue ptr returned from session Run should not be null"), Some(Arc::clone(ctx.session_inner)))
+		.zip(&ctx.output_types)
+		.map(|(tensor_ptr, ty)| unsafe {
+			Value::from_ptr_with_type(
+				NonNull::new(tensor_ptr).expect("OrtValue ptr returned from session Run should not be null"),
+				Some(Arc::clone(&ctx.session_inner)),
+				ty.as_ref()
+			)
 		})
-		.collect();
+		// wrap every output before bailing on an error, so none are leaked
+		.collect::<SmallVec<Result<DynValue>, { STACK_SESSION_OUTPUTS }>>()
+		.into_iter()
+		.collect::<Result<_>>();
 
-	ctx.inner.emplace_value(Ok(SessionOutputs::new(ctx.output_names, outputs)));
+	ctx.inner
+		.emplace_value(outputs.map(|outputs| SessionOutputs::new(ctx.output_names, outputs)));
 	ctx.inner.wake();
 }
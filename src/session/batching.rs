@@ -0,0 +1,760 @@
+//! Contains [`DynamicBatcher`], which transparently batches inference requests from many concurrent callers into a
+//! single [`Session::run`].
+//!
+//! ```
+//! # use std::sync::Arc;
+//! # use ort::{session::{Session, batching::DynamicBatcher}, value::TensorRef};
+//! # fn main() -> ort::Result<()> {
+//! let session = Session::builder()?.commit_from_file("tests/data/upsample.onnx")?;
+//! let batcher = Arc::new(
+//! 	DynamicBatcher::builder(session)
+//! 		.with_max_batch_size(8)
+//! 		.with_max_delay(std::time::Duration::from_millis(2))
+//! 		.build()?
+//! );
+//!
+//! let handles: Vec<_> = (0..4)
+//! 	.map(|_| {
+//! 		let batcher = Arc::clone(&batcher);
+//! 		std::thread::spawn(move || -> ort::Result<()> {
+//! 			// Each caller submits a batch of 1...
+//! 			let input = ndarray::Array4::<f32>::zeros((1, 64, 64, 3));
+//! 			let outputs = batcher.run(ort::inputs![TensorRef::from_array_view(&input)?])?;
+//! 			// ...and receives only its own slice of the batched output.
+//! 			let (shape, _) = outputs[0].try_extract_tensor::<f32>()?;
+//! 			assert_eq!(&**shape, [1, 128, 128, 3]);
+//! 			Ok(())
+//! 		})
+//! 	})
+//! 	.collect();
+//! for handle in handles {
+//! 	handle.join().unwrap()?;
+//! }
+//! # 	Ok(())
+//! # }
+//! ```
+
+use alloc::{boxed::Box, collections::VecDeque, format, string::String, sync::Arc, vec, vec::Vec};
+use core::{fmt, mem::size_of, ops::Index, slice, time::Duration};
+use std::{
+	sync::{Condvar, Mutex, MutexGuard, mpsc},
+	thread::{self, JoinHandle},
+	time::Instant
+};
+
+use super::{Session, SessionInputValue, SessionInputs};
+use crate::{
+	error::{Error, ErrorCode, Result},
+	memory::Allocator,
+	tensor::{PrimitiveTensorElementType, TensorElementType},
+	value::{DynTensor, DynTensorValueType, DynValue, Value, ValueType}
+};
+
+/// Metrics describing a single batch processed by a [`DynamicBatcher`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct BatchMetrics {
+	/// The number of requests (i.e. calls to [`DynamicBatcher::run`]) that were combined into this batch.
+	pub requests: usize,
+	/// The total size of the batch dimension; the sum of the batch size of each request.
+	pub batch_size: usize,
+	/// The number of elements across all inputs that had to be filled with padding.
+	pub padded_elements: usize,
+	/// How long the oldest request in this batch spent waiting in the queue before the batch was formed.
+	pub queue_time: Duration,
+	/// How long the underlying [`Session::run`] took.
+	pub run_time: Duration
+}
+
+type MetricsCallback = Box<dyn Fn(&BatchMetrics) + Send + Sync>;
+
+/// Builder used to configure & create a [`DynamicBatcher`]. See [`DynamicBatcher::builder`].
+pub struct DynamicBatcherBuilder {
+	session: Arc<Session>,
+	max_batch_size: usize,
+	max_delay: Duration,
+	padding: Vec<(String, TensorElementType, Vec<u8>)>,
+	metrics_callback: Option<MetricsCallback>
+}
+
+impl DynamicBatcherBuilder {
+	/// Configures the maximum size of the batch dimension. Once this many items are queued, the batch is run
+	/// immediately without waiting for [`DynamicBatcherBuilder::with_max_delay`] to elapse.
+	///
+	/// A single request whose own batch size exceeds this limit is still run, but in a batch of its own.
+	///
+	/// Defaults to `8`.
+	#[must_use]
+	pub fn with_max_batch_size(mut self, max_batch_size: usize) -> Self {
+		self.max_batch_size = max_batch_size.max(1);
+		self
+	}
+
+	/// Configures the maximum amount of time a request will wait in the queue for other requests to join its batch.
+	///
+	/// Defaults to 5 milliseconds.
+	#[must_use]
+	pub fn with_max_delay(mut self, max_delay: Duration) -> Self {
+		self.max_delay = max_delay;
+		self
+	}
+
+	/// Allows requests with differing non-batch dimensions for the input named `input` to be batched together by
+	/// padding each request's data with `value` up to the largest size in the batch.
+	///
+	/// Padding is applied to the end of each dimension. Note that outputs are *not* un-padded, since the batcher
+	/// cannot know how the model maps input dimensions to output dimensions; callers should trim outputs themselves if
+	/// required.
+	///
+	/// Without padding configured, only requests with identical shapes (other than the batch dimension) are batched
+	/// together.
+	#[must_use]
+	pub fn with_padding<T: PrimitiveTensorElementType + Copy>(mut self, input: impl Into<String>, value: T) -> Self {
+		let bytes = unsafe { slice::from_raw_parts((&value as *const T).cast::<u8>(), size_of::<T>()) }.to_vec();
+		self.padding.push((input.into(), T::into_tensor_element_type(), bytes));
+		self
+	}
+
+	/// Registers a callback which will be called with [`BatchMetrics`] after each batch is processed.
+	///
+	/// The callback runs on the batcher's worker thread, so it should return quickly.
+	#[must_use]
+	pub fn with_metrics_callback(mut self, callback: impl Fn(&BatchMetrics) + Send + Sync + 'static) -> Self {
+		self.metrics_callback = Some(Box::new(callback));
+		self
+	}
+
+	/// Validates the configuration against the session's inputs & outputs and starts the batcher's worker thread.
+	///
+	/// All of the session's inputs and outputs must be primitive tensors with a dynamic (`-1`) leading dimension.
+	pub fn build(self) -> Result<DynamicBatcher> {
+		let mut inputs = Vec::with_capacity(self.session.inputs.len());
+		for input in &self.session.inputs {
+			let (ty, shape) = batchable_tensor_info(&input.name, &input.input_type)?;
+			let padding = match self.padding.iter().find(|(name, ..)| name == &input.name) {
+				Some((_, pad_ty, bytes)) if *pad_ty == ty => Some(bytes.clone()),
+				Some((_, pad_ty, _)) => {
+					return Err(Error::new_with_code(
+						ErrorCode::InvalidArgument,
+						format!("Padding value for input '{}' is of type {pad_ty}, but the input is of type {ty}", input.name)
+					));
+				}
+				None => None
+			};
+			inputs.push(BatchedInput { ty, shape, padding });
+		}
+		if let Some((name, ..)) = self
+			.padding
+			.iter()
+			.find(|(name, ..)| !self.session.inputs.iter().any(|i| &i.name == name))
+		{
+			return Err(Error::new_with_code(ErrorCode::InvalidArgument, format!("Padding was configured for unknown input '{name}'")));
+		}
+		for output in &self.session.outputs {
+			batchable_tensor_info(&output.name, &output.output_type)?;
+		}
+
+		let shared = Arc::new(BatcherShared {
+			session: self.session,
+			inputs,
+			max_batch_size: self.max_batch_size,
+			max_delay: self.max_delay,
+			metrics_callback: self.metrics_callback,
+			queue: Mutex::new(BatcherQueue {
+				requests: VecDeque::new(),
+				shutdown: false
+			}),
+			available: Condvar::new()
+		});
+
+		let worker = thread::Builder::new()
+			.name(String::from("ort-dynamic-batcher"))
+			.spawn({
+				let shared = Arc::clone(&shared);
+				move || shared.worker()
+			})
+			.map_err(Error::wrap)?;
+
+		Ok(DynamicBatcher { shared, worker: Some(worker) })
+	}
+}
+
+impl fmt::Debug for DynamicBatcherBuilder {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("DynamicBatcherBuilder")
+			.field("max_batch_size", &self.max_batch_size)
+			.field("max_delay", &self.max_delay)
+			.field("padding", &self.padding)
+			.finish_non_exhaustive()
+	}
+}
+
+/// Collects inference requests from concurrent callers and runs them as a single batch.
+///
+/// Requests are queued until either [`max_batch_size`](DynamicBatcherBuilder::with_max_batch_size) items are waiting,
+/// or the oldest request has waited for [`max_delay`](DynamicBatcherBuilder::with_max_delay). The inputs of all
+/// requests in the batch are then concatenated along the leading (batch) dimension, the session is run once, and each
+/// output is split along its leading dimension and returned to the respective caller.
+///
+/// All inputs and outputs of the session must be tensors with a dynamic leading dimension. Requests are copied into
+/// the batcher's queue, so inputs must be CPU-accessible tensors of a fixed-size element type (i.e. not strings).
+///
+/// The batcher runs on a dedicated worker thread, which is stopped (after processing any remaining requests) when the
+/// batcher is dropped.
+pub struct DynamicBatcher {
+	shared: Arc<BatcherShared>,
+	worker: Option<JoinHandle<()>>
+}
+
+impl DynamicBatcher {
+	/// Creates a new [`DynamicBatcherBuilder`] to batch requests to the given session.
+	///
+	/// The session may either be passed by value or shared via an [`Arc`], in which case it can still be used to run
+	/// inference directly.
+	pub fn builder(session: impl Into<Arc<Session>>) -> DynamicBatcherBuilder {
+		DynamicBatcherBuilder {
+			session: session.into(),
+			max_batch_size: 8,
+			max_delay: Duration::from_millis(5),
+			padding: Vec::new(),
+			metrics_callback: None
+		}
+	}
+
+	/// Returns the session used by this batcher.
+	#[must_use]
+	pub fn session(&self) -> &Session {
+		&self.shared.session
+	}
+
+	/// Returns the number of requests currently waiting to be batched.
+	#[must_use]
+	pub fn queued_requests(&self) -> usize {
+		self.shared.lock_queue().requests.len()
+	}
+
+	/// Submits a request to the batcher & blocks until its outputs are available.
+	///
+	/// Inputs are specified the same way as in [`Session::run`], and must include every input of the session. The
+	/// leading dimension of each input is the batch dimension, and must be the same for all inputs of the request.
+	pub fn run<'i, 'v: 'i, const N: usize>(&self, input_values: impl Into<SessionInputs<'i, 'v, N>>) -> Result<BatchOutputs> {
+		let (inputs, batch_size) = self.shared.prepare_inputs(input_values.into())?;
+		let (tx, rx) = mpsc::sync_channel(1);
+		self.shared.lock_queue().requests.push_back(PendingRequest {
+			inputs,
+			batch_size,
+			enqueued: Instant::now(),
+			tx
+		});
+		self.shared.available.notify_one();
+
+		rx.recv()
+			.map_err(|_| Error::new("Dynamic batcher worker exited before the request was processed"))?
+	}
+}
+
+impl fmt::Debug for DynamicBatcher {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("DynamicBatcher")
+			.field("session", &self.shared.session)
+			.field("max_batch_size", &self.shared.max_batch_size)
+			.field("max_delay", &self.shared.max_delay)
+			.finish_non_exhaustive()
+	}
+}
+
+impl Drop for DynamicBatcher {
+	fn drop(&mut self) {
+		self.shared.lock_queue().shutdown = true;
+		self.shared.available.notify_all();
+		if let Some(worker) = self.worker.take() {
+			let _ = worker.join();
+		}
+	}
+}
+
+/// The outputs of a single request processed by a [`DynamicBatcher`].
+///
+/// Each output has the same batch size as the corresponding request's inputs.
+#[derive(Debug)]
+pub struct BatchOutputs {
+	names: Vec<String>,
+	values: Vec<DynValue>
+}
+
+impl BatchOutputs {
+	/// Returns the output with the given name, if it exists.
+	pub fn get(&self, key: impl AsRef<str>) -> Option<&DynValue> {
+		let key = key.as_ref();
+		self.names.iter().position(|name| name == key).map(|i| &self.values[i])
+	}
+
+	/// Removes the output with the given name & returns it, if it exists.
+	pub fn remove(&mut self, key: impl AsRef<str>) -> Option<DynValue> {
+		let key = key.as_ref();
+		let index = self.names.iter().position(|name| name == key)?;
+		self.names.remove(index);
+		Some(self.values.remove(index))
+	}
+
+	/// Returns the number of outputs.
+	#[must_use]
+	pub fn len(&self) -> usize {
+		self.values.len()
+	}
+
+	/// Returns `true` if there are no outputs.
+	#[must_use]
+	pub fn is_empty(&self) -> bool {
+		self.values.is_empty()
+	}
+
+	/// Returns an iterator over the names & values of each output.
+	pub fn iter(&self) -> impl ExactSizeIterator<Item = (&str, &DynValue)> {
+		self.names.iter().map(String::as_str).zip(self.values.iter())
+	}
+}
+
+impl Index<&str> for BatchOutputs {
+	type Output = DynValue;
+	fn index(&self, key: &str) -> &Self::Output {
+		self.get(key).expect("no output with the given name")
+	}
+}
+
+impl Index<usize> for BatchOutputs {
+	type Output = DynValue;
+	fn index(&self, index: usize) -> &Self::Output {
+		&self.values[index]
+	}
+}
+
+impl IntoIterator for BatchOutputs {
+	type Item = (String, DynValue);
+	type IntoIter = core::iter::Zip<vec::IntoIter<String>, vec::IntoIter<DynValue>>;
+
+	fn into_iter(self) -> Self::IntoIter {
+		self.names.into_iter().zip(self.values)
+	}
+}
+
+struct BatchedInput {
+	ty: TensorElementType,
+	shape: Vec<i64>,
+	padding: Option<Vec<u8>>
+}
+
+struct HostTensor {
+	shape: Vec<usize>,
+	data: Vec<u8>
+}
+
+struct PendingRequest {
+	inputs: Vec<HostTensor>,
+	batch_size: usize,
+	enqueued: Instant,
+	tx: mpsc::SyncSender<Result<BatchOutputs>>
+}
+
+struct BatcherQueue {
+	requests: VecDeque<PendingRequest>,
+	shutdown: bool
+}
+
+struct BatcherShared {
+	session: Arc<Session>,
+	inputs: Vec<BatchedInput>,
+	max_batch_size: usize,
+	max_delay: Duration,
+	metrics_callback: Option<MetricsCallback>,
+	queue: Mutex<BatcherQueue>,
+	available: Condvar
+}
+
+impl BatcherShared {
+	fn lock_queue(&self) -> MutexGuard<'_, BatcherQueue> {
+		self.queue.lock().expect("Poisoned batcher mutex")
+	}
+
+	/// Validates the inputs of a request & copies them into host memory so they can be sent to the worker thread.
+	/// Returns the copied inputs and the request's batch size.
+	fn prepare_inputs<const N: usize>(&self, input_values: SessionInputs<'_, '_, N>) -> Result<(Vec<HostTensor>, usize)> {
+		let session_inputs = &self.session.inputs;
+		let named_values: Vec<(&str, &Value)> = match &input_values {
+			SessionInputs::ValueSlice(values) => session_inputs.iter().map(|i| i.name.as_str()).zip(values.iter().map(|v| &**v)).collect(),
+			SessionInputs::ValueArray(values) => session_inputs.iter().map(|i| i.name.as_str()).zip(values.iter().map(|v| &**v)).collect(),
+			SessionInputs::ValueMap(values) => values.iter().map(|(k, v)| (k.as_ref(), &**v)).collect()
+		};
+		let provided = match &input_values {
+			SessionInputs::ValueSlice(values) => values.len(),
+			SessionInputs::ValueArray(values) => values.len(),
+			SessionInputs::ValueMap(values) => values.len()
+		};
+		if provided != session_inputs.len() {
+			return Err(Error::new_with_code(
+				ErrorCode::InvalidArgument,
+				format!("{provided} inputs were provided, but the model expects {}", session_inputs.len())
+			));
+		}
+
+		let mut batch_size = None;
+		let mut inputs = Vec::with_capacity(session_inputs.len());
+		for (input, batched) in session_inputs.iter().zip(&self.inputs) {
+			let Some((_, value)) = named_values.iter().find(|(name, _)| *name == input.name) else {
+				return Err(Error::new_with_code(ErrorCode::InvalidArgument, format!("Missing input '{}'", input.name)));
+			};
+			let tensor = value.downcast_ref::<DynTensorValueType>()?;
+			let ValueType::Tensor { ty, shape, .. } = tensor.dtype() else {
+				unreachable!()
+			};
+			if *ty != batched.ty {
+				return Err(Error::new_with_code(ErrorCode::InvalidArgument, format!("Input '{}' should be of type {}, got {ty}", input.name, batched.ty)));
+			}
+			if shape.len() != batched.shape.len() {
+				return Err(Error::new_with_code(
+					ErrorCode::InvalidArgument,
+					format!("Input '{}' should have rank {}, got shape {shape}", input.name, batched.shape.len())
+				));
+			}
+			if shape
+				.iter()
+				.zip(&batched.shape)
+				.skip(1)
+				.any(|(&actual, &expected)| expected >= 0 && actual != expected)
+			{
+				return Err(Error::new_with_code(
+					ErrorCode::InvalidArgument,
+					format!("Input '{}' should have shape {:?}, got {shape}", input.name, batched.shape)
+				));
+			}
+			if !tensor.memory_info().is_cpu_accessible() {
+				return Err(Error::new_with_code(ErrorCode::InvalidArgument, format!("Input '{}' must be in CPU-accessible memory to be batched", input.name)));
+			}
+
+			let this_batch = shape[0] as usize;
+			match batch_size {
+				None => batch_size = Some(this_batch),
+				Some(b) if b != this_batch => {
+					return Err(Error::new_with_code(
+						ErrorCode::InvalidArgument,
+						format!("All inputs must have the same batch size; input '{}' has batch size {this_batch}, expected {b}", input.name)
+					));
+				}
+				Some(_) => {}
+			}
+
+			let len = ty.byte_size(shape.num_elements());
+			let data = unsafe { slice::from_raw_parts(tensor.data_ptr()?.cast::<u8>(), len) }.to_vec();
+			inputs.push(HostTensor {
+				shape: shape.iter().map(|&d| d as usize).collect(),
+				data
+			});
+		}
+
+		let batch_size = batch_size.unwrap_or(0);
+		if batch_size == 0 {
+			return Err(Error::new_with_code(ErrorCode::InvalidArgument, "Requests to a dynamic batcher must have a batch size of at least 1"));
+		}
+
+		Ok((inputs, batch_size))
+	}
+
+	fn worker(&self) {
+		loop {
+			let mut queue = self.lock_queue();
+			while queue.requests.is_empty() && !queue.shutdown {
+				queue = self.available.wait(queue).expect("Poisoned batcher mutex");
+			}
+			if queue.requests.is_empty() {
+				// shutting down and nothing left to process
+				return;
+			}
+
+			// wait for more requests to join the batch, until the oldest request's deadline
+			let deadline = queue.requests[0].enqueued + self.max_delay;
+			while !queue.shutdown && queue.requests.iter().map(|r| r.batch_size).sum::<usize>() < self.max_batch_size {
+				let Some(remaining) = deadline.checked_duration_since(Instant::now()) else {
+					break;
+				};
+				queue = self.available.wait_timeout(queue, remaining).expect("Poisoned batcher mutex").0;
+			}
+
+			let batch = self.take_batch(&mut queue.requests);
+			drop(queue);
+
+			self.process_batch(batch);
+		}
+	}
+
+	/// Removes the oldest request, plus any other requests compatible with it, from the queue.
+	fn take_batch(&self, requests: &mut VecDeque<PendingRequest>) -> Vec<PendingRequest> {
+		let first = requests.pop_front().expect("queue should not be empty");
+		let mut total = first.batch_size;
+		let mut batch = vec![first];
+
+		let mut i = 0;
+		while i < requests.len() && total < self.max_batch_size {
+			let candidate = &requests[i];
+			if total + candidate.batch_size <= self.max_batch_size && self.is_compatible(&batch[0], candidate) {
+				total += candidate.batch_size;
+				batch.push(requests.remove(i).expect("index should be in bounds"));
+			} else {
+				i += 1;
+			}
+		}
+		batch
+	}
+
+	/// Two requests are compatible if, for every input, they either have the same non-batch dimensions, or padding is
+	/// configured for that input.
+	fn is_compatible(&self, a: &PendingRequest, b: &PendingRequest) -> bool {
+		self.inputs
+			.iter()
+			.zip(a.inputs.iter().zip(&b.inputs))
+			.all(|(input, (a, b))| input.padding.is_some() || a.shape[1..] == b.shape[1..])
+	}
+
+	fn process_batch(&self, batch: Vec<PendingRequest>) {
+		let started = Instant::now();
+		let queue_time = batch
+			.iter()
+			.map(|r| started.saturating_duration_since(r.enqueued))
+			.max()
+			.unwrap_or_default();
+		match self.run_batch(&batch) {
+			Ok((outputs, mut metrics)) => {
+				metrics.queue_time = queue_time;
+				for (request, outputs) in batch.into_iter().zip(outputs) {
+					let _ = request.tx.send(Ok(outputs));
+				}
+				if let Some(callback) = &self.metrics_callback {
+					callback(&metrics);
+				}
+			}
+			Err(e) => {
+				for request in batch {
+					let _ = request.tx.send(Err(Error::new_with_code(e.code(), e.message())));
+				}
+			}
+		}
+	}
+
+	fn run_batch(&self, batch: &[PendingRequest]) -> Result<(Vec<BatchOutputs>, BatchMetrics)> {
+		let allocator = Allocator::default();
+		let total_batch: usize = batch.iter().map(|r| r.batch_size).sum();
+		let mut padded_elements = 0;
+
+		let mut input_values: Vec<SessionInputValue<'static>> = Vec::with_capacity(self.inputs.len());
+		for (i, input) in self.inputs.iter().enumerate() {
+			let rank = input.shape.len();
+			let mut shape = vec![total_batch; rank];
+			for (d, dim) in shape.iter_mut().enumerate().skip(1) {
+				*dim = batch.iter().map(|r| r.inputs[i].shape[d]).max().unwrap_or(0);
+			}
+
+			let mut tensor = DynTensor::new(&allocator, input.ty, shape.clone())?;
+			let element_size = input.ty.byte_size(1);
+			let row_elements: usize = shape[1..].iter().product();
+			let dst = unsafe { slice::from_raw_parts_mut(tensor.data_ptr_mut()?.cast::<u8>(), input.ty.byte_size(total_batch * row_elements)) };
+			if let Some(padding) = &input.padding {
+				for element in dst.chunks_exact_mut(element_size) {
+					element.copy_from_slice(padding);
+				}
+			}
+
+			let mut offset = 0;
+			for request in batch {
+				let src = &request.inputs[i];
+				let mut dst_shape = shape.clone();
+				dst_shape[0] = request.batch_size;
+				let row_bytes = element_size * row_elements;
+				copy_padded(&src.data, &src.shape, &mut dst[offset * row_bytes..(offset + request.batch_size) * row_bytes], &dst_shape, element_size);
+				offset += request.batch_size;
+				padded_elements += request.batch_size * row_elements - src.shape.iter().product::<usize>();
+			}
+
+			input_values.push(tensor.into());
+		}
+
+		let run_started = Instant::now();
+		let outputs = self.session.run(input_values.as_slice())?;
+		let run_time = run_started.elapsed();
+
+		let mut split_outputs: Vec<BatchOutputs> = batch
+			.iter()
+			.map(|_| BatchOutputs {
+				names: Vec::with_capacity(outputs.len()),
+				values: Vec::with_capacity(outputs.len())
+			})
+			.collect();
+		for (name, value) in outputs.iter() {
+			let tensor = value.downcast::<DynTensorValueType>()?;
+			let ValueType::Tensor { ty, shape, .. } = tensor.dtype() else {
+				unreachable!()
+			};
+			if shape.first().copied() != Some(total_batch as i64) {
+				return Err(Error::new_with_code(
+					ErrorCode::RuntimeException,
+					format!("Output '{name}' has shape {shape}, but a leading batch dimension of {total_batch} was expected")
+				));
+			}
+			if !tensor.memory_info().is_cpu_accessible() {
+				return Err(Error::new_with_code(ErrorCode::RuntimeException, format!("Output '{name}' must be in CPU-accessible memory to be split")));
+			}
+
+			let row_bytes = ty.byte_size(shape[1..].iter().product::<i64>() as usize);
+			let src = unsafe { slice::from_raw_parts(tensor.data_ptr()?.cast::<u8>(), row_bytes * total_batch) };
+			let mut offset = 0;
+			for (request, split) in batch.iter().zip(split_outputs.iter_mut()) {
+				let mut request_shape = shape.clone();
+				request_shape[0] = request.batch_size as i64;
+				let mut request_output = DynTensor::new(&allocator, *ty, request_shape)?;
+				let len = row_bytes * request.batch_size;
+				let dst = unsafe { slice::from_raw_parts_mut(request_output.data_ptr_mut()?.cast::<u8>(), len) };
+				dst.copy_from_slice(&src[offset..offset + len]);
+				offset += len;
+
+				split.names.push(String::from(name));
+				split.values.push(request_output.into_dyn());
+			}
+		}
+
+		Ok((
+			split_outputs,
+			BatchMetrics {
+				requests: batch.len(),
+				batch_size: total_batch,
+				padded_elements,
+				queue_time: Duration::ZERO,
+				run_time
+			}
+		))
+	}
+}
+
+/// Returns the element type & shape of a batchable tensor, or an error if `dtype` can't be batched.
+fn batchable_tensor_info(name: &str, dtype: &ValueType) -> Result<(TensorElementType, Vec<i64>)> {
+	let ValueType::Tensor { ty, shape, .. } = dtype else {
+		return Err(Error::new_with_code(ErrorCode::InvalidArgument, format!("'{name}' is not a tensor ({dtype}) and cannot be batched")));
+	};
+	if ty.byte_size(1) == 0 || ty.is_packed() {
+		return Err(Error::new_with_code(ErrorCode::InvalidArgument, format!("'{name}' is a tensor of {ty}, which cannot be batched")));
+	}
+	if shape.first() != Some(&-1) {
+		return Err(Error::new_with_code(
+			ErrorCode::InvalidArgument,
+			format!("'{name}' has shape {shape}; a dynamic (-1) leading dimension is required for batching")
+		));
+	}
+	Ok((*ty, shape.to_vec()))
+}
+
+/// Copies a row-major tensor of shape `src_shape` into the (larger or equal) row-major tensor `dst` of shape
+/// `dst_shape`, leaving any elements outside of `src_shape` untouched.
+fn copy_padded(src: &[u8], src_shape: &[usize], dst: &mut [u8], dst_shape: &[usize], element_size: usize) {
+	debug_assert_eq!(src_shape.len(), dst_shape.len());
+	if src_shape == dst_shape {
+		dst.copy_from_slice(src);
+		return;
+	}
+
+	let rank = src_shape.len();
+	let run_len = src_shape[rank - 1] * element_size;
+	let outer_elements: usize = src_shape[..rank - 1].iter().product();
+
+	let mut index = vec![0usize; rank.saturating_sub(1)];
+	for run in 0..outer_elements {
+		let mut dst_offset = 0;
+		for (d, &i) in index.iter().enumerate() {
+			dst_offset = dst_offset * dst_shape[d] + i;
+		}
+		let dst_offset = dst_offset * dst_shape[rank - 1] * element_size;
+		dst[dst_offset..dst_offset + run_len].copy_from_slice(&src[run * run_len..(run + 1) * run_len]);
+
+		// increment the multi-dimensional index
+		for d in (0..index.len()).rev() {
+			index[d] += 1;
+			if index[d] < src_shape[d] {
+				break;
+			}
+			index[d] = 0;
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::{
+		sync::{Arc, Mutex},
+		thread
+	};
+
+	use super::{BatchMetrics, DynamicBatcher, copy_padded};
+	use crate::{session::Session, value::TensorRef};
+
+	#[test]
+	fn test_copy_padded() {
+		// 2x2 -> 3x3, padding with 9
+		let src = [1u8, 2, 3, 4];
+		let mut dst = [9u8; 9];
+		copy_padded(&src, &[2, 2], &mut dst, &[3, 3], 1);
+		assert_eq!(dst, [1, 2, 9, 3, 4, 9, 9, 9, 9]);
+
+		// element size > 1
+		let src = [1u16, 2, 3, 4, 5, 6].map(u16::to_le_bytes).concat();
+		let mut dst = vec![0u8; 2 * 2 * 4];
+		copy_padded(&src, &[2, 3], &mut dst, &[2, 4], 2);
+		assert_eq!(dst, [1u16, 2, 3, 0, 4, 5, 6, 0].map(u16::to_le_bytes).concat());
+
+		// 3D
+		let src: Vec<u8> = (1..=8).collect();
+		let mut dst = vec![0u8; 12];
+		copy_padded(&src, &[2, 2, 2], &mut dst, &[2, 2, 3], 1);
+		assert_eq!(dst, [1, 2, 0, 3, 4, 0, 5, 6, 0, 7, 8, 0]);
+	}
+
+	#[test]
+	fn test_dynamic_batcher() -> crate::Result<()> {
+		let session = Session::builder()?.commit_from_file("tests/data/upsample.onnx")?;
+		let input_name = session.inputs[0].name.clone();
+		let metrics: Arc<Mutex<Vec<BatchMetrics>>> = Arc::default();
+		let batcher = DynamicBatcher::builder(session)
+			.with_max_batch_size(4)
+			.with_max_delay(std::time::Duration::from_millis(50))
+			.with_padding(input_name, -1.0_f32)
+			.with_metrics_callback({
+				let metrics = Arc::clone(&metrics);
+				move |m| metrics.lock().expect("poisoned").push(m.clone())
+			})
+			.build()?;
+
+		thread::scope(|s| -> crate::Result<()> {
+			let handles: Vec<_> = (0..8)
+				.map(|i| {
+					let batcher = &batcher;
+					s.spawn(move || -> crate::Result<()> {
+						// ragged height: 4 or 5
+						let height = 4 + i % 2;
+						let input = ndarray::Array4::<f32>::from_elem((1, height, 4, 3), i as f32);
+						let outputs = batcher.run(crate::inputs![TensorRef::from_array_view(&input)?])?;
+						let (shape, data) = outputs[0].try_extract_tensor::<f32>()?;
+						assert_eq!(shape[0], 1);
+						assert!(shape[1] >= height as i64 * 2);
+						assert_eq!(&shape[2..], [8, 3]);
+						// the first row is never padding, so should contain this request's value
+						assert!(data[..8 * 3].iter().all(|&x| x == i as f32));
+						Ok(())
+					})
+				})
+				.collect();
+			for handle in handles {
+				handle.join().expect("thread panicked")?;
+			}
+			Ok(())
+		})?;
+
+		let metrics = metrics.lock().expect("poisoned");
+		assert_eq!(metrics.iter().map(|m| m.requests).sum::<usize>(), 8);
+		assert!(metrics.iter().all(|m| m.batch_size <= 4));
+		Ok(())
+	}
+}
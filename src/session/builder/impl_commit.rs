@@ -153,16 +153,93 @@ impl SessionBuilder {
 		if let Some(thread_manager) = self.thread_manager.take() {
 			extras.push(Box::new(thread_manager) as Box<dyn Any>);
 		}
+		// ONNX Runtime does not copy external initializers, so they must outlive the session. They are `Arc`s shared with
+		// clones of this builder, since the session (and thus its references) may be dropped on any thread.
+		extras.extend(self.external_initializers.drain(..).map(|v| Box::new(v) as Box<dyn Any>));
+		extras.extend(self.external_initializer_buffers.drain(..).map(|b| Box::new(b) as Box<dyn Any>));
 
-		Ok(Session {
+		let session = Session {
 			inner: Arc::new(SharedSessionInner {
 				session_ptr,
 				allocator,
 				_extras: extras
 			}),
 			inputs,
-			outputs
-		})
+			outputs,
+			validate_inputs: self.validate_inputs,
+			cast_inputs: self.cast_inputs,
+			warmup_report: None
+		};
+		self.warm_up(session)
+	}
+
+	/// Memory-maps an ONNX or ORT format model from a file and builds the session.
+	///
+	/// The memory savings apply to initializers stored as external data: each external data file the model references
+	/// is mapped and handed to ONNX Runtime, which uses the initializers directly from the mapping instead of reading
+	/// them into memory. The model itself is still parsed into ONNX Runtime's own memory, so a `.onnx` model with
+	/// embedded initializers uses about as much memory as with [`SessionBuilder::commit_from_file`]. The mappings are
+	/// kept alive for as long as the session is.
+	///
+	/// External data files are looked up relative to the model's directory.
+	///
+	/// # Safety
+	/// Although this function is safe to call, the model file (and any external data files) **must not be modified**
+	/// while the session is alive, as this is undefined behavior. To update a model in place, write the new model to a
+	/// different file and move it over the old one instead; see also [`ReloadableSession`].
+	///
+	/// ```
+	/// # use ort::session::Session;
+	/// # fn main() -> ort::Result<()> {
+	/// let session = Session::builder()?.commit_from_mmap("tests/data/upsample.ort")?;
+	/// # 	Ok(())
+	/// # }
+	/// ```
+	///
+	/// [`ReloadableSession`]: crate::session::ReloadableSession
+	#[cfg(feature = "mmap")]
+	#[cfg_attr(docsrs, doc(cfg(feature = "mmap")))]
+	pub fn commit_from_mmap(self, model_filepath: impl AsRef<Path>) -> Result<Session> {
+		self.commit_from_mmap_inner(model_filepath.as_ref())
+	}
+
+	#[cfg(feature = "mmap")]
+	fn commit_from_mmap_inner(mut self, model_filepath: &Path) -> Result<Session> {
+		let map = |path: &Path| -> Result<memmap2::Mmap> {
+			let file =
+				std::fs::File::open(path).map_err(|e| Error::new_with_code(ErrorCode::NoSuchFile, format!("Failed to open `{}`: {e}", path.display())))?;
+			// SAFETY: see the safety section of `commit_from_mmap`'s documentation
+			unsafe { memmap2::Mmap::map(&file) }.map_err(|e| Error::new(format!("Failed to memory-map `{}`: {e}", path.display())))
+		};
+
+		let model = map(model_filepath)?;
+		let mut mappings: Vec<memmap2::Mmap> = Vec::new();
+		if let Some(model_dir) = model_filepath.parent() {
+			// External data not mapped below (e.g. because we couldn't find it) will instead be read by ONNX Runtime.
+			self.add_config_entry("session.model_external_initializers_file_folder_path", &model_dir.to_string_lossy())?;
+
+			for location in crate::value::external_data_locations(&model)? {
+				let path = model_dir.join(&location);
+				if !path.is_file() {
+					continue;
+				}
+				let data = map(&path)?;
+				let file_name = crate::util::path_to_os_char(&location);
+				let sizes = [data.len()];
+				ortsys![unsafe AddExternalInitializersFromMemory(self.ptr_mut(), &file_name.as_ptr(), &data.as_ptr().cast::<core::ffi::c_char>().cast_mut(), sizes.as_ptr(), 1)?];
+				mappings.push(data);
+			}
+		}
+
+		// Only applies to `.ort` models; allows us to use the mapped bytes without copying.
+		self.add_config_entry("session.use_ort_model_bytes_directly", "1")?;
+		self.add_config_entry("session.use_ort_model_bytes_for_initializers", "1")?;
+
+		let mut session = self.commit_from_memory(&model)?;
+		let inner = Arc::get_mut(&mut session.inner).expect("newly created session should not be shared");
+		inner._extras.push(Box::new(model));
+		inner._extras.extend(mappings.into_iter().map(|m| Box::new(m) as Box<dyn Any>));
+		Ok(session)
 	}
 
 	/// Load an ONNX graph from memory and commit the session
@@ -233,6 +310,10 @@ impl SessionBuilder {
 		if let Some(thread_manager) = self.thread_manager.take() {
 			extras.push(Box::new(thread_manager) as Box<dyn Any>);
 		}
+		// ONNX Runtime does not copy external initializers, so they must outlive the session. They are `Arc`s shared with
+		// clones of this builder, since the session (and thus its references) may be dropped on any thread.
+		extras.extend(self.external_initializers.drain(..).map(|v| Box::new(v) as Box<dyn Any>));
+		extras.extend(self.external_initializer_buffers.drain(..).map(|b| Box::new(b) as Box<dyn Any>));
 
 		let session = Session {
 			inner: Arc::new(SharedSessionInner {
@@ -241,8 +322,25 @@ impl SessionBuilder {
 				_extras: extras
 			}),
 			inputs,
-			outputs
+			outputs,
+			validate_inputs: self.validate_inputs,
+			cast_inputs: self.cast_inputs,
+			#[cfg(feature = "std")]
+			warmup_report: None
 		};
+		self.warm_up(session)
+	}
+
+	#[cfg(feature = "std")]
+	fn warm_up(&self, mut session: Session) -> Result<Session> {
+		if let Some(warmup) = &self.warmup {
+			session.warmup_report = Some(session.warmup(warmup)?);
+		}
+		Ok(session)
+	}
+
+	#[cfg(not(feature = "std"))]
+	fn warm_up(&self, session: Session) -> Result<Session> {
 		Ok(session)
 	}
 }
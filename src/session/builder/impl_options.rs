@@ -1,11 +1,9 @@
-use alloc::{rc::Rc, sync::Arc};
+use alloc::sync::Arc;
 use core::{any::Any, ffi::c_void, ptr};
 #[cfg(feature = "std")]
 use std::{borrow::Cow, path::Path};
 
 use super::SessionBuilder;
-#[cfg(feature = "std")]
-use crate::util::path_to_os_char;
 use crate::{
 	AsPointer,
 	environment::{self, ThreadManager},
@@ -17,6 +15,8 @@ use crate::{
 	util::with_cstr,
 	value::DynValue
 };
+#[cfg(feature = "std")]
+use crate::{session::warmup::Warmup, util::path_to_os_char};
 
 impl SessionBuilder {
 	/// Registers a list of execution providers for this session. Execution providers are registered in the order they
@@ -120,7 +120,7 @@ impl SessionBuilder {
 	///
 	/// If not provided, the session is created using ONNX Runtime's default device allocator.
 	pub fn with_allocator(mut self, info: MemoryInfo) -> Result<Self> {
-		self.memory_info = Some(Rc::new(info));
+		self.memory_info = Some(Arc::new(info));
 		Ok(self)
 	}
 
@@ -157,7 +157,7 @@ impl SessionBuilder {
 
 	pub fn with_external_initializer(mut self, name: impl AsRef<str>, value: DynValue) -> Result<Self> {
 		let ptr = self.ptr_mut();
-		let value = Rc::new(value);
+		let value = Arc::new(value);
 		with_cstr(name.as_ref().as_bytes(), &|name| {
 			ortsys![unsafe AddExternalInitializers(ptr, &name.as_ptr(), &value.ptr(), 1)?];
 			Ok(())
@@ -215,12 +215,105 @@ impl SessionBuilder {
 		Ok(self)
 	}
 
+	/// Enables or disables validation of inputs passed to [`Session::run`](crate::session::Session::run) and friends.
+	///
+	/// When enabled, the names, types, and shapes of inputs are checked against [`Session::inputs`] before each run,
+	/// including checking that named symbolic dimensions (like `batch_size`) have the same size across all inputs. A
+	/// failed check returns an error with code [`ErrorCode::InputMismatch`], whose details can be obtained via
+	/// [`Error::input_mismatch`]. Without validation, such errors are only caught by ONNX Runtime, which reports them
+	/// with a less descriptive message.
+	///
+	/// Validation is disabled by default, since it adds a small overhead to every run.
+	///
+	/// ```
+	/// # use ort::{session::Session, value::Tensor, ErrorCode};
+	/// # fn main() -> ort::Result<()> {
+	/// let session = Session::builder()?
+	/// 	.with_input_validation(true)?
+	/// 	.commit_from_file("tests/data/upsample.onnx")?;
+	///
+	/// // The model expects a rank-4 `f32` tensor.
+	/// let input = Tensor::<i64>::from_array(([1usize, 64, 64, 3], vec![0; 64 * 64 * 3]))?;
+	/// let error = session.run(ort::inputs![input]).unwrap_err();
+	/// assert_eq!(error.code(), ErrorCode::InputMismatch);
+	/// assert_eq!(error.input_mismatch().unwrap().input, "up_sampling2d_input:0");
+	/// # 	Ok(())
+	/// # }
+	/// ```
+	///
+	/// [`Session::inputs`]: crate::session::Session::inputs
+	/// [`ErrorCode::InputMismatch`]: crate::ErrorCode::InputMismatch
+	/// [`Error::input_mismatch`]: crate::Error::input_mismatch
+	pub fn with_input_validation(mut self, enable: bool) -> Result<Self> {
+		self.validate_inputs = enable;
+		Ok(self)
+	}
+
+	/// Enables or disables automatic casting of inputs passed to [`Session::run`](crate::session::Session::run) and
+	/// friends.
+	///
+	/// When enabled, tensor inputs whose element type differs from the one declared in [`Session::inputs`] are cast to
+	/// the declared type before each run, following the rules of [`DynTensor::cast`]. This makes it possible to e.g.
+	/// feed an `f16` model with `f32` data, or pass `i32` token IDs to a model expecting `i64`. Casting copies the
+	/// input's data, so it adds overhead to every run with a mismatched input; inputs which already have the right
+	/// element type are passed through as-is. String tensors are never cast.
+	///
+	/// Casting happens before [input validation](SessionBuilder::with_input_validation), if enabled. Casting is
+	/// disabled by default.
+	///
+	/// ```
+	/// # use ort::{session::Session, value::Tensor};
+	/// # fn main() -> ort::Result<()> {
+	/// let session = Session::builder()?
+	/// 	.with_input_casting(true)?
+	/// 	.commit_from_file("tests/data/upsample.onnx")?;
+	///
+	/// // The model expects an `f32` tensor.
+	/// let input = Tensor::<f64>::from_array(([1usize, 64, 64, 3], vec![0.0; 64 * 64 * 3]))?;
+	/// let outputs = session.run(ort::inputs![input])?;
+	/// # 	Ok(())
+	/// # }
+	/// ```
+	///
+	/// [`Session::inputs`]: crate::session::Session::inputs
+	/// [`DynTensor::cast`]: crate::value::DynTensor::cast
+	pub fn with_input_casting(mut self, enable: bool) -> Result<Self> {
+		self.cast_inputs = enable;
+		Ok(self)
+	}
+
+	/// Warms up the session before it is returned from `commit_*`, by running it on synthetic inputs as configured by
+	/// `warmup`. This moves the overhead of the first few runs (allocations, weight prepacking, kernel compilation) to
+	/// session creation, so that the first real request isn't unusually slow.
+	///
+	/// The time taken by each warmup run is available via [`Session::warmup_report`]. If a warmup run fails, the error
+	/// is returned from `commit_*`.
+	///
+	/// ```
+	/// # use ort::session::{warmup::Warmup, Session};
+	/// # fn main() -> ort::Result<()> {
+	/// let session = Session::builder()?
+	/// 	.with_warmup(Warmup::new(5))?
+	/// 	.commit_from_file("tests/data/upsample.onnx")?;
+	/// println!("{:?}", session.warmup_report());
+	/// # 	Ok(())
+	/// # }
+	/// ```
+	///
+	/// [`Session::warmup_report`]: crate::session::Session::warmup_report
+	#[cfg(feature = "std")]
+	#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+	pub fn with_warmup(mut self, warmup: Warmup) -> Result<Self> {
+		self.warmup = Some(warmup);
+		Ok(self)
+	}
+
 	pub fn with_thread_manager<T: ThreadManager + Any + 'static>(mut self, manager: T) -> Result<Self> {
-		let manager = Rc::new(manager);
+		let manager = Arc::new(manager);
 		ortsys![unsafe SessionOptionsSetCustomThreadCreationOptions(self.ptr_mut(), (&*manager as *const T) as *mut c_void)?];
 		ortsys![unsafe SessionOptionsSetCustomCreateThreadFn(self.ptr_mut(), Some(environment::thread_create::<T>))?];
 		ortsys![unsafe SessionOptionsSetCustomJoinThreadFn(self.ptr_mut(), Some(environment::thread_join::<T>))?];
-		self.thread_manager = Some(manager as Rc<dyn Any>);
+		self.thread_manager = Some(manager as Arc<dyn Any>);
 		Ok(self)
 	}
 }
@@ -0,0 +1,186 @@
+use alloc::{
+	borrow::Cow,
+	boxed::Box,
+	format,
+	string::{String, ToString},
+	sync::Arc,
+	vec::Vec
+};
+use core::{ops::Deref, ptr};
+use std::path::{Path, PathBuf};
+
+use safetensors::{Dtype, SafeTensors};
+
+use super::SessionBuilder;
+use crate::{
+	error::{Error, ErrorCode, Result},
+	memory::{Allocator, MemoryInfo},
+	tensor::{Shape, TensorElementType},
+	value::{DynTensor, tensor_from_array}
+};
+
+/// The source of a [safetensors](https://huggingface.co/docs/safetensors) file passed to
+/// [`SessionBuilder::with_safetensors_initializers`]; either a path to a file, which will be memory-mapped, or an
+/// in-memory buffer.
+#[derive(Debug, Clone)]
+pub enum SafeTensorsSource {
+	File(PathBuf),
+	Memory(Cow<'static, [u8]>)
+}
+
+impl From<&Path> for SafeTensorsSource {
+	fn from(value: &Path) -> Self {
+		Self::File(value.to_path_buf())
+	}
+}
+
+impl From<PathBuf> for SafeTensorsSource {
+	fn from(value: PathBuf) -> Self {
+		Self::File(value)
+	}
+}
+
+impl From<&str> for SafeTensorsSource {
+	fn from(value: &str) -> Self {
+		Self::File(PathBuf::from(value))
+	}
+}
+
+impl From<&'static [u8]> for SafeTensorsSource {
+	fn from(value: &'static [u8]) -> Self {
+		Self::Memory(Cow::Borrowed(value))
+	}
+}
+
+impl From<Vec<u8>> for SafeTensorsSource {
+	fn from(value: Vec<u8>) -> Self {
+		Self::Memory(Cow::Owned(value))
+	}
+}
+
+impl From<Cow<'static, [u8]>> for SafeTensorsSource {
+	fn from(value: Cow<'static, [u8]>) -> Self {
+		Self::Memory(value)
+	}
+}
+
+enum Buffer {
+	Mapped(memmap2::Mmap),
+	Memory(Cow<'static, [u8]>)
+}
+
+impl Deref for Buffer {
+	type Target = [u8];
+
+	fn deref(&self) -> &Self::Target {
+		match self {
+			Self::Mapped(map) => map,
+			Self::Memory(buffer) => buffer
+		}
+	}
+}
+
+impl SessionBuilder {
+	/// Adds every tensor in a [safetensors](https://huggingface.co/docs/safetensors) file as an external initializer,
+	/// allowing a graph to be shipped without its weights.
+	///
+	/// `source` can be a path to a file, which will be memory-mapped, or an in-memory buffer; see
+	/// [`SafeTensorsSource`]. Tensors are created directly on top of the file's data rather than being copied, and the
+	/// backing buffer is kept alive for as long as the session is.
+	///
+	/// ```no_run
+	/// # use ort::session::Session;
+	/// # fn main() -> ort::Result<()> {
+	/// let session = Session::builder()?
+	/// 	.with_safetensors_initializers("model.safetensors")?
+	/// 	.commit_from_file("model.onnx")?;
+	/// # 	Ok(())
+	/// # }
+	/// ```
+	///
+	/// # Safety
+	/// Although this function is safe to call, if `source` is a file, it **must not be modified** while the session is
+	/// alive, as this is undefined behavior.
+	pub fn with_safetensors_initializers(self, source: impl Into<SafeTensorsSource>) -> Result<Self> {
+		self.with_safetensors_initializers_renamed(source, |name| Some(name.to_string()))
+	}
+
+	/// Adds the tensors in a [safetensors](https://huggingface.co/docs/safetensors) file as external initializers,
+	/// like [`SessionBuilder::with_safetensors_initializers`], but with the names of initializers determined by
+	/// `rename`. Tensors for which `rename` returns `None` are skipped.
+	///
+	/// ```no_run
+	/// # use ort::session::Session;
+	/// # fn main() -> ort::Result<()> {
+	/// let session = Session::builder()?
+	/// 	.with_safetensors_initializers_renamed("model.safetensors", |name| {
+	/// 		name.strip_prefix("model.").map(str::to_string)
+	/// 	})?
+	/// 	.commit_from_file("model.onnx")?;
+	/// # 	Ok(())
+	/// # }
+	/// ```
+	pub fn with_safetensors_initializers_renamed(
+		mut self,
+		source: impl Into<SafeTensorsSource>,
+		mut rename: impl FnMut(&str) -> Option<String>
+	) -> Result<Self> {
+		let buffer = Arc::new(match source.into() {
+			SafeTensorsSource::File(path) => {
+				let file =
+					std::fs::File::open(&path).map_err(|e| Error::new_with_code(ErrorCode::NoSuchFile, format!("Failed to open `{}`: {e}", path.display())))?;
+				// SAFETY: see the safety section of this function's documentation
+				Buffer::Mapped(unsafe { memmap2::Mmap::map(&file) }.map_err(|e| Error::new(format!("Failed to memory-map `{}`: {e}", path.display())))?)
+			}
+			SafeTensorsSource::Memory(buffer) => Buffer::Memory(buffer)
+		});
+
+		let tensors = SafeTensors::deserialize(&buffer)
+			.map_err(|e| Error::new_with_code(ErrorCode::InvalidArgument, format!("Failed to parse safetensors header: {e}")))?;
+		for (name, view) in tensors.iter() {
+			let Some(initializer_name) = rename(name) else {
+				continue;
+			};
+			let ty = element_type(view.dtype()).ok_or_else(|| {
+				Error::new_with_code(ErrorCode::NotImplemented, format!("Tensor `{name}` has unsupported safetensors dtype {:?}", view.dtype()))
+			})?;
+			let shape = Shape::from(view.shape());
+			let data = view.data();
+
+			// ONNX Runtime expects tensor data to be aligned to its element size. Files written by the `safetensors`
+			// library always are, but others may not be, in which case we have to copy the data instead.
+			let tensor = if data.as_ptr().align_offset(ty.byte_size(1)) == 0 {
+				tensor_from_array(MemoryInfo::default(), shape, data.as_ptr().cast_mut().cast(), ty.byte_size(1), ty, Some(Box::new(Arc::clone(&buffer))))?
+			} else {
+				let mut tensor = DynTensor::new(&Allocator::default(), ty, shape)?;
+				if !data.is_empty() {
+					unsafe { ptr::copy_nonoverlapping(data.as_ptr(), tensor.data_ptr_mut()?.cast::<u8>(), data.len()) };
+				}
+				tensor
+			};
+			self = self.with_external_initializer(initializer_name, tensor.into_dyn())?;
+		}
+		Ok(self)
+	}
+}
+
+fn element_type(dtype: Dtype) -> Option<TensorElementType> {
+	Some(match dtype {
+		Dtype::BOOL => TensorElementType::Bool,
+		Dtype::U8 => TensorElementType::Uint8,
+		Dtype::I8 => TensorElementType::Int8,
+		Dtype::F8_E5M2 => TensorElementType::Float8E5M2,
+		Dtype::F8_E4M3 => TensorElementType::Float8E4M3FN,
+		Dtype::I16 => TensorElementType::Int16,
+		Dtype::U16 => TensorElementType::Uint16,
+		Dtype::F16 => TensorElementType::Float16,
+		Dtype::BF16 => TensorElementType::Bfloat16,
+		Dtype::I32 => TensorElementType::Int32,
+		Dtype::U32 => TensorElementType::Uint32,
+		Dtype::F32 => TensorElementType::Float32,
+		Dtype::F64 => TensorElementType::Float64,
+		Dtype::I64 => TensorElementType::Int64,
+		Dtype::U64 => TensorElementType::Uint64,
+		_ => return None
+	})
+}
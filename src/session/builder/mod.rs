@@ -1,16 +1,23 @@
-use alloc::{borrow::Cow, rc::Rc, sync::Arc, vec::Vec};
+use alloc::{borrow::Cow, sync::Arc, vec::Vec};
 use core::{
 	any::Any,
 	ptr::{self, NonNull}
 };
 
+#[cfg(feature = "std")]
+use crate::session::warmup::Warmup;
 use crate::{AsPointer, error::Result, memory::MemoryInfo, operator::OperatorDomain, ortsys, util::with_cstr, value::DynValue};
 
 mod impl_commit;
 mod impl_config_keys;
 mod impl_options;
+#[cfg(feature = "safetensors")]
+mod impl_safetensors;
 
 pub use self::impl_options::{GraphOptimizationLevel, PrepackedWeights};
+#[cfg(feature = "safetensors")]
+#[cfg_attr(docsrs, doc(cfg(feature = "safetensors")))]
+pub use self::impl_safetensors::SafeTensorsSource;
 
 /// Creates a session using the builder pattern.
 ///
@@ -32,13 +39,17 @@ pub use self::impl_options::{GraphOptimizationLevel, PrepackedWeights};
 /// [`Session`]: crate::session::Session
 pub struct SessionBuilder {
 	session_options_ptr: NonNull<ort_sys::OrtSessionOptions>,
-	memory_info: Option<Rc<MemoryInfo>>,
+	memory_info: Option<Arc<MemoryInfo>>,
 	operator_domains: Vec<Arc<OperatorDomain>>,
-	external_initializers: Vec<Rc<DynValue>>,
+	external_initializers: Vec<Arc<DynValue>>,
 	external_initializer_buffers: Vec<Cow<'static, [u8]>>,
 	prepacked_weights: Option<PrepackedWeights>,
-	thread_manager: Option<Rc<dyn Any>>,
-	no_global_thread_pool: bool
+	thread_manager: Option<Arc<dyn Any>>,
+	no_global_thread_pool: bool,
+	validate_inputs: bool,
+	cast_inputs: bool,
+	#[cfg(feature = "std")]
+	warmup: Option<Warmup>
 }
 
 impl Clone for SessionBuilder {
@@ -57,7 +68,11 @@ impl Clone for SessionBuilder {
 			external_initializer_buffers: self.external_initializer_buffers.clone(),
 			prepacked_weights: self.prepacked_weights.clone(),
 			thread_manager: self.thread_manager.clone(),
-			no_global_thread_pool: self.no_global_thread_pool
+			no_global_thread_pool: self.no_global_thread_pool,
+			validate_inputs: self.validate_inputs,
+			cast_inputs: self.cast_inputs,
+			#[cfg(feature = "std")]
+			warmup: self.warmup.clone()
 		}
 	}
 }
@@ -93,7 +108,11 @@ impl SessionBuilder {
 			external_initializer_buffers: Vec::new(),
 			prepacked_weights: None,
 			thread_manager: None,
-			no_global_thread_pool: false
+			no_global_thread_pool: false,
+			validate_inputs: false,
+			cast_inputs: false,
+			#[cfg(feature = "std")]
+			warmup: None
 		})
 	}
 
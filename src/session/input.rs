@@ -88,7 +88,7 @@ impl<'v, const N: usize> From<[SessionInputValue<'v>; N]> for SessionInputs<'_,
 /// # use ndarray::Array1;
 /// # use ort::{value::Tensor, session::{builder::GraphOptimizationLevel, Session}};
 /// # fn main() -> Result<(), Box<dyn Error>> {
-/// # 	let mut session = Session::builder()?.commit_from_file("model.onnx")?;
+/// # 	let session = Session::builder()?.commit_from_file("model.onnx")?;
 /// let _ = session.run(ort::inputs![Tensor::from_array(([5], vec![1, 2, 3, 4, 5]))?])?;
 /// # 	Ok(())
 /// # }
@@ -101,7 +101,7 @@ impl<'v, const N: usize> From<[SessionInputValue<'v>; N]> for SessionInputs<'_,
 /// # use ndarray::Array1;
 /// # use ort::{value::Tensor, session::{builder::GraphOptimizationLevel, Session}};
 /// # fn main() -> Result<(), Box<dyn Error>> {
-/// # 	let mut session = Session::builder()?.commit_from_file("model.onnx")?;
+/// # 	let session = Session::builder()?.commit_from_file("model.onnx")?;
 /// let _ = session.run(ort::inputs! {
 /// 	"tokens" => Tensor::from_array(([5], vec![1, 2, 3, 4, 5]))?
 /// })?;
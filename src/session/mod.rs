@@ -3,7 +3,7 @@
 //! ```
 //! # use ort::{session::Session, value::TensorRef};
 //! # fn main() -> ort::Result<()> {
-//! let mut session = Session::builder()?.commit_from_file("tests/data/upsample.onnx")?;
+//! let session = Session::builder()?.commit_from_file("tests/data/upsample.onnx")?;
 //! let input = ndarray::Array4::<f32>::zeros((1, 64, 64, 3));
 //! let outputs = session.run(ort::inputs![TensorRef::from_array_view(&input)?])?;
 //! # 	Ok(())
@@ -21,7 +21,10 @@ use core::{
 	slice
 };
 #[cfg(feature = "std")]
-use std::ffi::CString;
+use std::{
+	ffi::CString,
+	time::{Duration, Instant}
+};
 
 use smallvec::SmallVec;
 
@@ -36,21 +39,52 @@ use crate::{
 	value::{DynValue, Value, ValueType}
 };
 
+#[cfg(feature = "arrow")]
+mod arrow;
 #[cfg(feature = "std")]
 mod r#async;
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub mod batching;
 pub mod builder;
 pub mod input;
 pub mod output;
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub mod pool;
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub mod reload;
 pub mod run_options;
+pub mod stateful;
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub mod test_data;
+pub mod typed;
+mod validation;
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub mod warmup;
+#[cfg(feature = "std")]
+mod watchdog;
 #[cfg(feature = "std")]
 pub use self::r#async::InferenceFut;
 #[cfg(feature = "std")]
 use self::r#async::{AsyncInferenceContext, InferenceFutInner};
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub use self::pool::{PooledSession, SessionPool};
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub use self::reload::ReloadableSession;
+#[cfg(feature = "std")]
+use self::watchdog::Watchdog;
 use self::{builder::SessionBuilder, run_options::UntypedRunOptions};
 pub use self::{
 	input::{SessionInputValue, SessionInputs},
 	output::SessionOutputs,
-	run_options::{HasSelectedOutputs, NoSelectedOutputs, RunOptions, SelectedOutputMarker}
+	run_options::{HasSelectedOutputs, NoSelectedOutputs, RunOptions, SelectedOutputMarker},
+	typed::TypedSession
 };
 
 /// Holds onto an [`ort_sys::OrtSession`] pointer and its associated allocator.
@@ -89,7 +123,7 @@ impl Drop for SharedSessionInner {
 /// ```
 /// # use ort::{session::Session, value::TensorRef};
 /// # fn main() -> ort::Result<()> {
-/// let mut session = Session::builder()?.commit_from_file("tests/data/upsample.onnx")?;
+/// let session = Session::builder()?.commit_from_file("tests/data/upsample.onnx")?;
 /// let input = ndarray::Array4::<f32>::zeros((1, 64, 64, 3));
 /// let outputs = session.run(ort::inputs![TensorRef::from_array_view(&input)?])?;
 /// # 	Ok(())
@@ -101,7 +135,11 @@ pub struct Session {
 	/// Information about the graph's inputs.
 	pub inputs: Vec<Input>,
 	/// Information about the graph's outputs.
-	pub outputs: Vec<Output>
+	pub outputs: Vec<Output>,
+	validate_inputs: bool,
+	cast_inputs: bool,
+	#[cfg(feature = "std")]
+	warmup_report: Option<warmup::WarmupReport>
 }
 
 /// A [`Session`] where the graph data is stored in memory.
@@ -189,23 +227,43 @@ impl Session {
 			.collect()
 	}
 
+	/// Creates a [`TypedSession`] handle which runs this session with the strongly-typed inputs `I` & outputs `O`.
+	///
+	/// The names, element types, and ranks of the fields of `I` and `O` are validated against [`Session::inputs`] and
+	/// [`Session::outputs`] once here, so that the returned handle can run the model without any string lookups. See
+	/// [`typed`] for more details.
+	pub fn typed<I: typed::ModelInputs, O: typed::ModelOutputs>(&self) -> Result<TypedSession<'_, I, O>> {
+		TypedSession::new(self)
+	}
+
 	/// Run input data through the ONNX graph, performing inference.
 	///
 	/// See [`crate::inputs!`] for a convenient macro which will help you create your session inputs from `ndarray`s or
 	/// other data. You can also provide a `Vec`, array, or `HashMap` of [`Value`]s if you create your inputs
 	/// dynamically.
 	///
+	/// `run` only requires a shared reference to the session, so a single session can be shared between threads (e.g.
+	/// via an [`Arc`]) and used to run multiple inferences concurrently. To limit the number of concurrent runs, or to
+	/// spread runs across multiple sessions, see [`SessionPool`].
+	///
+	/// # Concurrent runs & execution providers
+	/// Running a session from multiple threads at once is safe with the CPU execution provider. Other execution
+	/// providers may not support it: DirectML only allows one thread to run a session at a time, and concurrent runs
+	/// have been known to segfault with CUDA and others. Sessions using these EPs should only be run by one thread at a
+	/// time, e.g. by creating a [`SessionPool`] with [`SessionPool::new`], which gives each run exclusive use of a
+	/// session.
+	///
 	/// ```
 	/// # use std::sync::Arc;
 	/// # use ort::{session::{run_options::RunOptions, Session}, tensor::TensorElementType, value::{Value, ValueType, TensorRef}};
 	/// # fn main() -> ort::Result<()> {
-	/// let mut session = Session::builder()?.commit_from_file("tests/data/upsample.onnx")?;
+	/// let session = Session::builder()?.commit_from_file("tests/data/upsample.onnx")?;
 	/// let input = ndarray::Array4::<f32>::zeros((1, 64, 64, 3));
 	/// let outputs = session.run(ort::inputs![TensorRef::from_array_view(&input)?])?;
 	/// # 	Ok(())
 	/// # }
 	/// ```
-	pub fn run<'s, 'i, 'v: 'i, const N: usize>(&'s mut self, input_values: impl Into<SessionInputs<'i, 'v, N>>) -> Result<SessionOutputs<'s, 's>> {
+	pub fn run<'s, 'i, 'v: 'i, const N: usize>(&'s self, input_values: impl Into<SessionInputs<'i, 'v, N>>) -> Result<SessionOutputs<'s, 's>> {
 		match input_values.into() {
 			SessionInputs::ValueSlice(input_values) => {
 				self.run_inner(self.inputs.iter().map(|input| input.name.as_str()).collect(), input_values.iter().collect(), None)
@@ -227,7 +285,7 @@ impl Session {
 	/// # use std::sync::Arc;
 	/// # use ort::{session::{Session, run_options::RunOptions}, value::{Value, ValueType, TensorRef}, tensor::TensorElementType};
 	/// # fn main() -> ort::Result<()> {
-	/// # 	let mut session = Session::builder()?.commit_from_file("tests/data/upsample.onnx")?;
+	/// # 	let session = Session::builder()?.commit_from_file("tests/data/upsample.onnx")?;
 	/// # 	let input = Value::from_array(ndarray::Array4::<f32>::zeros((1, 64, 64, 3)))?;
 	/// let run_options = Arc::new(RunOptions::new()?);
 	///
@@ -246,7 +304,7 @@ impl Session {
 	/// # }
 	/// ```
 	pub fn run_with_options<'r, 's: 'r, 'i, 'v: 'i, O: SelectedOutputMarker, const N: usize>(
-		&'s mut self,
+		&'s self,
 		input_values: impl Into<SessionInputs<'i, 'v, N>>,
 		run_options: &'r RunOptions<O>
 	) -> Result<SessionOutputs<'r, 's>> {
@@ -263,11 +321,90 @@ impl Session {
 		}
 	}
 
+	/// Run input data through the ONNX graph, terminating the run if it does not complete within `timeout`.
+	///
+	/// If the run is terminated, an error with code [`ErrorCode::Timeout`] is returned. Note that ONNX Runtime only
+	/// checks for termination between nodes, so a run may overshoot its timeout by up to the duration of a single
+	/// operator.
+	///
+	/// Deadlines are enforced by a single watchdog thread shared by all sessions, which is spawned on the first call to
+	/// this function (or [`Session::run_async_with_deadline`]) and lives for the rest of the process.
+	///
+	/// ```
+	/// # use std::time::Duration;
+	/// # use ort::{session::Session, value::TensorRef, ErrorCode};
+	/// # fn main() -> ort::Result<()> {
+	/// let session = Session::builder()?.commit_from_file("tests/data/upsample.onnx")?;
+	/// let input = ndarray::Array4::<f32>::zeros((1, 64, 64, 3));
+	/// match session.run_with_timeout(ort::inputs![TensorRef::from_array_view(&input)?], Duration::from_secs(1)) {
+	/// 	Ok(outputs) => {
+	/// 		// ...
+	/// 	}
+	/// 	Err(e) if e.code() == ErrorCode::Timeout => {
+	/// 		eprintln!("inference took too long!");
+	/// 	}
+	/// 	Err(e) => return Err(e)
+	/// }
+	/// # 	Ok(())
+	/// # }
+	/// ```
+	#[cfg(feature = "std")]
+	#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+	pub fn run_with_timeout<'s, 'i, 'v: 'i, const N: usize>(
+		&'s self,
+		input_values: impl Into<SessionInputs<'i, 'v, N>>,
+		timeout: Duration
+	) -> Result<SessionOutputs<'s, 's>> {
+		let run_options = RunOptions::new()?;
+		let watchdog = Watchdog::new(&run_options.inner, Instant::now() + timeout)?;
+		let input_values = input_values.into();
+		let (input_names, input_values) = self.split_inputs(&input_values);
+		let (output_names, output_tensors) = self.default_outputs();
+		let result = self.run_inner_with_outputs(input_names, input_values, output_names, output_tensors, Some(&run_options.inner));
+		watchdog.finish(result)
+	}
+
 	fn run_inner<'i, 'r, 's: 'r, 'v: 'i>(
 		&'s self,
 		input_names: SmallVec<&str, { STACK_SESSION_INPUTS }>,
 		input_values: SmallVec<&'i SessionInputValue<'v>, { STACK_SESSION_INPUTS }>,
 		run_options: Option<&'r UntypedRunOptions>
+	) -> Result<SessionOutputs<'r, 's>> {
+		let (output_names, output_tensors) = match run_options {
+			Some(r) => r.outputs.resolve_outputs(&self.outputs),
+			None => self.default_outputs()
+		};
+		self.run_inner_with_outputs(input_names, input_values, output_names, output_tensors, run_options)
+	}
+
+	fn output_type(&self, name: &str) -> Option<&ValueType> {
+		self.outputs.iter().find(|o| o.name == name).map(|o| &o.output_type)
+	}
+
+	#[cfg(feature = "std")]
+	/// Splits `input_values` into the names & values of each input, matching positional inputs to the session's inputs.
+	fn split_inputs<'a, 'v, const N: usize>(
+		&'a self,
+		input_values: &'a SessionInputs<'_, 'v, N>
+	) -> (SmallVec<&'a str, { STACK_SESSION_INPUTS }>, SmallVec<&'a SessionInputValue<'v>, { STACK_SESSION_INPUTS }>) {
+		match input_values {
+			SessionInputs::ValueSlice(input_values) => (self.inputs.iter().map(|input| input.name.as_str()).collect(), input_values.iter().collect()),
+			SessionInputs::ValueArray(input_values) => (self.inputs.iter().map(|input| input.name.as_str()).collect(), input_values.iter().collect()),
+			SessionInputs::ValueMap(input_values) => (input_values.iter().map(|(k, _)| k.as_ref()).collect(), input_values.iter().map(|(_, v)| v).collect())
+		}
+	}
+
+	fn default_outputs(&self) -> (SmallVec<&str, { STACK_SESSION_OUTPUTS }>, SmallVec<Option<DynValue>, { STACK_SESSION_OUTPUTS }>) {
+		(self.outputs.iter().map(|o| o.name.as_str()).collect(), iter::repeat_with(|| None).take(self.outputs.len()).collect())
+	}
+
+	fn run_inner_with_outputs<'i, 'r, 's: 'r, 'v: 'i>(
+		&'s self,
+		input_names: SmallVec<&str, { STACK_SESSION_INPUTS }>,
+		input_values: SmallVec<&'i SessionInputValue<'v>, { STACK_SESSION_INPUTS }>,
+		output_names: SmallVec<&'r str, { STACK_SESSION_OUTPUTS }>,
+		mut output_tensors: SmallVec<Option<DynValue>, { STACK_SESSION_OUTPUTS }>,
+		run_options: Option<&UntypedRunOptions>
 	) -> Result<SessionOutputs<'r, 's>> {
 		if input_values.len() > input_names.len() {
 			// If we provide more inputs than the model expects with `ort::inputs![a, b, c]`, then we get an `input_names` shorter
@@ -279,11 +416,21 @@ impl Session {
 				format!("{} inputs were provided, but the model only accepts {}.", input_values.len(), input_names.len())
 			));
 		}
-
-		let (output_names, mut output_tensors) = match run_options {
-			Some(r) => r.outputs.resolve_outputs(&self.outputs),
-			None => (self.outputs.iter().map(|o| o.name.as_str()).collect(), iter::repeat_with(|| None).take(self.outputs.len()).collect())
+		let cast_values = if self.cast_inputs {
+			validation::cast_inputs(&self.inputs, &input_names, &input_values)?
+		} else {
+			SmallVec::new()
 		};
+		let input_values: SmallVec<&SessionInputValue, { STACK_SESSION_INPUTS }> = input_values
+			.into_iter()
+			.enumerate()
+			.map(|(i, value)| cast_values.get(i).and_then(Option::as_ref).unwrap_or(value))
+			.collect();
+		if self.validate_inputs {
+			validation::validate_inputs(&self.inputs, input_names.iter().copied().zip(input_values.iter().map(|v| v.dtype())))?;
+		}
+		let (input_names, input_values) = omit_empty_optionals(input_names, input_values);
+
 		let output_value_ptrs: SmallVec<*mut ort_sys::OrtValue, { STACK_SESSION_OUTPUTS }> = output_tensors
 			.iter_mut()
 			.map(|c| match c {
@@ -317,15 +464,19 @@ impl Session {
 			.into_iter()
 			.enumerate()
 			.map(|(i, v)| match v {
-				Some(value) => value,
+				Some(value) => Ok(value),
 				None => unsafe {
-					Value::from_ptr(
+					Value::from_ptr_with_type(
 						NonNull::new(output_value_ptrs[i]).expect("OrtValue ptr returned from session Run should not be null"),
-						Some(Arc::clone(&self.inner))
+						Some(Arc::clone(&self.inner)),
+						self.output_type(output_names[i])
 					)
 				}
 			})
-			.collect();
+			// wrap every output before bailing on an error, so none are leaked
+			.collect::<SmallVec<Result<DynValue>, { STACK_SESSION_OUTPUTS }>>()
+			.into_iter()
+			.collect::<Result<_>>()?;
 
 		Ok(SessionOutputs::new(output_names, outputs))
 	}
@@ -360,12 +511,15 @@ impl Session {
 				.zip(binding.output_values.iter())
 				.map(|(ptr, (_, value))| unsafe {
 					if let Some(value) = value {
-						DynValue::clone_of(value)
+						Ok(DynValue::clone_of(value))
 					} else {
 						DynValue::from_ptr(NonNull::new(ptr).expect("OrtValue ptrs returned by GetBoundOutputValues should not be null"), Some(self.inner()))
 					}
 				})
-				.collect();
+				// wrap every output before bailing on an error, so none are leaked
+				.collect::<SmallVec<Result<DynValue>, { STACK_SESSION_OUTPUTS }>>()
+				.into_iter()
+				.collect::<Result<_>>()?;
 
 			// output values will be freed when the `Value`s in `SessionOutputs` drop
 
@@ -385,6 +539,9 @@ impl Session {
 	/// Inference will be performed on a thread in the session's thread pool. **Thus, the session must have been
 	/// configured to have multiple intra-op threads**; see [`SessionBuilder::with_intra_threads`].
 	///
+	/// Note that dropping the returned future before it resolves **blocks** until the run is terminated; see
+	/// [`InferenceFut`] for details.
+	///
 	/// See [`crate::inputs!`] for a convenient macro which will help you create your session inputs from `ndarray`s or
 	/// other data. You can also provide a `Vec`, array, or `HashMap` of [`Value`]s if you create your inputs
 	/// dynamically.
@@ -393,7 +550,7 @@ impl Session {
 	/// # use std::sync::Arc;
 	/// # use ort::{session::{Session, run_options::RunOptions}, value::{Value, ValueType, TensorRef}, tensor::TensorElementType};
 	/// # fn main() -> ort::Result<()> { tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap().block_on(async {
-	/// let mut session = Session::builder()?.with_intra_threads(2)?.commit_from_file("tests/data/upsample.onnx")?;
+	/// let session = Session::builder()?.with_intra_threads(2)?.commit_from_file("tests/data/upsample.onnx")?;
 	/// let input = ndarray::Array4::<f32>::zeros((1, 64, 64, 3));
 	/// let options = RunOptions::new()?;
 	/// let outputs = session.run_async(ort::inputs![TensorRef::from_array_view(&input)?], &options)?.await?;
@@ -403,7 +560,7 @@ impl Session {
 	#[cfg(feature = "std")]
 	#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
 	pub fn run_async<'r, 's: 'r, 'i, 'v: 'i + 's, O: SelectedOutputMarker, const N: usize>(
-		&'s mut self,
+		&'s self,
 		input_values: impl Into<SessionInputs<'i, 'v, N>>,
 		run_options: &'r RunOptions<O>
 	) -> Result<InferenceFut<'s, 'r, 'v>> {
@@ -420,6 +577,39 @@ impl Session {
 		}
 	}
 
+	/// Asynchronously run input data through the ONNX graph, terminating the run if it does not complete before
+	/// `deadline`.
+	///
+	/// If the run is terminated, the future resolves to an error with code [`ErrorCode::Timeout`]. See
+	/// [`Session::run_async`] and [`Session::run_with_timeout`] for more details.
+	///
+	/// Note that if the run is terminated, `run_options` will remain in the terminated state; call
+	/// [`RunOptions::unterminate`] before using it for another run.
+	///
+	/// ```
+	/// # use std::time::{Duration, Instant};
+	/// # use ort::{session::{Session, run_options::RunOptions}, value::TensorRef};
+	/// # fn main() -> ort::Result<()> { tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap().block_on(async {
+	/// let session = Session::builder()?.with_intra_threads(2)?.commit_from_file("tests/data/upsample.onnx")?;
+	/// let input = ndarray::Array4::<f32>::zeros((1, 64, 64, 3));
+	/// let options = RunOptions::new()?;
+	/// let deadline = Instant::now() + Duration::from_secs(1);
+	/// let outputs = session.run_async_with_deadline(ort::inputs![TensorRef::from_array_view(&input)?], &options, deadline)?.await?;
+	/// # 	Ok(())
+	/// # }) }
+	/// ```
+	#[cfg(feature = "std")]
+	#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+	pub fn run_async_with_deadline<'r, 's: 'r, 'i, 'v: 'i + 's, O: SelectedOutputMarker, const N: usize>(
+		&'s self,
+		input_values: impl Into<SessionInputs<'i, 'v, N>>,
+		run_options: &'r RunOptions<O>,
+		deadline: Instant
+	) -> Result<InferenceFut<'s, 'r, 'v>> {
+		let watchdog = Watchdog::new(&run_options.inner, deadline)?;
+		Ok(self.run_async(input_values, run_options)?.with_watchdog(watchdog))
+	}
+
 	#[cfg(feature = "std")]
 	fn run_inner_async<'i, 'r, 's: 'r, 'v: 'i + 's>(
 		&'s self,
@@ -427,6 +617,21 @@ impl Session {
 		input_values: SmallVec<&SessionInputValue<'v>, { STACK_SESSION_INPUTS }>,
 		run_options: &'r UntypedRunOptions
 	) -> Result<InferenceFut<'s, 'r, 'v>> {
+		let cast_values = if self.cast_inputs {
+			validation::cast_inputs(&self.inputs, &input_names, &input_values)?
+		} else {
+			SmallVec::new()
+		};
+		let input_values: SmallVec<&SessionInputValue, { STACK_SESSION_INPUTS }> = input_values
+			.into_iter()
+			.enumerate()
+			.map(|(i, value)| cast_values.get(i).and_then(Option::as_ref).unwrap_or(value))
+			.collect();
+		if self.validate_inputs {
+			validation::validate_inputs(&self.inputs, input_names.iter().copied().zip(input_values.iter().map(|v| v.dtype())))?;
+		}
+		let (input_names, input_values) = omit_empty_optionals(input_names, input_values);
+
 		let input_name_ptrs = input_names
 			.into_iter()
 			.map(|name| CString::new(name.as_bytes()).map(|s| s.into_raw().cast_const()))
@@ -444,6 +649,7 @@ impl Session {
 		}
 
 		let (output_names, mut output_tensors) = run_options.outputs.resolve_outputs(&self.outputs);
+		let output_types = output_names.iter().map(|name| self.output_type(name).cloned()).collect();
 		let output_name_ptrs = output_names
 			.iter()
 			.map(|n| CString::new(*n).unwrap_or_else(|_| unreachable!()))
@@ -470,8 +676,9 @@ impl Session {
 			input_name_ptrs,
 			output_name_ptrs,
 			output_names,
+			output_types,
 			output_value_ptrs: output_tensor_ptrs,
-			session_inner: &self.inner
+			session_inner: Arc::clone(&self.inner)
 		}));
 
 		ortsys![
@@ -546,6 +753,22 @@ impl Session {
 	}
 }
 
+/// Removes empty optionals created with [`Optional::none`](crate::value::Optional::none) from the inputs. ONNX Runtime
+/// has no way to create an empty optional value, but treats omitted optional inputs as empty.
+fn omit_empty_optionals<'n, 'i, 'v>(
+	input_names: SmallVec<&'n str, { STACK_SESSION_INPUTS }>,
+	input_values: SmallVec<&'i SessionInputValue<'v>, { STACK_SESSION_INPUTS }>
+) -> (SmallVec<&'n str, { STACK_SESSION_INPUTS }>, SmallVec<&'i SessionInputValue<'v>, { STACK_SESSION_INPUTS }>) {
+	if !input_values.iter().any(|v| v.is_none_placeholder()) {
+		return (input_names, input_values);
+	}
+	input_names
+		.into_iter()
+		.zip(input_values)
+		.filter(|(_, v)| !v.is_none_placeholder())
+		.unzip()
+}
+
 /// Workload type, used to signal to execution providers whether to prioritize performance or efficiency.
 ///
 /// See [`Session::set_workload_type`].
@@ -560,8 +783,9 @@ pub enum WorkloadType {
 
 // https://github.com/microsoft/onnxruntime/issues/114
 unsafe impl Send for Session {}
-// Allowing `Sync` segfaults with CUDA, DirectML, and seemingly any EP other than the CPU EP. I'm not certain if it's a
-// temporary bug in ONNX Runtime or a wontfix. Maybe this impl should be removed just to be safe?
+// `Sync` allows a session to be run concurrently through `&self`, which is safe with the CPU EP. Concurrent runs
+// segfault with CUDA, DirectML, and seemingly any EP other than the CPU EP, though; `Session::run` documents this and
+// points users of those EPs to `SessionPool`, which can give each run exclusive use of a session.
 unsafe impl Sync for Session {}
 
 impl AsPointer for Session {
@@ -0,0 +1,330 @@
+//! Contains [`SessionPool`], which distributes inference requests from many threads across one or more [`Session`]s.
+//!
+//! ```
+//! # use std::sync::Arc;
+//! # use ort::{session::{Session, SessionPool}, value::TensorRef};
+//! # fn main() -> ort::Result<()> {
+//! // Create a pool of 2 sessions, so at most 2 runs will be in flight at any one time.
+//! let pool = Arc::new(SessionPool::from_fn(2, |_| Session::builder()?.commit_from_file("tests/data/upsample.onnx"))?);
+//!
+//! let handles: Vec<_> = (0..4)
+//! 	.map(|_| {
+//! 		let pool = Arc::clone(&pool);
+//! 		std::thread::spawn(move || -> ort::Result<()> {
+//! 			let input = ndarray::Array4::<f32>::zeros((1, 64, 64, 3));
+//! 			let outputs = pool.run(ort::inputs![TensorRef::from_array_view(&input)?])?;
+//! 			let (shape, _) = outputs[0].try_extract_tensor::<f32>()?;
+//! 			assert_eq!(&**shape, [1, 128, 128, 3]);
+//! 			Ok(())
+//! 		})
+//! 	})
+//! 	.collect();
+//! for handle in handles {
+//! 	handle.join().unwrap()?;
+//! }
+//! # 	Ok(())
+//! # }
+//! ```
+
+use alloc::{format, vec::Vec};
+use core::{fmt, ops::Deref, time::Duration};
+use std::{
+	sync::{Condvar, Mutex, MutexGuard},
+	time::Instant
+};
+
+use super::{Input, Output, RunOptions, SelectedOutputMarker, Session, SessionInputs, SessionOutputs};
+use crate::error::{Error, ErrorCode, Result};
+
+/// A pool of [`Session`]s which can be shared between threads to run inference concurrently.
+///
+/// A pool can be created in one of two modes:
+/// - [`SessionPool::new`]/[`SessionPool::from_fn`] create a pool of `N` independent sessions. Each run acquires
+///   exclusive use of one of these sessions. This is the mode to use for execution providers which don't handle
+///   concurrent runs on a single session well (like CUDA or DirectML), or when each session is configured differently
+///   (i.e. pinned to a different device).
+/// - [`SessionPool::shared`] wraps a single session, and allows at most `N` runs to use it concurrently. Since only one
+///   copy of the model is loaded, this uses much less memory than the former.
+///
+/// In both modes, callers that can't immediately get a slot will wait until another run completes, providing
+/// backpressure when the pool is saturated. [`SessionPool::try_acquire`] and [`SessionPool::acquire_timeout`] can be
+/// used to avoid blocking indefinitely.
+pub struct SessionPool {
+	sessions: Vec<Session>,
+	capacity: usize,
+	/// Indices into `sessions` that are currently available. In shared mode, this contains `capacity` copies of `0`.
+	free: Mutex<Vec<usize>>,
+	released: Condvar
+}
+
+impl SessionPool {
+	/// Creates a pool from a list of sessions, where each session may only be used by one run at a time.
+	///
+	/// All sessions are expected to share the same inputs & outputs; an error is returned if they differ, or if no
+	/// sessions were provided.
+	pub fn new(sessions: impl IntoIterator<Item = Session>) -> Result<Self> {
+		let sessions: Vec<Session> = sessions.into_iter().collect();
+		let Some(first) = sessions.first() else {
+			return Err(Error::new_with_code(ErrorCode::InvalidArgument, "Cannot create a `SessionPool` with no sessions"));
+		};
+		for (i, session) in sessions.iter().enumerate().skip(1) {
+			if !signatures_match(&first.inputs, &first.outputs, session) {
+				return Err(Error::new_with_code(
+					ErrorCode::InvalidArgument,
+					format!("Session #{i} in `SessionPool` has different inputs or outputs than session #0")
+				));
+			}
+		}
+
+		let capacity = sessions.len();
+		Ok(Self {
+			sessions,
+			capacity,
+			free: Mutex::new((0..capacity).rev().collect()),
+			released: Condvar::new()
+		})
+	}
+
+	/// Creates a pool of `size` sessions, calling `f` with the index of each session to create it.
+	///
+	/// ```
+	/// # use ort::session::{Session, SessionPool};
+	/// # fn main() -> ort::Result<()> {
+	/// let builder = Session::builder()?.with_intra_threads(1)?;
+	/// let pool = SessionPool::from_fn(4, |_| builder.clone().commit_from_file("tests/data/upsample.onnx"))?;
+	/// assert_eq!(pool.capacity(), 4);
+	/// # 	Ok(())
+	/// # }
+	/// ```
+	pub fn from_fn(size: usize, f: impl FnMut(usize) -> Result<Session>) -> Result<Self> {
+		Self::new((0..size).map(f).collect::<Result<Vec<_>>>()?)
+	}
+
+	/// Creates a pool which shares a single session between at most `max_concurrent_runs` concurrent runs.
+	///
+	/// Note that the session should be configured with enough intra-op threads (or use a global/custom thread pool) to
+	/// actually benefit from running multiple inferences concurrently.
+	pub fn shared(session: Session, max_concurrent_runs: usize) -> Result<Self> {
+		if max_concurrent_runs == 0 {
+			return Err(Error::new_with_code(ErrorCode::InvalidArgument, "`SessionPool` must allow at least 1 concurrent run"));
+		}
+		Ok(Self {
+			sessions: alloc::vec![session],
+			capacity: max_concurrent_runs,
+			free: Mutex::new(alloc::vec![0; max_concurrent_runs]),
+			released: Condvar::new()
+		})
+	}
+
+	/// Returns the maximum number of runs that can be performed concurrently with this pool.
+	#[must_use]
+	pub fn capacity(&self) -> usize {
+		self.capacity
+	}
+
+	/// Returns the number of runs that could be started right now without waiting.
+	#[must_use]
+	pub fn available(&self) -> usize {
+		self.lock_free().len()
+	}
+
+	/// Returns the sessions owned by this pool. In shared mode, this contains only one session.
+	#[must_use]
+	pub fn sessions(&self) -> &[Session] {
+		&self.sessions
+	}
+
+	/// Information about the inputs of the pool's sessions.
+	#[must_use]
+	pub fn inputs(&self) -> &[Input] {
+		&self.sessions[0].inputs
+	}
+
+	/// Information about the outputs of the pool's sessions.
+	#[must_use]
+	pub fn outputs(&self) -> &[Output] {
+		&self.sessions[0].outputs
+	}
+
+	/// Acquires a session from the pool, blocking the current thread until one is available.
+	///
+	/// The session is returned to the pool when the returned [`PooledSession`] is dropped.
+	pub fn acquire(&self) -> PooledSession<'_> {
+		let mut free = self.lock_free();
+		loop {
+			if let Some(index) = free.pop() {
+				return PooledSession { pool: self, index };
+			}
+			free = self.released.wait(free).expect("Poisoned pool mutex");
+		}
+	}
+
+	/// Attempts to acquire a session from the pool without blocking. Returns `None` if all sessions are currently in
+	/// use.
+	pub fn try_acquire(&self) -> Option<PooledSession<'_>> {
+		self.lock_free().pop().map(|index| PooledSession { pool: self, index })
+	}
+
+	/// Acquires a session from the pool, blocking the current thread for at most `timeout` until one is available.
+	/// Returns `None` if no session became available in time.
+	pub fn acquire_timeout(&self, timeout: Duration) -> Option<PooledSession<'_>> {
+		let deadline = Instant::now() + timeout;
+		let mut free = self.lock_free();
+		loop {
+			if let Some(index) = free.pop() {
+				return Some(PooledSession { pool: self, index });
+			}
+			let remaining = deadline.checked_duration_since(Instant::now())?;
+			let (guard, res) = self.released.wait_timeout(free, remaining).expect("Poisoned pool mutex");
+			free = guard;
+			if res.timed_out() && free.is_empty() {
+				return None;
+			}
+		}
+	}
+
+	/// Run input data through the ONNX graph using the next available session, blocking until one is available.
+	///
+	/// The session is released back to the pool as soon as inference completes; the returned outputs do not hold a
+	/// slot in the pool.
+	///
+	/// See [`Session::run`] for more details.
+	pub fn run<'s, 'i, 'v: 'i, const N: usize>(&'s self, input_values: impl Into<SessionInputs<'i, 'v, N>>) -> Result<SessionOutputs<'s, 's>> {
+		let slot = self.acquire();
+		let session: &'s Session = &self.sessions[slot.index];
+		session.run(input_values)
+	}
+
+	/// Run input data through the ONNX graph using the next available session, with a [`RunOptions`] struct.
+	///
+	/// See [`Session::run_with_options`] for more details.
+	pub fn run_with_options<'r, 's: 'r, 'i, 'v: 'i, O: SelectedOutputMarker, const N: usize>(
+		&'s self,
+		input_values: impl Into<SessionInputs<'i, 'v, N>>,
+		run_options: &'r RunOptions<O>
+	) -> Result<SessionOutputs<'r, 's>> {
+		let slot = self.acquire();
+		let session: &'s Session = &self.sessions[slot.index];
+		session.run_with_options(input_values, run_options)
+	}
+
+	fn lock_free(&self) -> MutexGuard<'_, Vec<usize>> {
+		self.free.lock().expect("Poisoned pool mutex")
+	}
+
+	fn release(&self, index: usize) {
+		self.lock_free().push(index);
+		self.released.notify_one();
+	}
+}
+
+impl fmt::Debug for SessionPool {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("SessionPool")
+			.field("sessions", &self.sessions.len())
+			.field("capacity", &self.capacity)
+			.field("available", &self.available())
+			.finish()
+	}
+}
+
+fn signatures_match(inputs: &[Input], outputs: &[Output], session: &Session) -> bool {
+	inputs.len() == session.inputs.len()
+		&& outputs.len() == session.outputs.len()
+		&& inputs
+			.iter()
+			.zip(&session.inputs)
+			.all(|(a, b)| a.name == b.name && a.input_type == b.input_type)
+		&& outputs
+			.iter()
+			.zip(&session.outputs)
+			.all(|(a, b)| a.name == b.name && a.output_type == b.output_type)
+}
+
+/// A [`Session`] acquired from a [`SessionPool`]. The session is returned to the pool when this guard is dropped.
+///
+/// This type is automatically `Deref`'d into a `Session`, so you can use it like you would a regular `Session`.
+#[derive(Debug)]
+pub struct PooledSession<'p> {
+	pool: &'p SessionPool,
+	index: usize
+}
+
+impl PooledSession<'_> {
+	/// Returns the index of this session within [`SessionPool::sessions`].
+	#[must_use]
+	pub fn index(&self) -> usize {
+		self.index
+	}
+}
+
+impl Deref for PooledSession<'_> {
+	type Target = Session;
+	fn deref(&self) -> &Self::Target {
+		&self.pool.sessions[self.index]
+	}
+}
+
+impl Drop for PooledSession<'_> {
+	fn drop(&mut self) {
+		self.pool.release(self.index);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::{sync::Arc, thread, time::Duration};
+
+	use super::SessionPool;
+	use crate::{session::Session, value::TensorRef};
+
+	#[test]
+	fn test_pool_many_threads() -> crate::Result<()> {
+		let pool = Arc::new(SessionPool::from_fn(2, |_| Session::builder()?.commit_from_file("tests/data/upsample.onnx"))?);
+		assert_eq!(pool.capacity(), 2);
+
+		thread::scope(|s| -> crate::Result<()> {
+			let handles: Vec<_> = (0..16)
+				.map(|i| {
+					let pool = Arc::clone(&pool);
+					s.spawn(move || -> crate::Result<()> {
+						let input = ndarray::Array4::<f32>::from_elem((1, 8 + i, 8, 3), i as f32);
+						let outputs = pool.run(crate::inputs![TensorRef::from_array_view(&input)?])?;
+						let output = outputs[0].try_extract_array::<f32>()?;
+						assert_eq!(output.shape(), &[1, (8 + i) * 2, 16, 3]);
+						Ok(())
+					})
+				})
+				.collect();
+			for handle in handles {
+				handle.join().expect("thread panicked")?;
+			}
+			Ok(())
+		})?;
+
+		assert_eq!(pool.available(), 2);
+		Ok(())
+	}
+
+	#[test]
+	fn test_shared_pool_backpressure() -> crate::Result<()> {
+		let session = Session::builder()?.commit_from_file("tests/data/upsample.onnx")?;
+		let pool = SessionPool::shared(session, 2)?;
+		assert_eq!(pool.sessions().len(), 1);
+
+		let a = pool.acquire();
+		let b = pool.try_acquire().expect("pool should have a second slot");
+		assert!(pool.try_acquire().is_none());
+		assert!(pool.acquire_timeout(Duration::from_millis(10)).is_none());
+
+		// both slots refer to the same session
+		assert_eq!(a.index(), b.index());
+		assert_eq!(a.inputs.len(), 1);
+
+		drop(a);
+		assert!(pool.acquire_timeout(Duration::from_millis(10)).is_some());
+		drop(b);
+		assert_eq!(pool.available(), 2);
+		Ok(())
+	}
+}
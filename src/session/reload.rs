@@ -0,0 +1,300 @@
+//! Contains [`ReloadableSession`], which allows swapping the model behind a long-lived session without interrupting
+//! in-flight runs.
+//!
+//! ```no_run
+//! # use std::{sync::Arc, time::Duration};
+//! # use ort::{session::{ReloadableSession, Session}, value::TensorRef};
+//! # fn main() -> ort::Result<()> {
+//! let session = Arc::new(ReloadableSession::new(|| Session::builder()?.with_intra_threads(2), "model.onnx")?);
+//!
+//! // Check for changes to `model.onnx` every 10 seconds. The model is reloaded until `watcher` is dropped.
+//! let watcher = session.watch(Duration::from_secs(10))?;
+//!
+//! let input = ndarray::Array4::<f32>::zeros((1, 64, 64, 3));
+//! let current = session.session();
+//! let outputs = current.run(ort::inputs![TensorRef::from_array_view(&input)?])?;
+//! # 	Ok(())
+//! # }
+//! ```
+
+use alloc::{boxed::Box, format, string::String, sync::Arc};
+use core::{fmt, time::Duration};
+use std::{
+	fs,
+	path::{Path, PathBuf},
+	sync::{
+		Mutex, MutexGuard, RwLock, Weak,
+		atomic::{AtomicU64, Ordering},
+		mpsc::{self, RecvTimeoutError}
+	},
+	thread::{self, JoinHandle},
+	time::SystemTime
+};
+
+use super::{Session, builder::SessionBuilder};
+use crate::error::{Error, ErrorCode, Result};
+
+/// Identifies a version of a model file; if any of these change, the file is assumed to have changed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Fingerprint {
+	modified: Option<SystemTime>,
+	len: u64
+}
+
+impl Fingerprint {
+	fn of(path: &Path) -> Result<Self> {
+		let metadata =
+			fs::metadata(path).map_err(|e| Error::new_with_code(ErrorCode::NoSuchFile, format!("Failed to read metadata of `{}`: {e}", path.display())))?;
+		Ok(Self {
+			modified: metadata.modified().ok(),
+			len: metadata.len()
+		})
+	}
+}
+
+/// Creates the [`SessionBuilder`] each version of the model is committed with.
+type BuilderFn = dyn Fn() -> Result<SessionBuilder> + Send;
+
+struct Source {
+	builder: Box<BuilderFn>,
+	path: PathBuf,
+	fingerprint: Fingerprint,
+	/// The fingerprint of the file if it last failed to reload, so [`ReloadableSession::reload_if_changed`] doesn't
+	/// keep retrying a broken model.
+	rejected: Option<Fingerprint>
+}
+
+/// A [`Session`] whose model can be replaced while it is in use.
+///
+/// The current session is obtained with [`ReloadableSession::session`], which returns an [`Arc`] that keeps that
+/// version of the model alive for as long as it is held. When the model is reloaded, the new session is committed
+/// with a fresh [`SessionBuilder`] from the function the `ReloadableSession` was created with, and then atomically
+/// swapped in; runs which started on the old session continue to use it until they complete, and the old session is
+/// freed once the last reference to it is dropped.
+///
+/// The new model's inputs and outputs must match those of the current model (see [`ReloadableSession::reload`]), so
+/// callers can rely on the session's signature not changing from under them.
+///
+/// Reloads can be triggered manually with [`ReloadableSession::reload`] (or [`ReloadableSession::reload_from`] to load
+/// a different file), or automatically when the model file changes with [`ReloadableSession::watch`]. If the builders
+/// are configured with [`SessionBuilder::with_warmup`], new sessions are warmed up before being swapped in.
+///
+/// [`SessionBuilder::with_warmup`]: crate::session::builder::SessionBuilder::with_warmup
+pub struct ReloadableSession {
+	current: RwLock<Arc<Session>>,
+	source: Mutex<Source>,
+	generation: AtomicU64
+}
+
+impl ReloadableSession {
+	/// Creates a reloadable session by committing a builder returned by `builder` with the model at `path`.
+	///
+	/// `builder` is called again for every reload, possibly on another thread (e.g. the one spawned by
+	/// [`ReloadableSession::watch`]), since a [`SessionBuilder`] itself cannot be sent between threads.
+	///
+	/// ```no_run
+	/// # use ort::session::{ReloadableSession, Session};
+	/// # fn main() -> ort::Result<()> {
+	/// let session = ReloadableSession::new(Session::builder, "model.onnx")?;
+	/// # 	Ok(())
+	/// # }
+	/// ```
+	pub fn new(builder: impl Fn() -> Result<SessionBuilder> + Send + 'static, path: impl AsRef<Path>) -> Result<Self> {
+		let path = path.as_ref().to_path_buf();
+		let fingerprint = Fingerprint::of(&path)?;
+		let session = builder()?.commit_from_file(&path)?;
+		Ok(Self {
+			current: RwLock::new(Arc::new(session)),
+			source: Mutex::new(Source {
+				builder: Box::new(builder),
+				path,
+				fingerprint,
+				rejected: None
+			}),
+			generation: AtomicU64::new(0)
+		})
+	}
+
+	/// Returns the current session.
+	///
+	/// The returned session is not affected by reloads; call this again to get the newest version of the model.
+	pub fn session(&self) -> Arc<Session> {
+		Arc::clone(&self.current.read().expect("lock poisoned"))
+	}
+
+	/// Returns the number of times the model has been successfully reloaded.
+	pub fn generation(&self) -> u64 {
+		self.generation.load(Ordering::Acquire)
+	}
+
+	/// Returns the path of the model file this session is loaded from.
+	pub fn path(&self) -> PathBuf {
+		self.lock_source().path.clone()
+	}
+
+	/// Reloads the model from its file, swapping in the new session.
+	///
+	/// Returns an error if the model fails to load, or if its inputs or outputs (names, types, and shapes) differ from
+	/// those of the current model. In either case, the current session remains in use.
+	pub fn reload(&self) -> Result<()> {
+		let mut source = self.lock_source();
+		let path = source.path.clone();
+		self.reload_inner(&mut source, path)
+	}
+
+	/// Loads the model at `path` and swaps in the new session. If successful, subsequent reloads (including those
+	/// triggered by [`ReloadableSession::watch`]) will load from `path`.
+	///
+	/// Like [`ReloadableSession::reload`], the new model must have the same inputs & outputs as the current model.
+	pub fn reload_from(&self, path: impl AsRef<Path>) -> Result<()> {
+		let mut source = self.lock_source();
+		self.reload_inner(&mut source, path.as_ref().to_path_buf())
+	}
+
+	/// Reloads the model if its file has changed since it was last loaded, returning whether or not it was reloaded.
+	///
+	/// Changes are detected by comparing the file's size & modification time. To avoid loading a partially-written
+	/// model, new versions should be written to a temporary file and then moved into place.
+	///
+	/// If the reload fails, the file is not reloaded again until it changes once more.
+	pub fn reload_if_changed(&self) -> Result<bool> {
+		let mut source = self.lock_source();
+		let fingerprint = Fingerprint::of(&source.path)?;
+		if fingerprint == source.fingerprint || source.rejected.as_ref() == Some(&fingerprint) {
+			return Ok(false);
+		}
+		let path = source.path.clone();
+		if let Err(e) = self.reload_inner(&mut source, path) {
+			source.rejected = Some(fingerprint);
+			return Err(e);
+		}
+		Ok(true)
+	}
+
+	fn reload_inner(&self, source: &mut Source, path: PathBuf) -> Result<()> {
+		// Take the fingerprint before loading, so that a change made while we're loading triggers another reload.
+		let fingerprint = Fingerprint::of(&path)?;
+		let session = (source.builder)()?.commit_from_file(&path)?;
+
+		let current = self.session();
+		check_signature(&current, &session)?;
+
+		*self.current.write().expect("lock poisoned") = Arc::new(session);
+		source.path = path;
+		source.fingerprint = fingerprint;
+		source.rejected = None;
+		self.generation.fetch_add(1, Ordering::AcqRel);
+		crate::info!(generation = self.generation(), path = %source.path.display(), "Reloaded session");
+		Ok(())
+	}
+
+	/// Spawns a thread which calls [`ReloadableSession::reload_if_changed`] every `interval`, until the returned
+	/// [`ReloadWatcher`] is dropped.
+	///
+	/// Errors encountered while reloading are logged, and the current session remains in use; the reload is retried
+	/// once the file changes again. Use [`ReloadWatcher::take_error`] to inspect the most recent error.
+	pub fn watch(self: &Arc<Self>, interval: Duration) -> Result<ReloadWatcher> {
+		let (stop, stopped) = mpsc::channel::<()>();
+		let last_error = Arc::new(Mutex::new(None));
+		let session = Arc::downgrade(self);
+		let thread = thread::Builder::new()
+			.name(String::from("ort-reload-watcher"))
+			.spawn({
+				let last_error = Arc::clone(&last_error);
+				move || watch_loop(&session, interval, &stopped, &last_error)
+			})
+			.map_err(Error::wrap)?;
+		Ok(ReloadWatcher {
+			stop: Some(stop),
+			thread: Some(thread),
+			last_error
+		})
+	}
+
+	fn lock_source(&self) -> MutexGuard<'_, Source> {
+		self.source.lock().expect("lock poisoned")
+	}
+}
+
+fn watch_loop(session: &Weak<ReloadableSession>, interval: Duration, stopped: &mpsc::Receiver<()>, last_error: &Mutex<Option<Error>>) {
+	// The sender is dropped when the watcher is dropped, which disconnects the channel and wakes us early.
+	while let Err(RecvTimeoutError::Timeout) = stopped.recv_timeout(interval) {
+		let Some(session) = session.upgrade() else {
+			break;
+		};
+		let result = session.reload_if_changed();
+		let mut last_error = last_error.lock().expect("lock poisoned");
+		match result {
+			Ok(false) => {}
+			Ok(true) => *last_error = None,
+			Err(e) => {
+				crate::warn!("Failed to reload session: {e}");
+				*last_error = Some(e);
+			}
+		}
+	}
+}
+
+fn check_signature(current: &Session, new: &Session) -> Result<()> {
+	let mismatch = |message: String| Err(Error::new_with_code(ErrorCode::InvalidArgument, format!("Reloaded model has a different signature: {message}")));
+
+	if current.inputs.len() != new.inputs.len() {
+		return mismatch(format!("expected {} inputs, got {}", current.inputs.len(), new.inputs.len()));
+	}
+	for (a, b) in current.inputs.iter().zip(&new.inputs) {
+		if a.name != b.name {
+			return mismatch(format!("expected input `{}`, got `{}`", a.name, b.name));
+		}
+		if a.input_type != b.input_type {
+			return mismatch(format!("input `{}` changed from {} to {}", a.name, a.input_type, b.input_type));
+		}
+	}
+
+	if current.outputs.len() != new.outputs.len() {
+		return mismatch(format!("expected {} outputs, got {}", current.outputs.len(), new.outputs.len()));
+	}
+	for (a, b) in current.outputs.iter().zip(&new.outputs) {
+		if a.name != b.name {
+			return mismatch(format!("expected output `{}`, got `{}`", a.name, b.name));
+		}
+		if a.output_type != b.output_type {
+			return mismatch(format!("output `{}` changed from {} to {}", a.name, a.output_type, b.output_type));
+		}
+	}
+	Ok(())
+}
+
+impl fmt::Debug for ReloadableSession {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("ReloadableSession")
+			.field("path", &self.path())
+			.field("generation", &self.generation())
+			.finish_non_exhaustive()
+	}
+}
+
+/// Watches a [`ReloadableSession`]'s model file for changes; see [`ReloadableSession::watch`].
+///
+/// The watcher thread is stopped when this is dropped.
+#[derive(Debug)]
+pub struct ReloadWatcher {
+	stop: Option<mpsc::Sender<()>>,
+	thread: Option<JoinHandle<()>>,
+	last_error: Arc<Mutex<Option<Error>>>
+}
+
+impl ReloadWatcher {
+	/// Takes the error encountered by the most recent reload attempt, if it failed and no reload has succeeded since.
+	pub fn take_error(&self) -> Option<Error> {
+		self.last_error.lock().expect("lock poisoned").take()
+	}
+}
+
+impl Drop for ReloadWatcher {
+	fn drop(&mut self) {
+		drop(self.stop.take());
+		if let Some(thread) = self.thread.take() {
+			let _ = thread.join();
+		}
+	}
+}
@@ -0,0 +1,561 @@
+//! Helpers for models with recurrent state, like the key/value cache of autoregressive transformers.
+//!
+//! Decoder models exported with a KV cache take the keys & values of all previous tokens as inputs (usually named
+//! `past_key_values.*`), and return them updated with the current tokens as outputs (usually named `present.*`).
+//! [`StatefulSession`] wires these together: the `present` outputs of one run are fed back as the `past` inputs of the
+//! next, so only the new tokens need to be provided at each step.
+//!
+//! ```no_run
+//! # use ort::{session::{Session, stateful::StatefulSession}, value::Tensor};
+//! # fn main() -> ort::Result<()> {
+//! let session = Session::builder()?.commit_from_file("gpt2-with-past.onnx")?;
+//! // Cache inputs & outputs are detected automatically from their names.
+//! let mut decoder = StatefulSession::new(&session)?;
+//!
+//! // The first step processes the whole prompt...
+//! let prompt = vec![464_i64, 1893, 318];
+//! let outputs = decoder.run(ort::inputs! {
+//! 	"input_ids" => Tensor::from_array(([1, prompt.len()], prompt.clone()))?
+//! })?;
+//! # let next_token = 0_i64;
+//! // ...and subsequent steps only the newly generated token.
+//! let outputs = decoder.run(ort::inputs! {
+//! 	"input_ids" => Tensor::from_array(([1, 1], vec![next_token]))?
+//! })?;
+//! assert_eq!(decoder.sequence_length(), prompt.len() + 1);
+//!
+//! // Undo the last step, e.g. to discard a rejected speculative token.
+//! decoder.rollback(1)?;
+//! # 	Ok(())
+//! # }
+//! ```
+
+use alloc::{
+	boxed::Box,
+	format,
+	string::{String, ToString},
+	vec::Vec
+};
+use core::{fmt, ptr};
+
+use smallvec::SmallVec;
+
+use super::{Session, SessionInputValue, SessionInputs, SessionOutputs};
+use crate::{
+	error::{Error, ErrorCode, Result},
+	memory::Allocator,
+	tensor::{Shape, SymbolicDimensions, TensorElementType},
+	util::STACK_SESSION_INPUTS,
+	value::{DynTensor, DynValue, Value, ValueType, tensor_from_array}
+};
+
+/// Pairs of `(past input prefix, present output prefix)` used to detect cache inputs & outputs.
+const CACHE_PREFIXES: [(&str, &str); 3] = [("past_key_values", "present"), ("past_key_values", "present_key_values"), ("past", "present")];
+/// Inputs which are used to determine the number of new tokens in a step when using preallocated caches.
+const STEP_INPUTS: [&str; 2] = ["input_ids", "inputs_embeds"];
+
+/// Configures a [`StatefulSession`]; see [`StatefulSession::builder`].
+pub struct StatefulSessionBuilder<'s> {
+	session: &'s Session,
+	mappings: Vec<(String, String)>,
+	sequence_axis: Option<usize>,
+	batch_size: usize,
+	dimensions: Vec<(String, i64)>,
+	max_sequence_length: Option<usize>,
+	step_input: Option<(String, usize)>
+}
+
+impl<'s> StatefulSessionBuilder<'s> {
+	/// Feeds the output named `present` back into the input named `past`.
+	///
+	/// If no mappings are configured, they are detected from the names of the session's inputs & outputs, i.e.
+	/// `past_key_values.0.key` is mapped to `present.0.key`.
+	pub fn with_cache(mut self, past: impl Into<String>, present: impl Into<String>) -> Self {
+		self.mappings.push((past.into(), present.into()));
+		self
+	}
+
+	/// Sets the axis of the cache tensors along which the sequence grows.
+	///
+	/// By default, this is the dimension whose symbolic name contains `seq` or `past` (e.g. `past_sequence_length`),
+	/// or the second-to-last dimension if no such dimension exists.
+	pub fn with_sequence_axis(mut self, axis: usize) -> Self {
+		self.sequence_axis = Some(axis);
+		self
+	}
+
+	/// Sets the batch size used to initialize empty caches when the first dimension of a cache input is dynamic.
+	/// Defaults to `1`.
+	pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+		self.batch_size = batch_size;
+		self
+	}
+
+	/// Sets the size of the dynamic dimension named `symbol` when initializing empty caches. This is required if a
+	/// cache input has dynamic dimensions other than the batch & sequence dimensions, e.g. `num_heads`.
+	pub fn with_dimension(mut self, symbol: impl Into<String>, size: usize) -> Self {
+		self.dimensions.push((symbol.into(), size as i64));
+		self
+	}
+
+	/// Keeps the caches in buffers preallocated for up to `max_sequence_length` tokens.
+	///
+	/// Without preallocation, ONNX Runtime allocates new cache outputs on every run. With preallocation, the caches
+	/// instead alternate between two fixed buffers per cache, which avoids reallocations but requires knowing how many
+	/// tokens each run adds to the cache; see [`StatefulSessionBuilder::with_step_input`].
+	pub fn with_preallocated_cache(mut self, max_sequence_length: usize) -> Self {
+		self.max_sequence_length = Some(max_sequence_length);
+		self
+	}
+
+	/// Sets the input whose dimension `axis` is the number of new tokens processed by a run. This is only used with
+	/// [preallocated caches](StatefulSessionBuilder::with_preallocated_cache).
+	///
+	/// Defaults to axis `1` of `input_ids` or `inputs_embeds`, whichever the model has.
+	pub fn with_step_input(mut self, name: impl Into<String>, axis: usize) -> Self {
+		self.step_input = Some((name.into(), axis));
+		self
+	}
+
+	/// Validates the configuration against the session and creates the [`StatefulSession`] with empty caches.
+	pub fn build(self) -> Result<StatefulSession<'s>> {
+		let session = self.session;
+		let mappings = if self.mappings.is_empty() { detect_mappings(session) } else { self.mappings };
+		if mappings.is_empty() {
+			return Err(Error::new_with_code(
+				ErrorCode::InvalidArgument,
+				"Could not detect any cache inputs/outputs; specify them with `StatefulSessionBuilder::with_cache`"
+			));
+		}
+
+		let mut caches = Vec::with_capacity(mappings.len());
+		for (past, present) in mappings {
+			let Some(input) = session.inputs.iter().find(|i| i.name == past) else {
+				return Err(Error::new_with_code(ErrorCode::InvalidArgument, format!("Model has no input named '{past}'")));
+			};
+			if !session.outputs.iter().any(|o| o.name == present) {
+				return Err(Error::new_with_code(ErrorCode::InvalidArgument, format!("Model has no output named '{present}'")));
+			}
+			let ValueType::Tensor { ty, shape, dimension_symbols } = &input.input_type else {
+				return Err(Error::new_with_code(ErrorCode::InvalidArgument, format!("Cache input '{past}' should be a tensor, but is {}", input.input_type)));
+			};
+
+			let axis = match self.sequence_axis {
+				Some(axis) => axis,
+				None => detect_sequence_axis(dimension_symbols)
+			};
+			if axis >= shape.len() {
+				return Err(Error::new_with_code(
+					ErrorCode::InvalidArgument,
+					format!("Sequence axis {axis} is out of bounds for cache input '{past}' of rank {}", shape.len())
+				));
+			}
+
+			let mut empty_shape = Shape::new(shape.iter().copied());
+			for (i, (dim, symbol)) in empty_shape.iter_mut().zip(dimension_symbols.iter()).enumerate() {
+				if i == axis {
+					*dim = 0;
+				} else if *dim < 0 {
+					*dim = match self.dimensions.iter().find(|(s, _)| s == symbol) {
+						Some((_, size)) => *size,
+						None if i == 0 => self.batch_size as i64,
+						None => {
+							return Err(Error::new_with_code(
+								ErrorCode::InvalidArgument,
+								format!(
+									"Cannot determine the size of dimension {i} ('{symbol}') of cache input '{past}'; specify it with `StatefulSessionBuilder::with_dimension`"
+								)
+							));
+						}
+					};
+				}
+			}
+
+			let buffers = match self.max_sequence_length {
+				Some(max_sequence_length) => {
+					let mut buffer_shape = empty_shape.clone();
+					buffer_shape[axis] = max_sequence_length as i64;
+					Some([DynTensor::new(session.allocator(), *ty, buffer_shape.clone())?, DynTensor::new(session.allocator(), *ty, buffer_shape)?])
+				}
+				None => None
+			};
+
+			let mut cache = CacheState {
+				past,
+				present,
+				element_type: *ty,
+				axis,
+				value: DynTensor::new(session.allocator(), *ty, empty_shape.clone())?.into_dyn(),
+				empty_shape,
+				buffers,
+				current: 0
+			};
+			if cache.buffers.is_some() {
+				cache.reset(session.allocator())?;
+			}
+			caches.push(cache);
+		}
+
+		let step_input = match (self.max_sequence_length, self.step_input) {
+			(None, _) => None,
+			(Some(_), Some(step_input)) => Some(step_input),
+			(Some(_), None) => match STEP_INPUTS.iter().find(|name| session.inputs.iter().any(|i| i.name == **name)) {
+				Some(name) => Some((name.to_string(), 1)),
+				None => {
+					return Err(Error::new_with_code(
+						ErrorCode::InvalidArgument,
+						"Could not detect the step input required for preallocated caches; specify it with `StatefulSessionBuilder::with_step_input`"
+					));
+				}
+			}
+		};
+
+		Ok(StatefulSession {
+			session,
+			caches,
+			sequence_length: 0,
+			max_sequence_length: self.max_sequence_length,
+			step_input
+		})
+	}
+}
+
+impl fmt::Debug for StatefulSessionBuilder<'_> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("StatefulSessionBuilder")
+			.field("mappings", &self.mappings)
+			.field("sequence_axis", &self.sequence_axis)
+			.field("batch_size", &self.batch_size)
+			.field("dimensions", &self.dimensions)
+			.field("max_sequence_length", &self.max_sequence_length)
+			.field("step_input", &self.step_input)
+			.finish_non_exhaustive()
+	}
+}
+
+#[derive(Debug)]
+struct CacheState {
+	past: String,
+	present: String,
+	element_type: TensorElementType,
+	axis: usize,
+	empty_shape: Shape,
+	/// The current value of the cache, which is fed to the `past` input.
+	value: DynValue,
+	/// With preallocation, the two buffers the cache alternates between. `value` is a view into `buffers[current]`.
+	buffers: Option<[DynTensor; 2]>,
+	current: usize
+}
+
+impl CacheState {
+	fn shape(&self) -> &Shape {
+		match self.value.dtype() {
+			ValueType::Tensor { shape, .. } => shape,
+			_ => unreachable!("cache values are always tensors")
+		}
+	}
+
+	/// Creates a tensor with the given shape which is backed by one of our preallocated buffers.
+	fn buffer_view(&self, buffer: usize, shape: Shape) -> Result<DynValue> {
+		let buffers = self.buffers.as_ref().expect("buffer_view called without preallocated buffers");
+		let buffer = &buffers[buffer];
+		let view = tensor_from_array(
+			buffer.memory_info().clone(),
+			shape,
+			buffer.data_ptr()?.cast_mut(),
+			self.element_type.byte_size(1),
+			self.element_type,
+			Some(Box::new(Value::clone_of(buffer)))
+		)?;
+		Ok(view.into_dyn())
+	}
+
+	fn reset(&mut self, allocator: &Allocator) -> Result<()> {
+		self.value = match &self.buffers {
+			Some(_) => {
+				self.current = 0;
+				self.buffer_view(0, self.empty_shape.clone())?
+			}
+			None => DynTensor::new(allocator, self.element_type, self.empty_shape.clone())?.into_dyn()
+		};
+		Ok(())
+	}
+
+	fn truncate(&mut self, len: usize, allocator: &Allocator) -> Result<()> {
+		if !self.value.memory_info().is_cpu_accessible() {
+			return Err(Error::new_with_code(
+				ErrorCode::NotImplemented,
+				format!("Cannot truncate cache '{}' because it is not in CPU-accessible memory", self.past)
+			));
+		}
+
+		let old_shape = self.shape().clone();
+		let old_len = old_shape[self.axis] as usize;
+		let outer = old_shape[..self.axis].iter().product::<i64>() as usize;
+		let row_bytes = self.element_type.byte_size(old_shape[self.axis + 1..].iter().product::<i64>() as usize);
+		let mut new_shape = old_shape;
+		new_shape[self.axis] = len as i64;
+
+		let src = self.value.data_ptr()?.cast::<u8>();
+		let new_value = match &self.buffers {
+			// Compact the rows in place.
+			Some(_) => self.buffer_view(self.current, new_shape)?,
+			None => DynTensor::new(allocator, self.element_type, new_shape)?.into_dyn()
+		};
+		let dst = new_value.data_ptr()?.cast::<u8>().cast_mut();
+		unsafe { copy_prefix(src, dst, outer, old_len, len, row_bytes) };
+		self.value = new_value;
+		Ok(())
+	}
+}
+
+/// Copies the first `new_len` rows of each of the `outer` slices of `src`, each containing `old_len` rows of
+/// `row_bytes` bytes, to `dst`. `src` and `dst` may be the same buffer.
+unsafe fn copy_prefix(src: *const u8, dst: *mut u8, outer: usize, old_len: usize, new_len: usize, row_bytes: usize) {
+	debug_assert!(new_len <= old_len);
+	for o in 0..outer {
+		// Since `new_len <= old_len`, the destination of each slice never overlaps a source slice which has yet to be
+		// copied, so copying front-to-back is safe in place.
+		unsafe { ptr::copy(src.add(o * old_len * row_bytes), dst.add(o * new_len * row_bytes), new_len * row_bytes) };
+	}
+}
+
+fn detect_mappings(session: &Session) -> Vec<(String, String)> {
+	session
+		.inputs
+		.iter()
+		.filter_map(|input| {
+			CACHE_PREFIXES.iter().find_map(|(past, present)| {
+				let suffix = input.name.strip_prefix(past)?;
+				let output = format!("{present}{suffix}");
+				session.outputs.iter().any(|o| o.name == output).then(|| (input.name.clone(), output))
+			})
+		})
+		.collect()
+}
+
+fn detect_sequence_axis(dimension_symbols: &SymbolicDimensions) -> usize {
+	dimension_symbols
+		.iter()
+		.position(|s| {
+			let s = s.to_ascii_lowercase();
+			s.contains("seq") || s.contains("past")
+		})
+		.unwrap_or_else(|| dimension_symbols.len().saturating_sub(2))
+}
+
+/// A [`Session`] which maintains a cache across runs, feeding the model's `present` outputs back into its `past`
+/// inputs. See the [module-level documentation](self) for more information.
+#[derive(Debug)]
+pub struct StatefulSession<'s> {
+	session: &'s Session,
+	caches: Vec<CacheState>,
+	sequence_length: usize,
+	max_sequence_length: Option<usize>,
+	step_input: Option<(String, usize)>
+}
+
+impl<'s> StatefulSession<'s> {
+	/// Creates a [`StatefulSession`] with cache inputs & outputs detected from their names.
+	///
+	/// Use [`StatefulSession::builder`] to configure the session further.
+	pub fn new(session: &'s Session) -> Result<Self> {
+		Self::builder(session).build()
+	}
+
+	/// Creates a [`StatefulSessionBuilder`] to configure a [`StatefulSession`].
+	pub fn builder(session: &'s Session) -> StatefulSessionBuilder<'s> {
+		StatefulSessionBuilder {
+			session,
+			mappings: Vec::new(),
+			sequence_axis: None,
+			batch_size: 1,
+			dimensions: Vec::new(),
+			max_sequence_length: None,
+			step_input: None
+		}
+	}
+
+	/// Returns the underlying session.
+	pub fn session(&self) -> &'s Session {
+		self.session
+	}
+
+	/// Returns the number of tokens currently held in the cache.
+	pub fn sequence_length(&self) -> usize {
+		self.sequence_length
+	}
+
+	/// Returns the current value of the cache fed to the input named `past`.
+	pub fn cache(&self, past: &str) -> Option<&DynValue> {
+		self.caches.iter().find(|c| c.past == past).map(|c| &c.value)
+	}
+
+	/// Runs the model, feeding the caches as inputs and updating them with the model's outputs.
+	///
+	/// `input_values` should contain every input except the cache inputs. If inputs are passed positionally (i.e. via
+	/// [`ort::inputs![a, b]`](crate::inputs)), they correspond to the session's inputs in order, skipping cache inputs.
+	///
+	/// The returned outputs contain every output except the cache outputs.
+	pub fn run<'i, 'v: 'i, const N: usize>(&mut self, input_values: impl Into<SessionInputs<'i, 'v, N>>) -> Result<SessionOutputs<'s, 's>> {
+		let session = self.session;
+		let input_values = input_values.into();
+		let cache_values: SmallVec<SessionInputValue<'_>, { STACK_SESSION_INPUTS }> = self.caches.iter().map(|c| SessionInputValue::from(&c.value)).collect();
+
+		let mut input_names: SmallVec<&str, { STACK_SESSION_INPUTS }> = SmallVec::new();
+		let mut values: SmallVec<&SessionInputValue<'_>, { STACK_SESSION_INPUTS }> = SmallVec::new();
+		match &input_values {
+			SessionInputs::ValueMap(input_values) => {
+				for (name, value) in input_values {
+					input_names.push(name.as_ref());
+					values.push(value);
+				}
+			}
+			SessionInputs::ValueSlice(input_values) => self.push_positional(&mut input_names, &mut values, input_values)?,
+			SessionInputs::ValueArray(input_values) => self.push_positional(&mut input_names, &mut values, input_values)?
+		}
+
+		let new_sequence_length = match &self.step_input {
+			Some((name, axis)) => {
+				let step = input_names
+					.iter()
+					.zip(values.iter())
+					.find(|(n, _)| *n == name)
+					.and_then(|(_, v)| match v.dtype() {
+						ValueType::Tensor { shape, .. } => shape.get(*axis).copied(),
+						_ => None
+					})
+					.ok_or_else(|| {
+						Error::new_with_code(
+							ErrorCode::InvalidArgument,
+							format!("Step input '{name}' should be a tensor with at least {} dimensions", axis + 1)
+						)
+					})?;
+				let new_sequence_length = self.sequence_length + step as usize;
+				if let Some(max_sequence_length) = self.max_sequence_length.filter(|max| new_sequence_length > *max) {
+					return Err(Error::new_with_code(
+						ErrorCode::InvalidArgument,
+						format!("Running {step} more tokens would exceed the maximum preallocated sequence length of {max_sequence_length}")
+					));
+				}
+				Some(new_sequence_length)
+			}
+			None => None
+		};
+
+		for (cache, value) in self.caches.iter().zip(cache_values.iter()) {
+			input_names.push(cache.past.as_str());
+			values.push(value);
+		}
+
+		let (output_names, mut output_tensors) = session.default_outputs();
+		if let Some(new_sequence_length) = new_sequence_length {
+			for cache in &self.caches {
+				let mut shape = cache.shape().clone();
+				shape[cache.axis] = new_sequence_length as i64;
+				let idx = output_names
+					.iter()
+					.position(|n| *n == cache.present)
+					.expect("present output should exist");
+				output_tensors[idx] = Some(cache.buffer_view(cache.current ^ 1, shape)?);
+			}
+		}
+
+		let mut outputs = session.run_inner_with_outputs(input_names, values, output_names, output_tensors, None)?;
+		drop(cache_values);
+
+		for cache in &mut self.caches {
+			cache.value = outputs.remove(&cache.present).expect("present output should exist");
+			if cache.buffers.is_some() {
+				cache.current ^= 1;
+			}
+		}
+		if let Some(cache) = self.caches.first() {
+			self.sequence_length = cache.shape()[cache.axis] as usize;
+		}
+
+		Ok(outputs)
+	}
+
+	fn push_positional<'a, 'v>(
+		&self,
+		input_names: &mut SmallVec<&'a str, { STACK_SESSION_INPUTS }>,
+		values: &mut SmallVec<&'a SessionInputValue<'v>, { STACK_SESSION_INPUTS }>,
+		input_values: &'a [SessionInputValue<'v>]
+	) -> Result<()>
+	where
+		's: 'a
+	{
+		let inputs: SmallVec<&str, { STACK_SESSION_INPUTS }> = self
+			.session
+			.inputs
+			.iter()
+			.map(|i| i.name.as_str())
+			.filter(|name| !self.caches.iter().any(|c| c.past == *name))
+			.collect();
+		if input_values.len() > inputs.len() {
+			return Err(Error::new_with_code(
+				ErrorCode::InvalidArgument,
+				format!("{} inputs were provided, but the model only accepts {} non-cache inputs.", input_values.len(), inputs.len())
+			));
+		}
+		input_names.extend(inputs.into_iter().take(input_values.len()));
+		values.extend(input_values);
+		Ok(())
+	}
+
+	/// Discards all but the first `len` tokens of the cache.
+	///
+	/// Truncation is only supported for caches residing in CPU-accessible memory.
+	pub fn truncate(&mut self, len: usize) -> Result<()> {
+		if len > self.sequence_length {
+			return Err(Error::new_with_code(ErrorCode::InvalidArgument, format!("Cannot truncate cache of length {} to length {len}", self.sequence_length)));
+		}
+		if len == self.sequence_length {
+			return Ok(());
+		}
+		for cache in &mut self.caches {
+			cache.truncate(len, self.session.allocator())?;
+		}
+		self.sequence_length = len;
+		Ok(())
+	}
+
+	/// Discards the last `tokens` tokens of the cache. See [`StatefulSession::truncate`].
+	pub fn rollback(&mut self, tokens: usize) -> Result<()> {
+		match self.sequence_length.checked_sub(tokens) {
+			Some(len) => self.truncate(len),
+			None => {
+				Err(Error::new_with_code(ErrorCode::InvalidArgument, format!("Cannot roll back {tokens} tokens from cache of length {}", self.sequence_length)))
+			}
+		}
+	}
+
+	/// Empties the cache, e.g. to start processing a new sequence.
+	pub fn reset(&mut self) -> Result<()> {
+		for cache in &mut self.caches {
+			cache.reset(self.session.allocator())?;
+		}
+		self.sequence_length = 0;
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::copy_prefix;
+
+	#[test]
+	fn test_copy_prefix() {
+		// 2 outer slices, each with 3 rows of 2 elements.
+		let mut data: [u8; 12] = [1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12];
+
+		let mut out = [0; 8];
+		unsafe { copy_prefix(data.as_ptr(), out.as_mut_ptr(), 2, 3, 2, 2) };
+		assert_eq!(out, [1, 2, 3, 4, 7, 8, 9, 10]);
+
+		// in place
+		unsafe { copy_prefix(data.as_ptr(), data.as_mut_ptr(), 2, 3, 1, 2) };
+		assert_eq!(&data[..4], &[1, 2, 7, 8]);
+	}
+}
@@ -0,0 +1,301 @@
+//! Running sessions over ONNX test data sets; see [`Session::run_test_data_set`].
+//!
+//! A test data set is a directory of serialized `TensorProto`s named `input_0.pb`, `input_1.pb`, ..., and
+//! `output_0.pb`, `output_1.pb`, ..., as found in the ONNX model zoo & the ONNX backend test suite. Running a session
+//! over a test data set checks that the outputs it produces match the expected outputs, which makes it easy to verify
+//! a model (or an execution provider) in CI.
+
+use alloc::{format, string::String, vec::Vec};
+use std::{fs, path::Path};
+
+use super::Session;
+use crate::{
+	error::{Error, ErrorCode, Result},
+	tensor::{F8E4M3FN, F8E4M3FNUZ, F8E5M2, F8E5M2FNUZ, TensorElementType, bf16_to_f32, f16_to_f32},
+	value::{DynTensor, DynTensorValueType, DynValue, ValueType}
+};
+
+/// The tolerance used when comparing floating-point outputs in [`Session::run_test_data_set`].
+///
+/// An actual value `a` matches an expected value `e` if `|a - e| <= absolute + relative * |e|`. Infinities must match
+/// exactly, and NaNs only match NaNs. Non-floating-point outputs are always compared exactly.
+///
+/// The default tolerance matches the one used by the ONNX backend test suite: a relative tolerance of `1e-3` and an
+/// absolute tolerance of `1e-7`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Tolerance {
+	relative: f64,
+	absolute: f64
+}
+
+impl Default for Tolerance {
+	fn default() -> Self {
+		Self::new(1e-3, 1e-7)
+	}
+}
+
+impl Tolerance {
+	/// Creates a tolerance with the given relative & absolute components.
+	pub fn new(relative: f64, absolute: f64) -> Self {
+		Self { relative, absolute }
+	}
+
+	/// Creates a tolerance which requires floating-point outputs to match exactly.
+	pub fn exact() -> Self {
+		Self::new(0.0, 0.0)
+	}
+
+	/// Returns the relative component of this tolerance.
+	pub fn relative(&self) -> f64 {
+		self.relative
+	}
+
+	/// Returns the absolute component of this tolerance.
+	pub fn absolute(&self) -> f64 {
+		self.absolute
+	}
+
+	fn matches(&self, actual: f64, expected: f64) -> bool {
+		if actual.is_nan() || expected.is_nan() {
+			return actual.is_nan() && expected.is_nan();
+		}
+		if actual.is_infinite() || expected.is_infinite() {
+			return actual == expected;
+		}
+		(actual - expected).abs() <= self.absolute + self.relative * expected.abs()
+	}
+}
+
+/// A set of inputs & expected outputs loaded from an ONNX test data set directory.
+///
+/// ```no_run
+/// # use ort::session::{test_data::{TestDataSet, Tolerance}, Session};
+/// # fn main() -> ort::Result<()> {
+/// let session = Session::builder()?.commit_from_file("mnist/model.onnx")?;
+/// let data_set = TestDataSet::load("mnist/test_data_set_0")?;
+/// session.run_test_data_set(&data_set, Tolerance::default())?;
+/// # 	Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct TestDataSet {
+	inputs: Vec<(String, DynTensor)>,
+	outputs: Vec<(String, DynTensor)>
+}
+
+impl TestDataSet {
+	/// Loads a test data set from the `input_N.pb` & `output_N.pb` files in the directory at `path`.
+	///
+	/// Tensors which have no name in the serialized `TensorProto` are matched to the session's inputs & outputs by
+	/// their index.
+	pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+		let path = path.as_ref();
+		let mut inputs = Vec::new();
+		let mut outputs = Vec::new();
+		let entries = fs::read_dir(path).map_err(|e| Error::new_with_code(ErrorCode::NoSuchFile, format!("Failed to read `{}`: {e}", path.display())))?;
+		for entry in entries {
+			let entry = entry.map_err(|e| Error::new_with_code(ErrorCode::NoSuchFile, format!("Failed to read `{}`: {e}", path.display())))?;
+			let file_name = entry.file_name();
+			let Some((list, index)) = file_name
+				.to_str()
+				.and_then(|name| name.strip_suffix(".pb"))
+				.and_then(|stem| {
+					stem.strip_prefix("input_")
+						.map(|i| (&mut inputs, i))
+						.or_else(|| stem.strip_prefix("output_").map(|i| (&mut outputs, i)))
+				})
+				.and_then(|(list, index)| Some((list, index.parse::<usize>().ok()?)))
+			else {
+				continue;
+			};
+
+			let file_path = entry.path();
+			let bytes =
+				fs::read(&file_path).map_err(|e| Error::new_with_code(ErrorCode::NoSuchFile, format!("Failed to read `{}`: {e}", file_path.display())))?;
+			let (name, tensor) = DynTensor::from_named_tensor_proto(&bytes)
+				.map_err(|e| Error::new_with_code(e.code(), format!("Failed to load `{}`: {e}", file_path.display())))?;
+			list.push((index, name, tensor));
+		}
+
+		Ok(Self {
+			inputs: into_ordered(inputs, "input", path)?,
+			outputs: into_ordered(outputs, "output", path)?
+		})
+	}
+
+	/// Creates a test data set from the given inputs & expected outputs. Empty names are matched to the session's
+	/// inputs & outputs by their index.
+	pub fn new(inputs: Vec<(String, DynTensor)>, outputs: Vec<(String, DynTensor)>) -> Self {
+		Self { inputs, outputs }
+	}
+
+	/// Returns the inputs of this test data set, in order.
+	pub fn inputs(&self) -> &[(String, DynTensor)] {
+		&self.inputs
+	}
+
+	/// Returns the expected outputs of this test data set, in order.
+	pub fn outputs(&self) -> &[(String, DynTensor)] {
+		&self.outputs
+	}
+}
+
+fn into_ordered(mut tensors: Vec<(usize, String, DynTensor)>, kind: &str, path: &Path) -> Result<Vec<(String, DynTensor)>> {
+	tensors.sort_by_key(|(index, ..)| *index);
+	for (i, (index, ..)) in tensors.iter().enumerate() {
+		if *index != i {
+			return Err(Error::new_with_code(ErrorCode::NoSuchFile, format!("Test data set `{}` is missing `{kind}_{i}.pb`", path.display())));
+		}
+	}
+	Ok(tensors.into_iter().map(|(_, name, tensor)| (name, tensor)).collect())
+}
+
+impl Session {
+	/// Runs this session over a [`TestDataSet`], returning an error if any output does not match its expected value
+	/// within the given [`Tolerance`].
+	///
+	/// Outputs are only checked if they appear in the test data set. Element types & shapes must always match exactly.
+	pub fn run_test_data_set(&self, data_set: &TestDataSet, tolerance: Tolerance) -> Result<()> {
+		let inputs = data_set
+			.inputs
+			.iter()
+			.enumerate()
+			.map(|(i, (name, tensor))| Ok((resolve_name(name, i, self.inputs.iter().map(|input| input.name.as_str()), "input")?, tensor)))
+			.collect::<Result<Vec<_>>>()?;
+		let outputs = self.run(inputs)?;
+
+		for (i, (name, expected)) in data_set.outputs.iter().enumerate() {
+			let name = resolve_name(name, i, self.outputs.iter().map(|output| output.name.as_str()), "output")?;
+			let actual = outputs
+				.get(name)
+				.ok_or_else(|| Error::new_with_code(ErrorCode::InvalidArgument, format!("Session did not produce output `{name}`")))?;
+			compare(actual, expected, tolerance).map_err(|e| Error::new_with_code(e.code(), format!("Output `{name}` does not match: {e}")))?;
+		}
+		Ok(())
+	}
+}
+
+fn resolve_name<'s>(name: &'s str, index: usize, mut names: impl Iterator<Item = &'s str>, kind: &str) -> Result<&'s str> {
+	if !name.is_empty() {
+		return Ok(name);
+	}
+	names.nth(index).ok_or_else(|| {
+		Error::new_with_code(
+			ErrorCode::InvalidArgument,
+			format!("Test data set has an unnamed {kind} at index {index}, but the session has no {kind} at that index")
+		)
+	})
+}
+
+fn compare(actual: &DynValue, expected: &DynTensor, tolerance: Tolerance) -> Result<()> {
+	let ValueType::Tensor { ty, shape, .. } = expected.dtype() else {
+		unreachable!();
+	};
+	match actual.dtype() {
+		ValueType::Tensor {
+			ty: actual_ty, shape: actual_shape, ..
+		} => {
+			if actual_ty != ty {
+				return Err(Error::new_with_code(ErrorCode::InvalidArgument, format!("expected element type {ty}, got {actual_ty}")));
+			}
+			if actual_shape != shape {
+				return Err(Error::new_with_code(ErrorCode::InvalidArgument, format!("expected shape {shape}, got {actual_shape}")));
+			}
+		}
+		actual_ty => return Err(Error::new_with_code(ErrorCode::InvalidArgument, format!("expected a tensor, got {actual_ty}")))
+	}
+	let actual = actual.downcast_ref::<DynTensorValueType>()?;
+
+	if *ty == TensorElementType::String {
+		let (_, actual) = actual.try_extract_strings()?;
+		let (_, expected) = expected.try_extract_strings()?;
+		if let Some(i) = actual.iter().zip(&expected).position(|(a, e)| a != e) {
+			return Err(Error::new_with_code(ErrorCode::InvalidArgument, format!("expected {:?} at index {i}, got {:?}", expected[i], actual[i])));
+		}
+		return Ok(());
+	}
+
+	let actual = actual.raw_bytes()?;
+	let expected = expected.raw_bytes()?;
+	match (to_floats(*ty, actual), to_floats(*ty, expected)) {
+		(Some(actual), Some(expected)) => {
+			// complex numbers are compared component-wise
+			let components = if matches!(ty, TensorElementType::Complex64 | TensorElementType::Complex128) { 2 } else { 1 };
+			if let Some(i) = actual.iter().zip(&expected).position(|(&a, &e)| !tolerance.matches(a, e)) {
+				return Err(Error::new_with_code(
+					ErrorCode::InvalidArgument,
+					format!("expected {} at index {}, got {} (tolerance: {tolerance:?})", expected[i], i / components, actual[i])
+				));
+			}
+		}
+		_ => {
+			// 4-bit types are packed, so compare whole bytes, which each hold 2 elements
+			let element_size = if ty.is_packed() { 1 } else { ty.byte_size(1) };
+			if let Some(i) = actual.chunks(element_size).zip(expected.chunks(element_size)).position(|(a, e)| a != e) {
+				let index = if ty.is_packed() { i * 2 } else { i };
+				return Err(Error::new_with_code(
+					ErrorCode::InvalidArgument,
+					format!(
+						"expected {:02x?} at index {index}, got {:02x?}",
+						&expected[i * element_size..][..element_size],
+						&actual[i * element_size..][..element_size]
+					)
+				));
+			}
+		}
+	}
+	Ok(())
+}
+
+/// Decodes the little-endian `bytes` of a tensor of type `ty` into `f64`s, or returns `None` if `ty` is not a
+/// floating-point type.
+fn to_floats(ty: TensorElementType, bytes: &[u8]) -> Option<Vec<f64>> {
+	fn decode<const N: usize>(bytes: &[u8], f: impl Fn([u8; N]) -> f64) -> Option<Vec<f64>> {
+		Some(
+			bytes
+				.chunks_exact(N)
+				.map(|chunk| f(chunk.try_into().expect("chunk has N bytes")))
+				.collect()
+		)
+	}
+
+	match ty {
+		TensorElementType::Float32 | TensorElementType::Complex64 => decode(bytes, |b| f64::from(f32::from_le_bytes(b))),
+		TensorElementType::Float64 | TensorElementType::Complex128 => decode(bytes, f64::from_le_bytes),
+		TensorElementType::Float16 => decode(bytes, |b| f64::from(f16_to_f32(u16::from_le_bytes(b)))),
+		TensorElementType::Bfloat16 => decode(bytes, |b| f64::from(bf16_to_f32(u16::from_le_bytes(b)))),
+		TensorElementType::Float8E4M3FN => decode(bytes, |[b]| f64::from(F8E4M3FN(b).to_f32())),
+		TensorElementType::Float8E4M3FNUZ => decode(bytes, |[b]| f64::from(F8E4M3FNUZ(b).to_f32())),
+		TensorElementType::Float8E5M2 => decode(bytes, |[b]| f64::from(F8E5M2(b).to_f32())),
+		TensorElementType::Float8E5M2FNUZ => decode(bytes, |[b]| f64::from(F8E5M2FNUZ(b).to_f32())),
+		_ => None
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use alloc::vec;
+
+	use super::{Tolerance, to_floats};
+	use crate::tensor::TensorElementType;
+
+	#[test]
+	fn test_to_floats() {
+		assert_eq!(to_floats(TensorElementType::Float16, &[0x00, 0x3C, 0x00, 0xC0]), Some(vec![1.0, -2.0]));
+		assert_eq!(to_floats(TensorElementType::Bfloat16, &[0x80, 0x3F, 0x49, 0x40]), Some(vec![1.0, 3.140625]));
+		assert_eq!(to_floats(TensorElementType::Int32, &[0; 4]), None);
+	}
+
+	#[test]
+	fn test_tolerance() {
+		let tolerance = Tolerance::default();
+		assert!(tolerance.matches(1.0, 1.0005));
+		assert!(!tolerance.matches(1.0, 1.002));
+		assert!(tolerance.matches(0.0, 5e-8));
+		assert!(tolerance.matches(f64::NAN, f64::NAN));
+		assert!(!tolerance.matches(f64::NAN, 1.0));
+		assert!(tolerance.matches(f64::INFINITY, f64::INFINITY));
+		assert!(!tolerance.matches(f64::INFINITY, f64::NEG_INFINITY));
+		assert!(!Tolerance::exact().matches(1.0, 1.0 + f64::EPSILON));
+	}
+}
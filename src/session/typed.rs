@@ -0,0 +1,385 @@
+//! Strongly-typed model inputs & outputs.
+//!
+//! Instead of building inputs with [`crate::inputs!`] and looking up outputs by name in [`SessionOutputs`], the inputs
+//! and outputs of a model can be described by plain structs implementing [`ModelInputs`] & [`ModelOutputs`]. With
+//! the `derive` feature, these traits can be derived via `#[derive(ort::Inputs)]` & `#[derive(ort::Outputs)]`.
+//!
+//! [`Session::typed`] checks the structs' field names, element types, and ranks against the model once, returning a
+//! [`TypedSession`] which can then run the model without any string lookups. A renamed model input thus becomes an
+//! error when the model is loaded, rather than deep inside ONNX Runtime on the first inference.
+#![cfg_attr(feature = "derive", doc = "```")]
+#![cfg_attr(not(feature = "derive"), doc = "```ignore")]
+//! # use ort::{session::Session, value::{Tensor, TensorRef}};
+//! #[derive(ort::Inputs)]
+//! struct UpsampleInputs<'a> {
+//! 	#[ort(name = "up_sampling2d_input:0", rank = 4)]
+//! 	image: TensorRef<'a, f32>
+//! }
+//!
+//! #[derive(ort::Outputs)]
+//! struct UpsampleOutputs {
+//! 	#[ort(name = "Identity:0", rank = 4)]
+//! 	upsampled: Tensor<f32>
+//! }
+//!
+//! # fn main() -> ort::Result<()> {
+//! let session = Session::builder()?.commit_from_file("tests/data/upsample.onnx")?;
+//! let upsample = session.typed::<UpsampleInputs, UpsampleOutputs>()?;
+//!
+//! let image = ndarray::Array4::<f32>::zeros((1, 64, 64, 3));
+//! let outputs = upsample.run(UpsampleInputs { image: TensorRef::from_array_view(&image)? })?;
+//! assert_eq!(**outputs.upsampled.shape(), [1, 128, 128, 3]);
+//! # 	Ok(())
+//! # }
+#![doc = "```"]
+//! By default, the name of each field is used as the name of the model input/output. Since ONNX names often aren't
+//! valid Rust identifiers, the name can be overridden with `#[ort(name = "...")]`. `#[ort(rank = N)]` additionally
+//! checks that the model's input/output has rank `N`.
+
+use alloc::{format, string::String, vec::Vec};
+use core::{fmt, iter, marker::PhantomData};
+
+use smallvec::SmallVec;
+
+use super::{NoSelectedOutputs, RunOptions, Session, SessionInputValue, SessionOutputs};
+use crate::{
+	error::{Error, ErrorCode, Result},
+	tensor::{IntoTensorElementType, TensorElementType},
+	util::{STACK_SESSION_INPUTS, STACK_SESSION_OUTPUTS},
+	value::{DynTensor, DynTensorValueType, DynValue, DynValueTypeMarker, Tensor, TensorValueType, ValueRef, ValueRefMut, ValueType}
+};
+
+/// Describes one field of a [`ModelInputs`] or [`ModelOutputs`] struct.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldDescriptor {
+	/// The name of the model input/output this field corresponds to.
+	pub name: &'static str,
+	/// The expected element type of the tensor, or `None` if the field can hold any type (i.e. [`DynTensor`]).
+	pub element_type: Option<TensorElementType>,
+	/// The expected rank of the tensor, or `None` if it should not be checked.
+	pub rank: Option<usize>
+}
+
+/// A struct describing the inputs of a model. Usually implemented via `#[derive(ort::Inputs)]`.
+pub trait ModelInputs {
+	/// This struct, with its lifetime (if any) replaced by `'v`. This allows a single [`TypedSession`] to be run with
+	/// inputs borrowed for different lifetimes. Any type parameters of the struct must outlive `'v`.
+	type WithLifetime<'v>: ModelInputs + 'v
+	where
+		Self: 'v;
+
+	/// Returns a descriptor for each field, in the same order as [`ModelInputs::into_values`].
+	fn fields() -> Vec<FieldDescriptor>;
+
+	/// Converts this struct into a list of values, in the same order as [`ModelInputs::fields`].
+	fn into_values<'v>(self) -> Vec<SessionInputValue<'v>>
+	where
+		Self: 'v;
+}
+
+/// A struct describing the outputs of a model. Usually implemented via `#[derive(ort::Outputs)]`.
+pub trait ModelOutputs: Sized {
+	/// Returns a descriptor for each field, in the same order as [`ModelOutputs::from_values`] expects them.
+	fn fields() -> Vec<FieldDescriptor>;
+
+	/// Creates this struct from a list of values, in the same order as [`ModelOutputs::fields`].
+	fn from_values(values: Vec<DynValue>) -> Result<Self>;
+}
+
+/// A type which can be used as a field of a [`ModelInputs`] or [`ModelOutputs`] struct.
+pub trait TypedField {
+	/// The element type this field expects, or `None` if any type is accepted.
+	fn element_type() -> Option<TensorElementType>;
+}
+
+/// A type which can be used as a field of a [`ModelOutputs`] struct.
+pub trait OutputField: TypedField + Sized {
+	/// Converts a session output into this type.
+	fn from_value(value: DynValue) -> Result<Self>;
+}
+
+impl<T: IntoTensorElementType + fmt::Debug> TypedField for Tensor<T> {
+	fn element_type() -> Option<TensorElementType> {
+		Some(T::into_tensor_element_type())
+	}
+}
+impl<T: IntoTensorElementType + fmt::Debug> TypedField for &Tensor<T> {
+	fn element_type() -> Option<TensorElementType> {
+		Some(T::into_tensor_element_type())
+	}
+}
+impl<T: IntoTensorElementType + fmt::Debug> TypedField for ValueRef<'_, TensorValueType<T>> {
+	fn element_type() -> Option<TensorElementType> {
+		Some(T::into_tensor_element_type())
+	}
+}
+impl<T: IntoTensorElementType + fmt::Debug> TypedField for ValueRefMut<'_, TensorValueType<T>> {
+	fn element_type() -> Option<TensorElementType> {
+		Some(T::into_tensor_element_type())
+	}
+}
+impl TypedField for DynTensor {
+	fn element_type() -> Option<TensorElementType> {
+		None
+	}
+}
+impl TypedField for ValueRef<'_, DynTensorValueType> {
+	fn element_type() -> Option<TensorElementType> {
+		None
+	}
+}
+impl TypedField for DynValue {
+	fn element_type() -> Option<TensorElementType> {
+		None
+	}
+}
+impl TypedField for ValueRef<'_, DynValueTypeMarker> {
+	fn element_type() -> Option<TensorElementType> {
+		None
+	}
+}
+
+impl<T: IntoTensorElementType + fmt::Debug> OutputField for Tensor<T> {
+	fn from_value(value: DynValue) -> Result<Self> {
+		value.downcast()
+	}
+}
+impl OutputField for DynTensor {
+	fn from_value(value: DynValue) -> Result<Self> {
+		value.downcast()
+	}
+}
+impl OutputField for DynValue {
+	fn from_value(value: DynValue) -> Result<Self> {
+		Ok(value)
+	}
+}
+
+/// A handle to a [`Session`] whose inputs & outputs have been validated against the structs `I` and `O`. Created
+/// via [`Session::typed`].
+pub struct TypedSession<'s, I, O> {
+	session: &'s Session,
+	input_names: SmallVec<&'static str, { STACK_SESSION_INPUTS }>,
+	output_names: SmallVec<&'static str, { STACK_SESSION_OUTPUTS }>,
+	_marker: PhantomData<fn(I) -> O>
+}
+
+impl<'s, I: ModelInputs, O: ModelOutputs> TypedSession<'s, I, O> {
+	pub(crate) fn new(session: &'s Session) -> Result<Self> {
+		let inputs = I::fields();
+		for field in &inputs {
+			let Some(input) = session.inputs.iter().find(|i| i.name == field.name) else {
+				return Err(missing_field_error("input", field, session.inputs.iter().map(|i| i.name.as_str())));
+			};
+			validate_field("input", field, &input.input_type)?;
+		}
+		if let Some(input) = session.inputs.iter().find(|i| !inputs.iter().any(|f| f.name == i.name)) {
+			return Err(Error::new_with_code(
+				ErrorCode::InvalidArgument,
+				format!("Model input '{}' is not provided by any field of `{}`", input.name, core::any::type_name::<I>())
+			));
+		}
+
+		let outputs = O::fields();
+		for field in &outputs {
+			let Some(output) = session.outputs.iter().find(|o| o.name == field.name) else {
+				return Err(missing_field_error("output", field, session.outputs.iter().map(|o| o.name.as_str())));
+			};
+			validate_field("output", field, &output.output_type)?;
+		}
+
+		Ok(Self {
+			session,
+			input_names: inputs.iter().map(|f| f.name).collect(),
+			output_names: outputs.iter().map(|f| f.name).collect(),
+			_marker: PhantomData
+		})
+	}
+
+	/// Returns the underlying session.
+	#[must_use]
+	pub fn session(&self) -> &'s Session {
+		self.session
+	}
+
+	/// Run inference on the model with the given inputs.
+	///
+	/// See [`Session::run`] for more details.
+	pub fn run(&self, inputs: I::WithLifetime<'_>) -> Result<O> {
+		self.run_inner(inputs, None)
+	}
+
+	/// Run inference on the model with the given inputs & [`RunOptions`].
+	///
+	/// See [`Session::run_with_options`] for more details.
+	pub fn run_with_options(&self, inputs: I::WithLifetime<'_>, run_options: &RunOptions<NoSelectedOutputs>) -> Result<O> {
+		self.run_inner(inputs, Some(run_options))
+	}
+
+	fn run_inner<'v>(&self, inputs: I::WithLifetime<'v>, run_options: Option<&RunOptions<NoSelectedOutputs>>) -> Result<O> {
+		let input_values = inputs.into_values();
+		let outputs: SessionOutputs<'_, 's> = self.session.run_inner_with_outputs(
+			self.input_names.clone(),
+			input_values.iter().collect(),
+			self.output_names.clone(),
+			iter::repeat_with(|| None).take(self.output_names.len()).collect(),
+			run_options.map(|r| &r.inner)
+		)?;
+		O::from_values(outputs.into_iter().map(|(_, v)| v).collect())
+	}
+}
+
+impl<I, O> fmt::Debug for TypedSession<'_, I, O> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("TypedSession")
+			.field("session", &self.session)
+			.field("inputs", &core::any::type_name::<I>())
+			.field("outputs", &core::any::type_name::<O>())
+			.finish()
+	}
+}
+
+fn missing_field_error<'a>(kind: &str, field: &FieldDescriptor, available: impl Iterator<Item = &'a str>) -> Error {
+	let available: Vec<String> = available.map(|n| format!("'{n}'")).collect();
+	Error::new_with_code(ErrorCode::InvalidArgument, format!("Model has no {kind} named '{}'; available {kind}s are: {}", field.name, available.join(", ")))
+}
+
+fn validate_field(kind: &str, field: &FieldDescriptor, dtype: &ValueType) -> Result<()> {
+	if field.element_type.is_none() && field.rank.is_none() {
+		return Ok(());
+	}
+
+	let ValueType::Tensor { ty, shape, .. } = dtype else {
+		return Err(Error::new_with_code(ErrorCode::InvalidArgument, format!("Model {kind} '{}' is of type {dtype}, but a tensor was expected", field.name)));
+	};
+	if let Some(expected) = field.element_type {
+		if expected != *ty {
+			return Err(Error::new_with_code(
+				ErrorCode::InvalidArgument,
+				format!("Model {kind} '{}' has element type {ty}, but the field expects {expected}", field.name)
+			));
+		}
+	}
+	if let Some(rank) = field.rank {
+		if rank != shape.len() {
+			return Err(Error::new_with_code(
+				ErrorCode::InvalidArgument,
+				format!("Model {kind} '{}' has shape {shape} (rank {}), but the field expects rank {rank}", field.name, shape.len())
+			));
+		}
+	}
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{FieldDescriptor, ModelInputs, ModelOutputs, validate_field};
+	use crate::{
+		session::{Session, SessionInputValue},
+		tensor::{Shape, SymbolicDimensions, TensorElementType},
+		value::{DynValue, Tensor, TensorRef, ValueType}
+	};
+
+	struct UpsampleInputs<'a> {
+		image: TensorRef<'a, f32>
+	}
+
+	impl ModelInputs for UpsampleInputs<'_> {
+		type WithLifetime<'v>
+			= UpsampleInputs<'v>
+		where
+			Self: 'v;
+
+		fn fields() -> Vec<FieldDescriptor> {
+			vec![FieldDescriptor {
+				name: "up_sampling2d_input:0",
+				element_type: Some(TensorElementType::Float32),
+				rank: Some(4)
+			}]
+		}
+
+		fn into_values<'v>(self) -> Vec<SessionInputValue<'v>>
+		where
+			Self: 'v
+		{
+			vec![self.image.into()]
+		}
+	}
+
+	struct UpsampleOutputs {
+		upsampled: Tensor<f32>
+	}
+
+	impl ModelOutputs for UpsampleOutputs {
+		fn fields() -> Vec<FieldDescriptor> {
+			vec![FieldDescriptor {
+				name: "Identity:0",
+				element_type: Some(TensorElementType::Float32),
+				rank: Some(4)
+			}]
+		}
+
+		fn from_values(values: Vec<DynValue>) -> crate::Result<Self> {
+			let mut values = values.into_iter();
+			Ok(Self {
+				upsampled: values.next().expect("one value per field").downcast()?
+			})
+		}
+	}
+
+	#[test]
+	fn test_validate_field() {
+		let dtype = ValueType::Tensor {
+			ty: TensorElementType::Float32,
+			shape: Shape::new([-1, -1, -1, 3]),
+			dimension_symbols: SymbolicDimensions::empty(4)
+		};
+		let field = FieldDescriptor {
+			name: "up_sampling2d_input:0",
+			element_type: Some(TensorElementType::Float32),
+			rank: Some(4)
+		};
+		assert!(validate_field("input", &field, &dtype).is_ok());
+		assert!(validate_field("input", &FieldDescriptor { rank: Some(3), ..field.clone() }, &dtype).is_err());
+		assert!(
+			validate_field(
+				"input",
+				&FieldDescriptor {
+					element_type: Some(TensorElementType::Int64),
+					..field.clone()
+				},
+				&dtype
+			)
+			.is_err()
+		);
+		assert!(
+			validate_field(
+				"input",
+				&FieldDescriptor {
+					element_type: None,
+					rank: None,
+					..field
+				},
+				&ValueType::Optional(Box::new(dtype))
+			)
+			.is_ok()
+		);
+	}
+
+	#[test]
+	fn test_typed_session() -> crate::Result<()> {
+		let session = Session::builder()?.commit_from_file("tests/data/upsample.onnx")?;
+		let upsample = session.typed::<UpsampleInputs, UpsampleOutputs>()?;
+
+		// the same handle can be reused with inputs borrowed for different lifetimes
+		for size in [16, 32] {
+			let image = ndarray::Array4::<f32>::zeros((1, size, size, 3));
+			let outputs = upsample.run(UpsampleInputs {
+				image: TensorRef::from_array_view(&image)?
+			})?;
+			assert_eq!(**outputs.upsampled.shape(), [1, size as i64 * 2, size as i64 * 2, 3]);
+		}
+
+		Ok(())
+	}
+}
@@ -0,0 +1,281 @@
+//! Pre-run validation & casting of session inputs; see [`SessionBuilder::with_input_validation`] &
+//! [`SessionBuilder::with_input_casting`].
+//!
+//! [`SessionBuilder::with_input_validation`]: crate::session::builder::SessionBuilder::with_input_validation
+//! [`SessionBuilder::with_input_casting`]: crate::session::builder::SessionBuilder::with_input_casting
+
+use alloc::{boxed::Box, string::ToString, vec::Vec};
+
+use smallvec::SmallVec;
+
+use super::{Input, SessionInputValue};
+use crate::{
+	error::{Error, InputMismatch, InputMismatchKind, Result},
+	tensor::TensorElementType,
+	util::STACK_SESSION_INPUTS,
+	value::{ValueType, cast_tensor}
+};
+
+/// Validates the provided `(name, type)` pairs against a session's `expected` inputs.
+pub(crate) fn validate_inputs<'a>(expected: &[Input], provided: impl IntoIterator<Item = (&'a str, &'a ValueType)>) -> Result<()> {
+	let mismatch = |input: &str, kind| Err(Error::from_input_mismatch(InputMismatch { input: input.to_string(), kind }));
+
+	// (symbol, size, first input the symbol was seen in)
+	let mut symbols: Vec<(&str, i64, &str)> = Vec::new();
+	let mut seen: Vec<&str> = Vec::with_capacity(expected.len());
+	for (name, actual) in provided {
+		let Some(input) = expected.iter().find(|i| i.name == name) else {
+			return mismatch(name, InputMismatchKind::Unknown);
+		};
+		seen.push(name);
+
+		let expected_type = match (&input.input_type, actual) {
+			(ValueType::Optional(inner), actual) if !matches!(actual, ValueType::Optional(_)) => inner.as_ref(),
+			(expected_type, _) => expected_type
+		};
+		match (expected_type, actual) {
+			(
+				ValueType::Tensor {
+					ty: expected_ty,
+					shape: expected_shape,
+					dimension_symbols
+				},
+				ValueType::Tensor {
+					ty: actual_ty, shape: actual_shape, ..
+				}
+			) => {
+				if expected_ty != actual_ty {
+					return mismatch(
+						name,
+						InputMismatchKind::ElementType {
+							expected: *expected_ty,
+							actual: *actual_ty
+						}
+					);
+				}
+				if expected_shape.len() != actual_shape.len() || expected_shape.iter().zip(actual_shape.iter()).any(|(&e, &a)| e >= 0 && e != a) {
+					return mismatch(
+						name,
+						InputMismatchKind::Shape {
+							expected: expected_shape.clone(),
+							actual: actual_shape.clone()
+						}
+					);
+				}
+
+				for ((&dim, symbol), &size) in expected_shape.iter().zip(dimension_symbols.iter()).zip(actual_shape.iter()) {
+					if dim >= 0 || symbol.is_empty() {
+						continue;
+					}
+					match symbols.iter().find(|(s, ..)| *s == symbol.as_str()) {
+						Some(&(_, first_size, first_input)) if first_size != size => {
+							return mismatch(
+								name,
+								InputMismatchKind::SymbolicDimension {
+									symbol: symbol.clone(),
+									first_input: first_input.to_string(),
+									expected: first_size,
+									actual: size
+								}
+							);
+						}
+						Some(_) => {}
+						None => symbols.push((symbol.as_str(), size, name))
+					}
+				}
+			}
+			(expected_type, actual) => {
+				if !is_compatible(expected_type, actual) {
+					return mismatch(
+						name,
+						InputMismatchKind::Type {
+							expected: Box::new(expected_type.clone()),
+							actual: Box::new(actual.clone())
+						}
+					);
+				}
+			}
+		}
+	}
+
+	if let Some(input) = expected
+		.iter()
+		.find(|i| !matches!(i.input_type, ValueType::Optional(_)) && !seen.contains(&i.name.as_str()))
+	{
+		return mismatch(&input.name, InputMismatchKind::Missing);
+	}
+
+	Ok(())
+}
+
+/// Casts tensor inputs whose element type differs from the one declared by the session's `expected` inputs. Returns the
+/// cast value in place of each input which needed casting, and `None` for the others.
+pub(crate) fn cast_inputs(
+	expected: &[Input],
+	names: &[&str],
+	values: &[&SessionInputValue<'_>]
+) -> Result<SmallVec<Option<SessionInputValue<'static>>, { STACK_SESSION_INPUTS }>> {
+	names
+		.iter()
+		.zip(values)
+		.map(|(name, value)| match cast_target(expected, name, value.dtype()) {
+			Some(ty) => cast_tensor(&***value, ty).map(|tensor| Some(SessionInputValue::from(tensor))),
+			None => Ok(None)
+		})
+		.collect()
+}
+
+/// Returns the element type a tensor input of type `actual` should be cast to, if it differs from the one declared for
+/// the input `name`. Strings are never cast.
+fn cast_target(expected: &[Input], name: &str, actual: &ValueType) -> Option<TensorElementType> {
+	let input = expected.iter().find(|i| i.name == name)?;
+	let expected_type = match &input.input_type {
+		ValueType::Optional(inner) => inner.as_ref(),
+		expected_type => expected_type
+	};
+	match (expected_type, actual) {
+		(ValueType::Tensor { ty: expected, .. }, ValueType::Tensor { ty: actual, .. })
+			if expected != actual
+				&& ![expected, actual]
+					.iter()
+					.any(|ty| matches!(ty, TensorElementType::String | TensorElementType::Undefined)) =>
+		{
+			Some(*expected)
+		}
+		_ => None
+	}
+}
+
+/// Checks that `actual` is the same kind of type as `expected`. Tensor shapes are not compared, since the shapes of
+/// tensors within sequences/optionals aren't known ahead of time.
+fn is_compatible(expected: &ValueType, actual: &ValueType) -> bool {
+	match (expected, actual) {
+		(ValueType::Tensor { ty: expected, .. }, ValueType::Tensor { ty: actual, .. }) => expected == actual,
+		(ValueType::SparseTensor { ty: expected, .. }, ValueType::SparseTensor { ty: actual, .. }) => expected == actual,
+		(ValueType::Sequence(expected), ValueType::Sequence(actual)) => is_compatible(expected, actual),
+		(ValueType::Map { key: ek, value: ev }, ValueType::Map { key: ak, value: av }) => ek == ak && ev == av,
+		(ValueType::Optional(expected), ValueType::Optional(actual)) => is_compatible(expected, actual),
+		_ => false
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use alloc::{boxed::Box, string::String};
+
+	use super::{cast_target, validate_inputs};
+	use crate::{
+		error::{ErrorCode, InputMismatchKind},
+		session::Input,
+		tensor::{Shape, SymbolicDimensions, TensorElementType},
+		value::ValueType
+	};
+
+	fn tensor(ty: TensorElementType, shape: &[i64], symbols: &[&str]) -> ValueType {
+		ValueType::Tensor {
+			ty,
+			shape: Shape::new(shape.iter().copied()),
+			dimension_symbols: SymbolicDimensions::new(symbols.iter().map(|s| String::from(*s)))
+		}
+	}
+
+	fn inputs() -> [Input; 2] {
+		[
+			Input {
+				name: String::from("input_ids"),
+				input_type: tensor(TensorElementType::Int64, &[-1, -1], &["batch", "sequence"])
+			},
+			Input {
+				name: String::from("pixel_values"),
+				input_type: tensor(TensorElementType::Float32, &[-1, 3, 224, 224], &["batch", "", "", ""])
+			}
+		]
+	}
+
+	fn kind(result: crate::Result<()>) -> (String, InputMismatchKind) {
+		let error = result.expect_err("validation should fail");
+		assert_eq!(error.code(), ErrorCode::InputMismatch);
+		let mismatch = error.input_mismatch().expect("error should have mismatch details");
+		(mismatch.input.clone(), mismatch.kind.clone())
+	}
+
+	#[test]
+	fn test_valid() -> crate::Result<()> {
+		let ids = tensor(TensorElementType::Int64, &[2, 17], &["", ""]);
+		let pixels = tensor(TensorElementType::Float32, &[2, 3, 224, 224], &["", "", "", ""]);
+		validate_inputs(&inputs(), [("pixel_values", &pixels), ("input_ids", &ids)])
+	}
+
+	#[test]
+	fn test_mismatches() {
+		let inputs = inputs();
+		let ids = tensor(TensorElementType::Int64, &[2, 17], &["", ""]);
+		let pixels = tensor(TensorElementType::Float32, &[2, 3, 224, 224], &["", "", "", ""]);
+
+		assert_eq!(kind(validate_inputs(&inputs, [("input_ids", &ids)])), (String::from("pixel_values"), InputMismatchKind::Missing));
+		assert_eq!(
+			kind(validate_inputs(&inputs, [("input_ids", &ids), ("pixel_values", &pixels), ("mask", &ids)])),
+			(String::from("mask"), InputMismatchKind::Unknown)
+		);
+
+		let wrong_ty = tensor(TensorElementType::Int32, &[2, 17], &["", ""]);
+		assert_eq!(
+			kind(validate_inputs(&inputs, [("input_ids", &wrong_ty), ("pixel_values", &pixels)])).1,
+			InputMismatchKind::ElementType {
+				expected: TensorElementType::Int64,
+				actual: TensorElementType::Int32
+			}
+		);
+
+		let wrong_rank = tensor(TensorElementType::Int64, &[34], &[""]);
+		assert!(matches!(kind(validate_inputs(&inputs, [("input_ids", &wrong_rank), ("pixel_values", &pixels)])).1, InputMismatchKind::Shape { .. }));
+		let wrong_dim = tensor(TensorElementType::Float32, &[2, 4, 224, 224], &["", "", "", ""]);
+		assert_eq!(
+			kind(validate_inputs(&inputs, [("input_ids", &ids), ("pixel_values", &wrong_dim)])).1,
+			InputMismatchKind::Shape {
+				expected: Shape::new([-1, 3, 224, 224]),
+				actual: Shape::new([2, 4, 224, 224])
+			}
+		);
+
+		let wrong_batch = tensor(TensorElementType::Float32, &[3, 3, 224, 224], &["", "", "", ""]);
+		assert_eq!(
+			kind(validate_inputs(&inputs, [("input_ids", &ids), ("pixel_values", &wrong_batch)])),
+			(
+				String::from("pixel_values"),
+				InputMismatchKind::SymbolicDimension {
+					symbol: String::from("batch"),
+					first_input: String::from("input_ids"),
+					expected: 2,
+					actual: 3
+				}
+			)
+		);
+
+		let sequence = ValueType::Sequence(Box::new(ids.clone()));
+		assert!(matches!(kind(validate_inputs(&inputs, [("input_ids", &sequence), ("pixel_values", &pixels)])).1, InputMismatchKind::Type { .. }));
+	}
+
+	#[test]
+	fn test_optional() -> crate::Result<()> {
+		let inputs = [Input {
+			name: String::from("past"),
+			input_type: ValueType::Optional(Box::new(tensor(TensorElementType::Float32, &[-1, 8], &["batch", ""])))
+		}];
+		validate_inputs(&inputs, [])?;
+		validate_inputs(&inputs, [("past", &tensor(TensorElementType::Float32, &[4, 8], &["", ""]))])?;
+		assert!(validate_inputs(&inputs, [("past", &tensor(TensorElementType::Float32, &[4, 7], &["", ""]))]).is_err());
+		Ok(())
+	}
+
+	#[test]
+	fn test_cast_target() {
+		let inputs = inputs();
+		let ids = tensor(TensorElementType::Int32, &[2, 17], &["", ""]);
+		assert_eq!(cast_target(&inputs, "input_ids", &ids), Some(TensorElementType::Int64));
+		assert_eq!(cast_target(&inputs, "pixel_values", &tensor(TensorElementType::Float32, &[1, 3, 224, 224], &["", "", "", ""])), None);
+		assert_eq!(cast_target(&inputs, "pixel_values", &tensor(TensorElementType::String, &[1, 3, 224, 224], &["", "", "", ""])), None);
+		assert_eq!(cast_target(&inputs, "mask", &ids), None);
+		assert_eq!(cast_target(&inputs, "input_ids", &ValueType::Sequence(Box::new(ids.clone()))), None);
+	}
+}
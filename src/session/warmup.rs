@@ -0,0 +1,241 @@
+//! Warming up sessions before they serve requests; see [`SessionBuilder::with_warmup`].
+//!
+//! The first few runs of a freshly created session are usually much slower than later runs, since ONNX Runtime (and
+//! execution providers) lazily allocate buffers, prepack weights, and compile kernels. Warming up a session performs
+//! this work ahead of time by running it on synthetic inputs.
+//!
+//! [`SessionBuilder::with_warmup`]: crate::session::builder::SessionBuilder::with_warmup
+
+use alloc::{format, string::String, vec, vec::Vec};
+use core::time::Duration;
+use std::time::Instant;
+
+use super::{Input, Session};
+use crate::{
+	error::{Error, ErrorCode, Result},
+	memory::Allocator,
+	tensor::{Shape, SymbolicDimensions, TensorElementType},
+	value::{DynMap, DynSparseTensor, DynTensor, DynValue, DynValueTypeMarker, Sequence, Tensor, ValueType}
+};
+
+/// Configures how a session is warmed up; see [`SessionBuilder::with_warmup`] & [`Session::warmup`].
+///
+/// Warmup runs the session on synthetic inputs generated from [`Session::inputs`]:
+/// - numeric & boolean tensors are filled with zeros;
+/// - string tensors are filled with empty strings;
+/// - maps contain a single entry with a zero (or empty string) key & value;
+/// - sequences contain a single synthetic element;
+/// - optional inputs are given a synthetic value of their inner type.
+///
+/// Dynamic dimensions use the size configured for their symbol via [`Warmup::with_dimension`], falling back to
+/// [`Warmup::with_default_dimension`] (1 by default) for unnamed or unconfigured dimensions. Since kernels may be
+/// specialized for particular shapes, it's best to configure dimensions to match the inputs the session will see in
+/// production.
+///
+/// ```
+/// # use ort::session::{warmup::Warmup, Session};
+/// # fn main() -> ort::Result<()> {
+/// let session = Session::builder()?
+/// 	.with_warmup(Warmup::new(3).with_dimension("batch_size", 8))?
+/// 	.commit_from_file("tests/data/upsample.onnx")?;
+///
+/// let report = session.warmup_report().unwrap();
+/// assert_eq!(report.runs().len(), 3);
+/// println!("first run took {:?}, last run took {:?}", report.first(), report.last());
+/// # 	Ok(())
+/// # }
+/// ```
+///
+/// [`SessionBuilder::with_warmup`]: crate::session::builder::SessionBuilder::with_warmup
+#[derive(Debug, Clone)]
+pub struct Warmup {
+	runs: usize,
+	dimensions: Vec<(String, i64)>,
+	default_dimension: i64
+}
+
+impl Default for Warmup {
+	fn default() -> Self {
+		Self::new(1)
+	}
+}
+
+impl Warmup {
+	/// Creates a warmup configuration which runs the session `runs` times.
+	pub fn new(runs: usize) -> Self {
+		Self {
+			runs,
+			dimensions: Vec::new(),
+			default_dimension: 1
+		}
+	}
+
+	/// Sets the size used for dynamic dimensions named `symbol`.
+	pub fn with_dimension(mut self, symbol: impl Into<String>, size: i64) -> Self {
+		let symbol = symbol.into();
+		self.dimensions.retain(|(s, _)| *s != symbol);
+		self.dimensions.push((symbol, size));
+		self
+	}
+
+	/// Sets the size used for dynamic dimensions which are unnamed or whose symbol was not configured with
+	/// [`Warmup::with_dimension`]. Defaults to 1.
+	pub fn with_default_dimension(mut self, size: i64) -> Self {
+		self.default_dimension = size;
+		self
+	}
+
+	/// Returns the number of warmup runs.
+	pub fn runs(&self) -> usize {
+		self.runs
+	}
+
+	/// Generates synthetic values for each of the given session inputs.
+	pub fn synthetic_inputs(&self, inputs: &[Input]) -> Result<Vec<(String, DynValue)>> {
+		inputs
+			.iter()
+			.map(|input| {
+				let value = self
+					.synthesize(&input.input_type)
+					.map_err(|e| Error::new_with_code(e.code(), format!("Failed to create synthetic value for input `{}`: {e}", input.name)))?;
+				Ok((input.name.clone(), value))
+			})
+			.collect()
+	}
+
+	fn resolve_shape(&self, shape: &Shape, dimension_symbols: &SymbolicDimensions) -> Shape {
+		shape
+			.iter()
+			.enumerate()
+			.map(|(i, &dim)| {
+				if dim >= 0 {
+					return dim;
+				}
+				dimension_symbols
+					.get(i)
+					.and_then(|symbol| self.dimensions.iter().find(|(s, _)| s == symbol))
+					.map_or(self.default_dimension, |(_, size)| *size)
+			})
+			.collect()
+	}
+
+	fn synthesize(&self, ty: &ValueType) -> Result<DynValue> {
+		match ty {
+			ValueType::Tensor { ty, shape, dimension_symbols } => Ok(synthetic_tensor(*ty, self.resolve_shape(shape, dimension_symbols))?.into_dyn()),
+			ValueType::Sequence(element) => Ok(Sequence::<DynValueTypeMarker>::new([self.synthesize(element)?])?.into_dyn()),
+			ValueType::Map { key, value } => {
+				let keys = synthetic_tensor(*key, Shape::new([1]))?;
+				let values = synthetic_tensor(*value, Shape::new([1]))?;
+				Ok(DynMap::new_dyn_kv(keys, values)?.into_dyn())
+			}
+			ValueType::SparseTensor { ty, shape, dimension_symbols } => {
+				Ok(DynSparseTensor::new_empty_coo(*ty, self.resolve_shape(shape, dimension_symbols))?.into_dyn())
+			}
+			ValueType::Optional(inner) => self.synthesize(inner)
+		}
+	}
+}
+
+fn synthetic_tensor(ty: TensorElementType, shape: Shape) -> Result<DynTensor> {
+	match ty {
+		TensorElementType::String => {
+			let data = vec![""; shape.num_elements()];
+			Ok(Tensor::from_string_array((shape, data.as_slice()))?.upcast())
+		}
+		TensorElementType::Undefined => Err(Error::new_with_code(ErrorCode::InvalidArgument, "Cannot create a tensor of undefined type")),
+		// `DynTensor::new` zeroes CPU tensors
+		ty => DynTensor::new(&Allocator::default(), ty, shape)
+	}
+}
+
+/// Timings of each run performed during warmup; see [`Session::warmup`].
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct WarmupReport {
+	runs: Vec<Duration>
+}
+
+impl WarmupReport {
+	/// Returns the duration of each warmup run, in order.
+	pub fn runs(&self) -> &[Duration] {
+		&self.runs
+	}
+
+	/// Returns the duration of the first run, which is typically the slowest.
+	pub fn first(&self) -> Option<Duration> {
+		self.runs.first().copied()
+	}
+
+	/// Returns the duration of the last run, which should be close to the session's steady-state latency.
+	pub fn last(&self) -> Option<Duration> {
+		self.runs.last().copied()
+	}
+
+	/// Returns the total time spent running the session.
+	pub fn total(&self) -> Duration {
+		self.runs.iter().sum()
+	}
+
+	/// Returns the mean duration of all runs.
+	pub fn mean(&self) -> Option<Duration> {
+		u32::try_from(self.runs.len()).ok().filter(|&n| n > 0).map(|n| self.total() / n)
+	}
+}
+
+impl Session {
+	/// Warms up this session by running it on synthetic inputs, returning the time taken by each run. See [`Warmup`]
+	/// for how inputs are generated.
+	///
+	/// This is performed automatically when the session is created if [`SessionBuilder::with_warmup`] was used.
+	///
+	/// [`SessionBuilder::with_warmup`]: crate::session::builder::SessionBuilder::with_warmup
+	pub fn warmup(&self, warmup: &Warmup) -> Result<WarmupReport> {
+		let inputs = warmup.synthetic_inputs(&self.inputs)?;
+		let inputs: Vec<(&str, &DynValue)> = inputs.iter().map(|(name, value)| (name.as_str(), value)).collect();
+
+		let mut runs = Vec::with_capacity(warmup.runs);
+		for _ in 0..warmup.runs {
+			let start = Instant::now();
+			drop(self.run(inputs.clone())?);
+			runs.push(start.elapsed());
+		}
+
+		let report = WarmupReport { runs };
+		crate::info!(first = ?report.first(), last = ?report.last(), total = ?report.total(), "Session warmup complete");
+		Ok(report)
+	}
+
+	/// Returns the timings of the warmup performed when this session was created, if it was configured with
+	/// [`SessionBuilder::with_warmup`].
+	///
+	/// [`SessionBuilder::with_warmup`]: crate::session::builder::SessionBuilder::with_warmup
+	pub fn warmup_report(&self) -> Option<&WarmupReport> {
+		self.warmup_report.as_ref()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use core::time::Duration;
+
+	use super::{Warmup, WarmupReport};
+	use crate::tensor::{Shape, SymbolicDimensions};
+
+	#[test]
+	fn test_resolve_shape() {
+		let warmup = Warmup::new(1).with_dimension("batch", 4).with_default_dimension(2);
+		let shape = Shape::new([-1, 3, -1, -1]);
+		let symbols = SymbolicDimensions::new(["batch".into(), String::new(), "sequence".into(), String::new()]);
+		assert_eq!(*warmup.resolve_shape(&shape, &symbols), [4, 3, 2, 2]);
+	}
+
+	#[test]
+	fn test_report() {
+		let report = WarmupReport {
+			runs: vec![Duration::from_millis(30), Duration::from_millis(10), Duration::from_millis(5)]
+		};
+		assert_eq!(report.total(), Duration::from_millis(45));
+		assert_eq!(report.mean(), Some(Duration::from_millis(15)));
+		assert_eq!(report.last(), Some(Duration::from_millis(5)));
+		assert_eq!(WarmupReport::default().mean(), None);
+	}
+}
@@ -0,0 +1,140 @@
+use alloc::{format, string::String, vec::Vec};
+use core::{ptr::NonNull, time::Duration};
+use std::{
+	sync::{
+		Arc, Condvar, Mutex, MutexGuard,
+		atomic::{AtomicBool, Ordering}
+	},
+	thread,
+	time::Instant
+};
+
+use super::run_options::UntypedRunOptions;
+use crate::{
+	error::{Error, ErrorCode, Result},
+	ortsys
+};
+
+struct RunOptionsPtr(NonNull<ort_sys::OrtRunOptions>);
+
+// `RunOptionsSetTerminate` may be called from any thread.
+unsafe impl Send for RunOptionsPtr {}
+
+impl RunOptionsPtr {
+	#[cfg_attr(not(feature = "tracing"), allow(unused_variables))]
+	fn terminate(&self) {
+		let terminate = || -> Result<()> {
+			ortsys![unsafe RunOptionsSetTerminate(self.0.as_ptr())?];
+			Ok(())
+		};
+		if let Err(e) = terminate() {
+			crate::error!("Failed to terminate session run after deadline: {e}");
+		}
+	}
+}
+
+struct Deadline {
+	deadline: Instant,
+	run_options: RunOptionsPtr,
+	timed_out: Arc<AtomicBool>
+}
+
+struct Deadlines {
+	pending: Vec<Deadline>,
+	thread_started: bool
+}
+
+/// Deadlines of all in-flight runs, shared by every [`Watchdog`] and enforced by a single thread.
+static DEADLINES: Mutex<Deadlines> = Mutex::new(Deadlines {
+	pending: Vec::new(),
+	thread_started: false
+});
+static DEADLINES_CHANGED: Condvar = Condvar::new();
+
+fn lock_deadlines() -> MutexGuard<'static, Deadlines> {
+	DEADLINES.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+fn watchdog_thread() {
+	let mut deadlines = lock_deadlines();
+	loop {
+		let now = Instant::now();
+		// Runs are terminated while the lock is held, so a `Watchdog` that has removed its deadline can be sure its run
+		// options are no longer in use by this thread.
+		deadlines.pending.retain(|d| {
+			if d.deadline > now {
+				return true;
+			}
+			d.timed_out.store(true, Ordering::Release);
+			d.run_options.terminate();
+			false
+		});
+		deadlines = match deadlines.pending.iter().map(|d| d.deadline).min() {
+			Some(next) => DEADLINES_CHANGED.wait_timeout(deadlines, next - now).unwrap_or_else(|e| e.into_inner()).0,
+			None => DEADLINES_CHANGED.wait(deadlines).unwrap_or_else(|e| e.into_inner())
+		};
+	}
+}
+
+/// Terminates a session run if it does not complete before a deadline.
+///
+/// Deadlines are enforced by one thread shared between all watchdogs, which is spawned the first time a watchdog is
+/// created and lives for the rest of the process.
+///
+/// The watchdog holds a pointer to the run's options without borrowing them; the owner must ensure the watchdog is
+/// dropped before the options are.
+pub(crate) struct Watchdog {
+	timed_out: Arc<AtomicBool>,
+	timeout: Duration
+}
+
+impl Watchdog {
+	pub(crate) fn new(run_options: &UntypedRunOptions, deadline: Instant) -> Result<Self> {
+		let timed_out = Arc::new(AtomicBool::new(false));
+		let timeout = deadline.saturating_duration_since(Instant::now());
+		let run_options = RunOptionsPtr(run_options.ptr);
+		if timeout.is_zero() {
+			// No need to involve the thread; terminate the run before it even starts.
+			timed_out.store(true, Ordering::Release);
+			run_options.terminate();
+			return Ok(Self { timed_out, timeout });
+		}
+
+		let mut deadlines = lock_deadlines();
+		if !deadlines.thread_started {
+			thread::Builder::new()
+				.name(String::from("ort-run-watchdog"))
+				.spawn(watchdog_thread)
+				.map_err(Error::wrap)?;
+			deadlines.thread_started = true;
+		}
+		deadlines.pending.push(Deadline {
+			deadline,
+			run_options,
+			timed_out: Arc::clone(&timed_out)
+		});
+		DEADLINES_CHANGED.notify_one();
+		Ok(Self { timed_out, timeout })
+	}
+
+	/// Stops the watchdog and converts `result` into a [`ErrorCode::Timeout`] error if the run was terminated by the
+	/// watchdog.
+	pub(crate) fn finish<T>(self, result: Result<T>) -> Result<T> {
+		let (timed_out, timeout) = (Arc::clone(&self.timed_out), self.timeout);
+		drop(self);
+		match result {
+			Err(_) if timed_out.load(Ordering::Acquire) => {
+				Err(Error::new_with_code(ErrorCode::Timeout, format!("Session run was terminated after exceeding its deadline ({timeout:?})")))
+			}
+			result => result
+		}
+	}
+}
+
+impl Drop for Watchdog {
+	fn drop(&mut self) {
+		// Even if the run has already timed out, we must take the lock to wait for the watchdog thread to finish
+		// terminating it.
+		lock_deadlines().pending.retain(|d| !Arc::ptr_eq(&d.timed_out, &self.timed_out));
+	}
+}
@@ -0,0 +1,205 @@
+//! Conversions between [Apache Arrow](https://arrow.apache.org/) arrays & tensors.
+//!
+//! Arrow arrays are 1-dimensional, so each array corresponds to a single column of a tabular model's inputs; see
+//! [`Session::inputs_from_record_batch`](crate::session::Session::inputs_from_record_batch) &
+//! [`SessionOutputs::to_record_batch`](crate::session::SessionOutputs::to_record_batch) to convert whole
+//! `RecordBatch`es.
+
+use alloc::{format, string::String, sync::Arc, vec, vec::Vec};
+use core::fmt::Debug;
+
+use arrow_array::{
+	Array, ArrayRef, ArrowPrimitiveType, BooleanArray, FixedSizeListArray, GenericStringArray, MapArray, OffsetSizeTrait, PrimitiveArray, StructArray,
+	builder::StringBuilder,
+	cast::AsArray,
+	types::{Float32Type, Float64Type, Int8Type, Int16Type, Int32Type, Int64Type, UInt8Type, UInt16Type, UInt32Type, UInt64Type}
+};
+use arrow_buffer::{ArrowNativeType, Buffer, OffsetBuffer, ScalarBuffer};
+use arrow_schema::{DataType, Field, Fields};
+
+use super::{PrimitiveTensorElementType, Shape, TensorElementType};
+use crate::{
+	error::{Error, ErrorCode, Result},
+	memory::Allocator,
+	session::SessionInputValue,
+	value::{DynMapValueType, DynValue, Tensor, TensorRef, TensorValueTypeMarker, Value, ValueType}
+};
+
+fn invalid(message: impl Into<String>) -> Error {
+	Error::new_with_code(ErrorCode::InvalidArgument, message.into())
+}
+
+fn reject_nulls(array: &dyn Array) -> Result<()> {
+	match array.null_count() {
+		0 => Ok(()),
+		n => Err(invalid(format!("Cannot convert Arrow array of type {} with {n} null(s) to a tensor", array.data_type())))
+	}
+}
+
+impl<'a, T: PrimitiveTensorElementType + ArrowNativeType + Debug> TensorRef<'a, T> {
+	/// Creates a 1-dimensional tensor view of an Arrow primitive array's values, without copying them.
+	///
+	/// Returns an error if the array contains nulls, since tensors have no way to represent them.
+	///
+	/// ```
+	/// # use arrow_array::Float32Array;
+	/// # use ort::value::TensorRef;
+	/// # fn main() -> ort::Result<()> {
+	/// let array = Float32Array::from(vec![1.0, 2.0, 3.0]);
+	/// let tensor = TensorRef::from_arrow(&array)?;
+	/// assert_eq!(tensor.extract_tensor().1, [1.0, 2.0, 3.0]);
+	/// # 	Ok(())
+	/// # }
+	/// ```
+	pub fn from_arrow<A: ArrowPrimitiveType<Native = T>>(array: &'a PrimitiveArray<A>) -> Result<TensorRef<'a, T>> {
+		reject_nulls(array)?;
+		TensorRef::from_array_view(([array.len()], array.values().as_ref()))
+	}
+}
+
+impl Tensor<String> {
+	/// Creates a 1-dimensional string tensor from an Arrow UTF-8 array.
+	///
+	/// Returns an error if the array contains nulls, since tensors have no way to represent them.
+	///
+	/// ```
+	/// # use arrow_array::StringArray;
+	/// # use ort::value::Tensor;
+	/// # fn main() -> ort::Result<()> {
+	/// let array = StringArray::from(vec!["a", "b"]);
+	/// let tensor = Tensor::from_arrow(&array)?;
+	/// assert_eq!(tensor.try_extract_strings()?.1, ["a", "b"]);
+	/// # 	Ok(())
+	/// # }
+	/// ```
+	pub fn from_arrow<O: OffsetSizeTrait>(array: &GenericStringArray<O>) -> Result<Tensor<String>> {
+		strings_to_tensor(array, Shape::from([array.len()]))
+	}
+}
+
+fn strings_to_tensor<O: OffsetSizeTrait>(array: &GenericStringArray<O>, shape: Shape) -> Result<Tensor<String>> {
+	reject_nulls(array)?;
+	Tensor::from_strings(shape, (0..array.len()).map(|i| array.value(i)))
+}
+
+/// Converts a primitive array to a tensor of the given shape. Arrays without nulls are borrowed; otherwise, nulls are
+/// replaced with `fill` (NaN for floating-point arrays), or an error is returned if there is no `fill`.
+fn primitive_to_value<A: ArrowPrimitiveType>(array: &PrimitiveArray<A>, shape: Shape, fill: Option<A::Native>) -> Result<SessionInputValue<'_>>
+where
+	A::Native: PrimitiveTensorElementType + Debug
+{
+	match fill {
+		Some(fill) if array.null_count() > 0 => {
+			let values: Vec<A::Native> = array.iter().map(|v| v.unwrap_or(fill)).collect();
+			Ok(Tensor::from_array((shape, values))?.into())
+		}
+		_ => {
+			reject_nulls(array)?;
+			Ok(TensorRef::from_array_view((shape, array.values().as_ref()))?.into())
+		}
+	}
+}
+
+/// Converts an Arrow array to a session input of the given shape; see
+/// [`Session::inputs_from_record_batch`](crate::session::Session::inputs_from_record_batch).
+pub(crate) fn array_to_value(array: &dyn Array, shape: Shape) -> Result<SessionInputValue<'_>> {
+	match array.data_type() {
+		DataType::Boolean => {
+			reject_nulls(array)?;
+			let values: Vec<bool> = array.as_boolean().values().iter().collect();
+			Ok(Tensor::from_array((shape, values))?.into())
+		}
+		DataType::Int8 => primitive_to_value(array.as_primitive::<Int8Type>(), shape, None),
+		DataType::Int16 => primitive_to_value(array.as_primitive::<Int16Type>(), shape, None),
+		DataType::Int32 => primitive_to_value(array.as_primitive::<Int32Type>(), shape, None),
+		DataType::Int64 => primitive_to_value(array.as_primitive::<Int64Type>(), shape, None),
+		DataType::UInt8 => primitive_to_value(array.as_primitive::<UInt8Type>(), shape, None),
+		DataType::UInt16 => primitive_to_value(array.as_primitive::<UInt16Type>(), shape, None),
+		DataType::UInt32 => primitive_to_value(array.as_primitive::<UInt32Type>(), shape, None),
+		DataType::UInt64 => primitive_to_value(array.as_primitive::<UInt64Type>(), shape, None),
+		#[cfg(feature = "half")]
+		DataType::Float16 => primitive_to_value(array.as_primitive::<arrow_array::types::Float16Type>(), shape, Some(half::f16::NAN)),
+		DataType::Float32 => primitive_to_value(array.as_primitive::<Float32Type>(), shape, Some(f32::NAN)),
+		DataType::Float64 => primitive_to_value(array.as_primitive::<Float64Type>(), shape, Some(f64::NAN)),
+		DataType::Utf8 => Ok(strings_to_tensor(array.as_string::<i32>(), shape)?.into()),
+		DataType::LargeUtf8 => Ok(strings_to_tensor(array.as_string::<i64>(), shape)?.into()),
+		ty => Err(invalid(format!("Arrow arrays of type {ty} cannot be converted to tensors")))
+	}
+}
+
+/// Concatenates the elements of several tensors of type `ty` into a single Arrow array.
+fn flat_array<Type: TensorValueTypeMarker + ?Sized>(ty: TensorElementType, tensors: &[&Value<Type>]) -> Result<ArrayRef> {
+	fn primitive<A: ArrowPrimitiveType>(bytes: &[u8]) -> ArrayRef {
+		let len = bytes.len() / size_of::<A::Native>();
+		// `from_slice_ref` copies into a new, suitably aligned buffer
+		Arc::new(PrimitiveArray::<A>::new(ScalarBuffer::new(Buffer::from_slice_ref(bytes), 0, len), None))
+	}
+
+	if ty == TensorElementType::String {
+		let mut builder = StringBuilder::new();
+		for tensor in tensors {
+			for string in tensor.try_extract_string_view()?.iter() {
+				builder.append_value(string);
+			}
+		}
+		return Ok(Arc::new(builder.finish()));
+	}
+
+	let mut bytes = Vec::new();
+	for tensor in tensors {
+		bytes.extend_from_slice(tensor.raw_bytes()?);
+	}
+	Ok(match ty {
+		TensorElementType::Bool => Arc::new(bytes.iter().map(|&b| Some(b != 0)).collect::<BooleanArray>()),
+		TensorElementType::Int8 => primitive::<Int8Type>(&bytes),
+		TensorElementType::Int16 => primitive::<Int16Type>(&bytes),
+		TensorElementType::Int32 => primitive::<Int32Type>(&bytes),
+		TensorElementType::Int64 => primitive::<Int64Type>(&bytes),
+		TensorElementType::Uint8 => primitive::<UInt8Type>(&bytes),
+		TensorElementType::Uint16 => primitive::<UInt16Type>(&bytes),
+		TensorElementType::Uint32 => primitive::<UInt32Type>(&bytes),
+		TensorElementType::Uint64 => primitive::<UInt64Type>(&bytes),
+		TensorElementType::Float16 => primitive::<arrow_array::types::Float16Type>(&bytes),
+		TensorElementType::Float32 => primitive::<Float32Type>(&bytes),
+		TensorElementType::Float64 => primitive::<Float64Type>(&bytes),
+		ty => return Err(invalid(format!("Tensors of type {ty} cannot be converted to Arrow arrays")))
+	})
+}
+
+/// Converts a sequence of maps, like the probabilities output by scikit-learn classifiers, to an Arrow map array with
+/// one entry per map.
+fn maps_to_array(value: &DynValue, key: TensorElementType, value_ty: TensorElementType) -> Result<ArrayRef> {
+	let maps = value.try_extract_sequence::<DynMapValueType>(&Allocator::default())?;
+	let entries = maps.iter().map(|map| map.key_value_tensors()).collect::<Result<Vec<_>>>()?;
+	let keys = flat_array(key, &entries.iter().map(|(keys, _)| keys).collect::<Vec<_>>())?;
+	let values = flat_array(value_ty, &entries.iter().map(|(_, values)| values).collect::<Vec<_>>())?;
+	let offsets = OffsetBuffer::from_lengths(entries.iter().map(|(keys, _)| keys.shape().num_elements()));
+
+	let fields = Fields::from(vec![Field::new("keys", keys.data_type().clone(), false), Field::new("values", values.data_type().clone(), true)]);
+	let entries = StructArray::try_new(fields.clone(), vec![keys, values], None).map_err(Error::wrap)?;
+	let field = Arc::new(Field::new("entries", DataType::Struct(fields), false));
+	Ok(Arc::new(MapArray::try_new(field, offsets, entries, None, false).map_err(Error::wrap)?))
+}
+
+/// Converts a session output to an Arrow array with one element per row; see
+/// [`SessionOutputs::to_record_batch`](crate::session::SessionOutputs::to_record_batch).
+pub(crate) fn value_to_array(value: &DynValue) -> Result<ArrayRef> {
+	match value.dtype() {
+		ValueType::Tensor { ty, shape, .. } => {
+			let values = flat_array(*ty, &[value])?;
+			match **shape {
+				[_] | [_, 1] => Ok(values),
+				[_, width] => {
+					let field = Arc::new(Field::new_list_field(values.data_type().clone(), false));
+					Ok(Arc::new(FixedSizeListArray::try_new(field, width as i32, values, None).map_err(Error::wrap)?))
+				}
+				_ => Err(invalid(format!("Cannot convert tensor of shape {shape} to an Arrow array; only tensors of rank 1 or 2 are supported")))
+			}
+		}
+		ValueType::Sequence(inner) => match **inner {
+			ValueType::Map { key, value: value_ty } => maps_to_array(value, key, value_ty),
+			_ => Err(invalid(format!("Cannot convert value of type {} to an Arrow array", value.dtype())))
+		},
+		t => Err(invalid(format!("Cannot convert value of type {t} to an Arrow array")))
+	}
+}
@@ -0,0 +1,390 @@
+//! Zero-copy interop with other libraries via [DLPack](https://dmlc.github.io/dlpack/latest/).
+//!
+//! Tensors can be imported from a [`DLManagedTensor`] with [`DynTensor::from_dlpack`], and exported to one with
+//! [`Tensor::to_dlpack`](crate::value::Tensor::to_dlpack). In both directions, the tensor's data is shared rather than
+//! copied.
+
+use alloc::{boxed::Box, format, sync::Arc, vec::Vec};
+use core::{
+	ffi::c_void,
+	ptr::{self, NonNull}
+};
+
+use super::{PrimitiveTensorElementType, Shape, TensorElementType};
+use crate::{
+	error::{Error, ErrorCode, Result},
+	memory::{AllocationDevice, AllocatorType, MemoryInfo, MemoryType},
+	value::{DowncastableTarget, DynTensor, Tensor, TensorValueType, TensorValueTypeMarker, Value, ValueInner, ValueType, tensor_from_array}
+};
+
+/// The type of device a [`DLTensor`]'s data resides on (`DLDeviceType`).
+#[repr(transparent)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DLDeviceType(pub i32);
+
+impl DLDeviceType {
+	pub const CPU: DLDeviceType = DLDeviceType(1);
+	pub const CUDA: DLDeviceType = DLDeviceType(2);
+	/// Pinned CUDA host memory, allocated by `cudaMallocHost`.
+	pub const CUDA_HOST: DLDeviceType = DLDeviceType(3);
+	pub const OPENCL: DLDeviceType = DLDeviceType(4);
+	pub const VULKAN: DLDeviceType = DLDeviceType(7);
+	pub const METAL: DLDeviceType = DLDeviceType(8);
+	pub const VPI: DLDeviceType = DLDeviceType(9);
+	pub const ROCM: DLDeviceType = DLDeviceType(10);
+	/// Pinned ROCm host memory, allocated by `hipMallocHost`.
+	pub const ROCM_HOST: DLDeviceType = DLDeviceType(11);
+	pub const EXT_DEV: DLDeviceType = DLDeviceType(12);
+	pub const CUDA_MANAGED: DLDeviceType = DLDeviceType(13);
+	pub const ONE_API: DLDeviceType = DLDeviceType(14);
+	pub const WEBGPU: DLDeviceType = DLDeviceType(15);
+	pub const HEXAGON: DLDeviceType = DLDeviceType(16);
+}
+
+/// The device a [`DLTensor`]'s data resides on (`DLDevice`).
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DLDevice {
+	pub device_type: DLDeviceType,
+	pub device_id: i32
+}
+
+/// The kind of a [`DLDataType`] (`DLDataTypeCode`).
+#[repr(transparent)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DLDataTypeCode(pub u8);
+
+impl DLDataTypeCode {
+	pub const INT: DLDataTypeCode = DLDataTypeCode(0);
+	pub const UINT: DLDataTypeCode = DLDataTypeCode(1);
+	pub const FLOAT: DLDataTypeCode = DLDataTypeCode(2);
+	pub const OPAQUE_HANDLE: DLDataTypeCode = DLDataTypeCode(3);
+	pub const BFLOAT: DLDataTypeCode = DLDataTypeCode(4);
+	pub const COMPLEX: DLDataTypeCode = DLDataTypeCode(5);
+	pub const BOOL: DLDataTypeCode = DLDataTypeCode(6);
+	pub const FLOAT8_E4M3FN: DLDataTypeCode = DLDataTypeCode(10);
+	pub const FLOAT8_E4M3FNUZ: DLDataTypeCode = DLDataTypeCode(11);
+	pub const FLOAT8_E5M2: DLDataTypeCode = DLDataTypeCode(12);
+	pub const FLOAT8_E5M2FNUZ: DLDataTypeCode = DLDataTypeCode(13);
+}
+
+/// The element type of a [`DLTensor`] (`DLDataType`).
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DLDataType {
+	pub code: DLDataTypeCode,
+	/// The number of bits in each lane.
+	pub bits: u8,
+	/// The number of lanes, for vector types; ONNX Runtime only supports `1`.
+	pub lanes: u16
+}
+
+/// A plain, unmanaged DLPack tensor (`DLTensor`).
+#[repr(C)]
+#[derive(Debug)]
+pub struct DLTensor {
+	pub data: *mut c_void,
+	pub device: DLDevice,
+	pub ndim: i32,
+	pub dtype: DLDataType,
+	pub shape: *mut i64,
+	/// Strides of each dimension, in elements; null for a compact row-major tensor.
+	pub strides: *mut i64,
+	pub byte_offset: u64
+}
+
+/// A DLPack tensor along with the means to release it (`DLManagedTensor`).
+///
+/// Whoever owns a `DLManagedTensor` must call its `deleter` exactly once when they are done with it.
+#[repr(C)]
+#[derive(Debug)]
+pub struct DLManagedTensor {
+	pub dl_tensor: DLTensor,
+	pub manager_ctx: *mut c_void,
+	pub deleter: Option<unsafe extern "C" fn(*mut DLManagedTensor)>
+}
+
+fn invalid(message: impl Into<alloc::string::String>) -> Error {
+	Error::new_with_code(ErrorCode::InvalidArgument, message.into())
+}
+
+fn element_type(dtype: DLDataType) -> Option<TensorElementType> {
+	if dtype.lanes != 1 {
+		return None;
+	}
+	Some(match (dtype.code, dtype.bits) {
+		(DLDataTypeCode::BOOL, 8) => TensorElementType::Bool,
+		(DLDataTypeCode::INT, 8) => TensorElementType::Int8,
+		(DLDataTypeCode::INT, 16) => TensorElementType::Int16,
+		(DLDataTypeCode::INT, 32) => TensorElementType::Int32,
+		(DLDataTypeCode::INT, 64) => TensorElementType::Int64,
+		(DLDataTypeCode::UINT, 8) => TensorElementType::Uint8,
+		(DLDataTypeCode::UINT, 16) => TensorElementType::Uint16,
+		(DLDataTypeCode::UINT, 32) => TensorElementType::Uint32,
+		(DLDataTypeCode::UINT, 64) => TensorElementType::Uint64,
+		(DLDataTypeCode::FLOAT, 16) => TensorElementType::Float16,
+		(DLDataTypeCode::FLOAT, 32) => TensorElementType::Float32,
+		(DLDataTypeCode::FLOAT, 64) => TensorElementType::Float64,
+		(DLDataTypeCode::BFLOAT, 16) => TensorElementType::Bfloat16,
+		(DLDataTypeCode::COMPLEX, 64) => TensorElementType::Complex64,
+		(DLDataTypeCode::COMPLEX, 128) => TensorElementType::Complex128,
+		(DLDataTypeCode::FLOAT8_E4M3FN, 8) => TensorElementType::Float8E4M3FN,
+		(DLDataTypeCode::FLOAT8_E4M3FNUZ, 8) => TensorElementType::Float8E4M3FNUZ,
+		(DLDataTypeCode::FLOAT8_E5M2, 8) => TensorElementType::Float8E5M2,
+		(DLDataTypeCode::FLOAT8_E5M2FNUZ, 8) => TensorElementType::Float8E5M2FNUZ,
+		_ => return None
+	})
+}
+
+fn data_type(ty: TensorElementType) -> Option<DLDataType> {
+	let (code, bits) = match ty {
+		TensorElementType::Bool => (DLDataTypeCode::BOOL, 8),
+		TensorElementType::Int8 => (DLDataTypeCode::INT, 8),
+		TensorElementType::Int16 => (DLDataTypeCode::INT, 16),
+		TensorElementType::Int32 => (DLDataTypeCode::INT, 32),
+		TensorElementType::Int64 => (DLDataTypeCode::INT, 64),
+		TensorElementType::Uint8 => (DLDataTypeCode::UINT, 8),
+		TensorElementType::Uint16 => (DLDataTypeCode::UINT, 16),
+		TensorElementType::Uint32 => (DLDataTypeCode::UINT, 32),
+		TensorElementType::Uint64 => (DLDataTypeCode::UINT, 64),
+		TensorElementType::Float16 => (DLDataTypeCode::FLOAT, 16),
+		TensorElementType::Float32 => (DLDataTypeCode::FLOAT, 32),
+		TensorElementType::Float64 => (DLDataTypeCode::FLOAT, 64),
+		TensorElementType::Bfloat16 => (DLDataTypeCode::BFLOAT, 16),
+		TensorElementType::Complex64 => (DLDataTypeCode::COMPLEX, 64),
+		TensorElementType::Complex128 => (DLDataTypeCode::COMPLEX, 128),
+		TensorElementType::Float8E4M3FN => (DLDataTypeCode::FLOAT8_E4M3FN, 8),
+		TensorElementType::Float8E4M3FNUZ => (DLDataTypeCode::FLOAT8_E4M3FNUZ, 8),
+		TensorElementType::Float8E5M2 => (DLDataTypeCode::FLOAT8_E5M2, 8),
+		TensorElementType::Float8E5M2FNUZ => (DLDataTypeCode::FLOAT8_E5M2FNUZ, 8),
+		TensorElementType::Int4 | TensorElementType::Uint4 | TensorElementType::String | TensorElementType::Undefined => return None
+	};
+	Some(DLDataType { code, bits, lanes: 1 })
+}
+
+fn memory_info(device: DLDevice) -> Result<MemoryInfo> {
+	let (allocation_device, memory_type) = match device.device_type {
+		DLDeviceType::CPU => (AllocationDevice::CPU, MemoryType::Default),
+		DLDeviceType::CUDA => (AllocationDevice::CUDA, MemoryType::Default),
+		DLDeviceType::CUDA_HOST => (AllocationDevice::CUDA_PINNED, MemoryType::CPUOutput),
+		DLDeviceType::ROCM => (AllocationDevice::HIP, MemoryType::Default),
+		DLDeviceType::ROCM_HOST => (AllocationDevice::HIP_PINNED, MemoryType::CPUOutput),
+		DLDeviceType(other) => return Err(invalid(format!("DLPack device type {other} is not supported by ONNX Runtime")))
+	};
+	MemoryInfo::new(allocation_device, device.device_id, AllocatorType::Device, memory_type)
+}
+
+fn device(memory_info: &MemoryInfo) -> Result<DLDevice> {
+	let device_type = match memory_info.allocation_device() {
+		AllocationDevice::CPU => DLDeviceType::CPU,
+		AllocationDevice::CUDA => DLDeviceType::CUDA,
+		AllocationDevice::CUDA_PINNED => DLDeviceType::CUDA_HOST,
+		AllocationDevice::HIP => DLDeviceType::ROCM,
+		AllocationDevice::HIP_PINNED => DLDeviceType::ROCM_HOST,
+		other => return Err(invalid(format!("Tensors allocated on device `{}` cannot be exported via DLPack", other.as_str())))
+	};
+	Ok(DLDevice {
+		device_type,
+		device_id: memory_info.device_id()
+	})
+}
+
+/// Returns `true` if `strides` describe a compact, row-major layout of `shape`. The strides of dimensions of size 1 are
+/// ignored, since they never affect which element is addressed.
+fn is_contiguous(shape: &[i64], strides: &[i64]) -> bool {
+	let mut expected = 1;
+	for (&dim, &stride) in shape.iter().zip(strides).rev() {
+		if dim != 1 && stride != expected {
+			return false;
+		}
+		expected *= dim;
+	}
+	true
+}
+
+/// Calls a [`DLManagedTensor`]'s deleter when the tensor created from it is dropped.
+struct DLManagedTensorGuard(NonNull<DLManagedTensor>);
+
+impl Drop for DLManagedTensorGuard {
+	fn drop(&mut self) {
+		let tensor = self.0.as_ptr();
+		if let Some(deleter) = unsafe { (*tensor).deleter } {
+			unsafe { deleter(tensor) };
+		}
+	}
+}
+
+/// The [`DLManagedTensor::manager_ctx`] of a tensor exported from ONNX Runtime.
+struct ExportedTensor {
+	managed: DLManagedTensor,
+	_shape: Vec<i64>,
+	_value: Arc<ValueInner>
+}
+
+unsafe extern "C" fn delete_exported(tensor: *mut DLManagedTensor) {
+	drop(unsafe { Box::from_raw((*tensor).manager_ctx.cast::<ExportedTensor>()) });
+}
+
+impl DynTensor {
+	/// Wraps the data of a DLPack tensor without copying it. The tensor's deleter is called once the returned tensor,
+	/// and every view of it, is dropped.
+	///
+	/// The DLPack tensor must be compact & row-major (its strides, if any, must match its shape), with one lane per
+	/// element. Data on CPU, CUDA & ROCm devices is supported.
+	///
+	/// ```
+	/// # use ort::value::{DynTensor, Tensor};
+	/// # fn main() -> ort::Result<()> {
+	/// let tensor = Tensor::from_array(([2usize, 2], vec![1.0_f32, 2.0, 3.0, 4.0]))?;
+	/// let managed = tensor.to_dlpack()?;
+	///
+	/// let imported = unsafe { DynTensor::from_dlpack(managed)? };
+	/// assert_eq!(imported.data_ptr()?, tensor.data_ptr()?);
+	/// assert_eq!(imported.try_extract_tensor::<f32>()?.1, [1.0, 2.0, 3.0, 4.0]);
+	/// # 	Ok(())
+	/// # }
+	/// ```
+	///
+	/// # Safety
+	/// `tensor` must point to a valid `DLManagedTensor` whose data stays valid until its deleter is called. On success,
+	/// ownership of `tensor` is transferred to the returned tensor; on error, the caller remains responsible for
+	/// calling its deleter.
+	pub unsafe fn from_dlpack(tensor: NonNull<DLManagedTensor>) -> Result<DynTensor> {
+		let dl_tensor = unsafe { &tensor.as_ref().dl_tensor };
+		let ty = element_type(dl_tensor.dtype).ok_or_else(|| invalid(format!("DLPack data type {:?} is not supported by ONNX Runtime", dl_tensor.dtype)))?;
+
+		let ndim = usize::try_from(dl_tensor.ndim).map_err(|_| invalid(format!("DLPack tensor has invalid rank {}", dl_tensor.ndim)))?;
+		let dims: &[i64] = if ndim == 0 { &[] } else { unsafe { core::slice::from_raw_parts(dl_tensor.shape, ndim) } };
+		if dims.iter().any(|&d| d < 0) {
+			return Err(invalid(format!("DLPack tensor has invalid shape {dims:?}")));
+		}
+		if !dl_tensor.strides.is_null() && ndim > 0 {
+			let strides = unsafe { core::slice::from_raw_parts(dl_tensor.strides, ndim) };
+			if !is_contiguous(dims, strides) {
+				return Err(invalid(format!(
+					"DLPack tensor with shape {dims:?} & strides {strides:?} is not contiguous; make it contiguous before importing it"
+				)));
+			}
+		}
+
+		let memory_info = memory_info(dl_tensor.device)?;
+		let data = dl_tensor.data.cast::<u8>().wrapping_add(dl_tensor.byte_offset as usize);
+		let mut value = tensor_from_array(memory_info, Shape::from(dims), data.cast(), ty.byte_size(1), ty, None)?;
+		// Only take ownership once the tensor has been created, so the deleter isn't called if creation fails.
+		Arc::get_mut(&mut value.inner).expect("tensor was just created")._backing = Some(Box::new(DLManagedTensorGuard(tensor)));
+		Ok(value)
+	}
+}
+
+impl<T: PrimitiveTensorElementType + core::fmt::Debug> Tensor<T> {
+	/// Wraps the data of a DLPack tensor without copying it, returning an error if its data type does not correspond
+	/// to `T`.
+	///
+	/// See [`DynTensor::from_dlpack`] for details on supported tensors.
+	///
+	/// # Safety
+	/// See [`DynTensor::from_dlpack`].
+	pub unsafe fn from_dlpack(tensor: NonNull<DLManagedTensor>) -> Result<Tensor<T>> {
+		let dtype = unsafe { tensor.as_ref().dl_tensor.dtype };
+		if element_type(dtype) != Some(T::into_tensor_element_type()) {
+			return Err(invalid(format!("Cannot import DLPack tensor of type {dtype:?} as a Tensor<{}>", T::into_tensor_element_type())));
+		}
+		let tensor = unsafe { DynTensor::from_dlpack(tensor)? };
+		debug_assert!(TensorValueType::<T>::can_downcast(tensor.dtype()));
+		Ok(unsafe { tensor.transmute_type() })
+	}
+}
+
+impl<Type: TensorValueTypeMarker + ?Sized> Value<Type> {
+	/// Exports this tensor as a DLPack tensor without copying its data, including the device it is allocated on.
+	///
+	/// The returned `DLManagedTensor` keeps this tensor's data alive until its deleter is called; the consumer is
+	/// responsible for calling it exactly once. The data is shared, so writes through either tensor are visible through
+	/// the other.
+	///
+	/// Returns an error for values which are not tensors, for string & 4-bit integer tensors, which have no DLPack
+	/// equivalent, and for tensors allocated on devices other than the CPU, CUDA, or ROCm.
+	pub fn to_dlpack(&self) -> Result<NonNull<DLManagedTensor>> {
+		let ValueType::Tensor { ty, shape, .. } = self.dtype() else {
+			return Err(invalid(format!("Cannot export value of type {} via DLPack", self.dtype())));
+		};
+		let dtype = data_type(*ty).ok_or_else(|| invalid(format!("Tensors of type {ty} cannot be exported via DLPack")))?;
+		let device = device(self.memory_info())?;
+		let data = self.data_ptr()?.cast_mut();
+
+		let mut shape: Vec<i64> = shape.to_vec();
+		let exported = Box::into_raw(Box::new(ExportedTensor {
+			managed: DLManagedTensor {
+				dl_tensor: DLTensor {
+					data,
+					device,
+					ndim: shape.len() as i32,
+					dtype,
+					// the `Vec`'s buffer stays put when the `Vec` itself is moved into the box
+					shape: shape.as_mut_ptr(),
+					strides: ptr::null_mut(),
+					byte_offset: 0
+				},
+				manager_ctx: ptr::null_mut(),
+				deleter: Some(delete_exported)
+			},
+			_shape: shape,
+			_value: Arc::clone(&self.inner)
+		}));
+		unsafe {
+			(*exported).managed.manager_ctx = exported.cast();
+			Ok(NonNull::new_unchecked(ptr::addr_of_mut!((*exported).managed)))
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{DLDataType, DLDataTypeCode, TensorElementType, data_type, element_type, is_contiguous};
+
+	#[test]
+	fn test_is_contiguous() {
+		assert!(is_contiguous(&[2, 3], &[3, 1]));
+		assert!(!is_contiguous(&[2, 3], &[1, 2]));
+		assert!(is_contiguous(&[2, 1, 3], &[3, 42, 1]));
+		assert!(is_contiguous(&[], &[]));
+	}
+
+	#[test]
+	fn test_data_type() {
+		assert_eq!(
+			element_type(DLDataType {
+				code: DLDataTypeCode::FLOAT,
+				bits: 32,
+				lanes: 1
+			}),
+			Some(TensorElementType::Float32)
+		);
+		assert_eq!(
+			element_type(DLDataType {
+				code: DLDataTypeCode::FLOAT,
+				bits: 32,
+				lanes: 4
+			}),
+			None
+		);
+		assert_eq!(
+			element_type(DLDataType {
+				code: DLDataTypeCode::OPAQUE_HANDLE,
+				bits: 64,
+				lanes: 1
+			}),
+			None
+		);
+		for ty in [
+			TensorElementType::Bool,
+			TensorElementType::Uint16,
+			TensorElementType::Bfloat16,
+			TensorElementType::Complex128,
+			TensorElementType::Float8E5M2
+		] {
+			assert_eq!(data_type(ty).and_then(element_type), Some(ty));
+		}
+		assert_eq!(data_type(TensorElementType::String), None);
+	}
+}
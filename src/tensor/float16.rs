@@ -0,0 +1,126 @@
+//! Conversions between `f32` and the 16-bit float types, [`TensorElementType::Float16`] (IEEE 754 half-precision) &
+//! [`TensorElementType::Bfloat16`], which work on their raw bits so they don't require the `half` crate.
+//!
+//! [`TensorElementType::Float16`]: super::TensorElementType::Float16
+//! [`TensorElementType::Bfloat16`]: super::TensorElementType::Bfloat16
+
+/// Converts the bits of an IEEE 754 half-precision float to an `f32`.
+pub(crate) fn f16_to_f32(bits: u16) -> f32 {
+	let sign = u32::from(bits & 0x8000) << 16;
+	let exponent = u32::from((bits >> 10) & 0x1F);
+	let mantissa = u32::from(bits & 0x3FF);
+	let magnitude = match (exponent, mantissa) {
+		(0, 0) => 0,
+		// subnormal; normalize it, since it is representable as a normal `f32`
+		(0, _) => {
+			let shift = mantissa.leading_zeros() - 21;
+			((127 - 15 + 1 - shift) << 23) | (((mantissa << shift) & 0x3FF) << 13)
+		}
+		(0x1F, _) => 0x7F80_0000 | (mantissa << 13),
+		_ => ((exponent + 127 - 15) << 23) | (mantissa << 13)
+	};
+	f32::from_bits(sign | magnitude)
+}
+
+/// Converts the bits of a bfloat16 to an `f32`.
+pub(crate) fn bf16_to_f32(bits: u16) -> f32 {
+	f32::from_bits(u32::from(bits) << 16)
+}
+
+/// Shifts `value` right by `shift` bits, rounding to the nearest integer, with ties to even.
+fn round_shift(value: u32, shift: u32) -> u32 {
+	let truncated = value >> shift;
+	let remainder = value & ((1 << shift) - 1);
+	let half = 1 << (shift - 1);
+	if remainder > half || (remainder == half && truncated & 1 == 1) {
+		truncated + 1
+	} else {
+		truncated
+	}
+}
+
+/// Converts an `f32` to the bits of an IEEE 754 half-precision float, rounding to the nearest value (ties to even).
+pub(crate) fn f32_to_f16(value: f32) -> u16 {
+	let bits = value.to_bits();
+	let sign = ((bits >> 16) & 0x8000) as u16;
+	let magnitude = bits & 0x7FFF_FFFF;
+	if magnitude > 0x7F80_0000 {
+		return sign | 0x7E00;
+	}
+
+	let exponent = (magnitude >> 23) as i32 - 127;
+	if exponent > 15 {
+		return sign | 0x7C00;
+	} else if exponent < -25 {
+		// less than half of the smallest subnormal
+		return sign;
+	}
+	let significand = (magnitude & 0x7F_FFFF) | 0x80_0000;
+	let encoded = if exponent < -14 {
+		// subnormal; the significand is scaled to units of the smallest subnormal
+		round_shift(significand, (-1 - exponent) as u32)
+	} else {
+		// rounding may carry into the exponent, or overflow to infinity, both of which are handled by the addition
+		(((exponent + 14) as u32) << 10) + round_shift(significand, 13)
+	};
+	sign | encoded as u16
+}
+
+/// Converts an `f32` to the bits of a bfloat16, rounding to the nearest value (ties to even).
+pub(crate) fn f32_to_bf16(value: f32) -> u16 {
+	let bits = value.to_bits();
+	if value.is_nan() {
+		// keep NaNs quiet, even if the truncated mantissa would be zero
+		return (bits >> 16) as u16 | 0x0040;
+	}
+	((bits + 0x7FFF + ((bits >> 16) & 1)) >> 16) as u16
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{bf16_to_f32, f16_to_f32, f32_to_bf16, f32_to_f16};
+
+	#[test]
+	fn test_f16_to_f32() {
+		assert_eq!(f16_to_f32(0x3C00), 1.0);
+		assert_eq!(f16_to_f32(0xC000), -2.0);
+		assert_eq!(f16_to_f32(0x7BFF), 65504.0);
+		assert_eq!(f16_to_f32(0x0001), 2.0_f32.powi(-24));
+		assert_eq!(f16_to_f32(0x0200), 2.0_f32.powi(-15));
+		assert_eq!(f16_to_f32(0x7C00), f32::INFINITY);
+		assert!(f16_to_f32(0x7E00).is_nan());
+	}
+
+	#[test]
+	fn test_bf16_to_f32() {
+		assert_eq!(bf16_to_f32(0x3F80), 1.0);
+		assert_eq!(bf16_to_f32(0xC000), -2.0);
+		assert_eq!(bf16_to_f32(0x7F80), f32::INFINITY);
+		assert!(bf16_to_f32(0x7FC0).is_nan());
+	}
+
+	#[test]
+	fn test_f32_to_f16() {
+		assert_eq!(f32_to_f16(1.0), 0x3C00);
+		assert_eq!(f32_to_f16(-2.0), 0xC000);
+		assert_eq!(f32_to_f16(65504.0), 0x7BFF);
+		assert_eq!(f32_to_f16(65520.0), 0x7C00);
+		assert_eq!(f32_to_f16(2.0_f32.powi(-24)), 0x0001);
+		assert_eq!(f32_to_f16(2.0_f32.powi(-25)), 0x0000);
+		assert_eq!(f32_to_f16(1.5 * 2.0_f32.powi(-25)), 0x0001);
+		assert_eq!(f32_to_f16(2.0_f32.powi(-15)), 0x0200);
+		// 1 + 2^-11 is halfway between 1 and the next f16, and rounds to even
+		assert_eq!(f32_to_f16(1.0 + 2.0_f32.powi(-11)), 0x3C00);
+		assert_eq!(f32_to_f16(f32::NEG_INFINITY), 0xFC00);
+		assert_eq!(f32_to_f16(f32::NAN) & 0x7E00, 0x7E00);
+	}
+
+	#[test]
+	fn test_f32_to_bf16() {
+		assert_eq!(f32_to_bf16(1.0), 0x3F80);
+		assert_eq!(f32_to_bf16(f32::from_bits(0x3F80_8000)), 0x3F80);
+		assert_eq!(f32_to_bf16(f32::from_bits(0x3F81_8000)), 0x3F82);
+		assert_eq!(f32_to_bf16(f32::MAX), 0x7F80);
+		assert_eq!(f32_to_bf16(f32::from_bits(0x7F80_0001)) & 0x7FC0, 0x7FC0);
+	}
+}
@@ -0,0 +1,323 @@
+//! 8-bit floating point element types.
+//!
+//! Conversions from `f32` follow the semantics of the ONNX `Cast` operator: values are rounded to the nearest
+//! representable value (ties to even), and by default, values which are out of range saturate to the largest finite
+//! value. See <https://onnx.ai/onnx/technical/float8.html> for details on each format.
+
+use core::fmt;
+
+use super::{IntoTensorElementType, PrimitiveTensorElementType, TensorElementType};
+
+/// Describes the layout & special values of an 8-bit float format.
+struct Format {
+	mantissa_bits: u32,
+	bias: i32,
+	/// The bit pattern of the largest finite value, without the sign bit.
+	max: u8,
+	/// Whether the format has infinities, in which case it follows IEEE 754 conventions for infinities & NaNs.
+	has_inf: bool,
+	/// Whether the format has no negative zero, in which case `0x80` is the only NaN value.
+	fnuz: bool
+}
+
+impl Format {
+	fn nan(&self, sign: u8) -> u8 {
+		if self.fnuz { 0x80 } else { sign | 0x7F }
+	}
+
+	fn decode(&self, bits: u8) -> f32 {
+		let sign = if bits & 0x80 != 0 { -1.0 } else { 1.0 };
+		let magnitude = bits & 0x7F;
+		if self.fnuz {
+			if bits == 0x80 {
+				return f32::NAN;
+			}
+		} else if self.has_inf {
+			if magnitude == 0x7C {
+				return sign * f32::INFINITY;
+			} else if magnitude > 0x7C {
+				return f32::NAN;
+			}
+		} else if magnitude == 0x7F {
+			return f32::NAN;
+		}
+
+		let exponent = i32::from(magnitude >> self.mantissa_bits);
+		let mantissa = u32::from(magnitude) & ((1 << self.mantissa_bits) - 1);
+		let value = if exponent == 0 {
+			mantissa as f32 * pow2(1 - self.bias - self.mantissa_bits as i32)
+		} else {
+			(mantissa | 1 << self.mantissa_bits) as f32 * pow2(exponent - self.bias - self.mantissa_bits as i32)
+		};
+		sign * value
+	}
+
+	fn encode(&self, value: f32, saturate: bool) -> u8 {
+		let bits = value.to_bits();
+		let sign = ((bits >> 24) & 0x80) as u8;
+		let magnitude = bits & 0x7FFF_FFFF;
+		if magnitude > 0x7F80_0000 {
+			return self.nan(sign);
+		}
+		if magnitude == 0x7F80_0000 {
+			return match (self.has_inf, saturate) {
+				(true, _) => sign | 0x7C,
+				(false, true) => sign | self.max,
+				(false, false) => self.nan(sign)
+			};
+		}
+
+		let f32_exponent = (magnitude >> 23) as i32;
+		let (exponent, significand) = if f32_exponent == 0 {
+			(-126, magnitude & 0x7F_FFFF)
+		} else {
+			(f32_exponent - 127, (magnitude & 0x7F_FFFF) | 0x80_0000)
+		};
+
+		let min_normal_exponent = 1 - self.bias;
+		let encoded = if exponent < min_normal_exponent {
+			// Subnormal in the target format; the significand is scaled to units of the smallest subnormal.
+			let shift = (23 - self.mantissa_bits as i32 + (min_normal_exponent - exponent)) as u32;
+			round_shift(significand, shift)
+		} else {
+			// The implicit leading bit of the rounded significand carries into the exponent, hence the `- 1`.
+			(((exponent + self.bias - 1) as u32) << self.mantissa_bits) + round_shift(significand, 23 - self.mantissa_bits)
+		};
+
+		if encoded > u32::from(self.max) {
+			return match (saturate, self.has_inf) {
+				(true, _) => sign | self.max,
+				(false, true) => sign | 0x7C,
+				(false, false) => self.nan(sign)
+			};
+		}
+		if encoded == 0 && self.fnuz {
+			return 0;
+		}
+		sign | encoded as u8
+	}
+}
+
+/// Shifts `value` right by `shift` bits, rounding to the nearest integer, with ties to even.
+fn round_shift(value: u32, shift: u32) -> u32 {
+	if shift == 0 {
+		return value;
+	} else if shift > 24 {
+		// `value` is at most 24 bits, so this is less than one half.
+		return 0;
+	}
+	let truncated = value >> shift;
+	let remainder = value & ((1 << shift) - 1);
+	let half = 1 << (shift - 1);
+	if remainder > half || (remainder == half && truncated & 1 == 1) {
+		truncated + 1
+	} else {
+		truncated
+	}
+}
+
+fn pow2(exponent: i32) -> f32 {
+	f32::from_bits(((exponent + 127) as u32) << 23)
+}
+
+macro_rules! float8_type {
+	($(#[$meta:meta])* $name:ident, $variant:ident, $format:expr) => {
+		$(#[$meta])*
+		///
+		/// Equality & hashing compare the underlying bits, so unlike `f32`, NaNs compare equal to themselves.
+		#[repr(transparent)]
+		#[derive(Default, Clone, Copy, PartialEq, Eq, Hash)]
+		pub struct $name(pub u8);
+
+		impl $name {
+			const FORMAT: Format = $format;
+
+			/// Creates a value from its raw bit representation.
+			#[inline]
+			#[must_use]
+			pub const fn from_bits(bits: u8) -> Self {
+				Self(bits)
+			}
+
+			/// Returns the raw bit representation of this value.
+			#[inline]
+			#[must_use]
+			pub const fn to_bits(self) -> u8 {
+				self.0
+			}
+
+			/// Converts an `f32` to this format, rounding to the nearest representable value. Values which are out of
+			/// range saturate to the largest finite value, as in the ONNX `Cast` operator with `saturate=1` (the default).
+			#[must_use]
+			pub fn from_f32(value: f32) -> Self {
+				Self(Self::FORMAT.encode(value, true))
+			}
+
+			/// Converts an `f32` to this format, rounding to the nearest representable value. Values which are out of
+			/// range become infinite if the format supports infinities, or NaN otherwise, as in the ONNX `Cast` operator
+			/// with `saturate=0`.
+			#[must_use]
+			pub fn from_f32_unsaturated(value: f32) -> Self {
+				Self(Self::FORMAT.encode(value, false))
+			}
+
+			/// Converts this value to an `f32`. This conversion is lossless.
+			#[must_use]
+			pub fn to_f32(self) -> f32 {
+				Self::FORMAT.decode(self.0)
+			}
+
+			/// Returns `true` if this value is NaN.
+			#[must_use]
+			pub fn is_nan(self) -> bool {
+				self.to_f32().is_nan()
+			}
+		}
+
+		impl From<$name> for f32 {
+			fn from(value: $name) -> f32 {
+				value.to_f32()
+			}
+		}
+
+		impl fmt::Debug for $name {
+			fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+				fmt::Debug::fmt(&self.to_f32(), f)
+			}
+		}
+
+		impl fmt::Display for $name {
+			fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+				fmt::Display::fmt(&self.to_f32(), f)
+			}
+		}
+
+		impl IntoTensorElementType for $name {
+			fn into_tensor_element_type() -> TensorElementType {
+				TensorElementType::$variant
+			}
+
+			private_impl!();
+		}
+
+		impl PrimitiveTensorElementType for $name {
+			private_impl!();
+		}
+	};
+}
+
+float8_type!(
+	/// An 8-bit float with 4 exponent bits and 3 mantissa bits, with no infinities. Corresponds to
+	/// [`TensorElementType::Float8E4M3FN`].
+	F8E4M3FN,
+	Float8E4M3FN,
+	Format {
+		mantissa_bits: 3,
+		bias: 7,
+		max: 0x7E,
+		has_inf: false,
+		fnuz: false
+	}
+);
+float8_type!(
+	/// An 8-bit float with 4 exponent bits and 3 mantissa bits, with no infinities and no negative zero. Corresponds
+	/// to [`TensorElementType::Float8E4M3FNUZ`].
+	F8E4M3FNUZ,
+	Float8E4M3FNUZ,
+	Format {
+		mantissa_bits: 3,
+		bias: 8,
+		max: 0x7F,
+		has_inf: false,
+		fnuz: true
+	}
+);
+float8_type!(
+	/// An 8-bit float with 5 exponent bits and 2 mantissa bits, following IEEE 754 conventions. Corresponds to
+	/// [`TensorElementType::Float8E5M2`].
+	F8E5M2,
+	Float8E5M2,
+	Format {
+		mantissa_bits: 2,
+		bias: 15,
+		max: 0x7B,
+		has_inf: true,
+		fnuz: false
+	}
+);
+float8_type!(
+	/// An 8-bit float with 5 exponent bits and 2 mantissa bits, with no infinities and no negative zero. Corresponds
+	/// to [`TensorElementType::Float8E5M2FNUZ`].
+	F8E5M2FNUZ,
+	Float8E5M2FNUZ,
+	Format {
+		mantissa_bits: 2,
+		bias: 16,
+		max: 0x7F,
+		has_inf: false,
+		fnuz: true
+	}
+);
+
+#[cfg(test)]
+mod tests {
+	use super::{F8E4M3FN, F8E4M3FNUZ, F8E5M2, F8E5M2FNUZ};
+
+	#[test]
+	fn test_roundtrip() {
+		for bits in 0..=u8::MAX {
+			let value = F8E4M3FN(bits);
+			if !value.is_nan() {
+				assert_eq!(F8E4M3FN::from_f32(value.to_f32()), value, "{bits:#04x}");
+			}
+			let value = F8E5M2(bits);
+			if !value.is_nan() {
+				assert_eq!(F8E5M2::from_f32(value.to_f32()), value, "{bits:#04x}");
+			}
+			let value = F8E4M3FNUZ(bits);
+			if !value.is_nan() {
+				assert_eq!(F8E4M3FNUZ::from_f32(value.to_f32()), value, "{bits:#04x}");
+			}
+			let value = F8E5M2FNUZ(bits);
+			if !value.is_nan() {
+				assert_eq!(F8E5M2FNUZ::from_f32(value.to_f32()), value, "{bits:#04x}");
+			}
+		}
+	}
+
+	#[test]
+	fn test_limits() {
+		assert_eq!(F8E4M3FN(0x7E).to_f32(), 448.0);
+		assert_eq!(F8E4M3FNUZ(0x7F).to_f32(), 240.0);
+		assert_eq!(F8E5M2(0x7B).to_f32(), 57344.0);
+		assert_eq!(F8E5M2FNUZ(0x7F).to_f32(), 57344.0);
+		assert_eq!(F8E4M3FN(0x01).to_f32(), 2.0_f32.powi(-9));
+		assert_eq!(F8E5M2(0x01).to_f32(), 2.0_f32.powi(-16));
+
+		assert_eq!(F8E4M3FN::from_f32(1000.0).to_f32(), 448.0);
+		assert_eq!(F8E4M3FN::from_f32(f32::NEG_INFINITY).to_f32(), -448.0);
+		assert!(F8E4M3FN::from_f32_unsaturated(1000.0).is_nan());
+		assert_eq!(F8E5M2::from_f32(f32::INFINITY).to_f32(), f32::INFINITY);
+		assert_eq!(F8E5M2::from_f32(1e6).to_f32(), 57344.0);
+		assert_eq!(F8E5M2::from_f32_unsaturated(1e6).to_f32(), f32::INFINITY);
+		assert!(F8E4M3FNUZ::from_f32(f32::NAN).is_nan());
+		assert_eq!(F8E4M3FNUZ::from_f32(-0.0).to_bits(), 0);
+		assert_eq!(F8E4M3FN::from_f32(-0.0).to_bits(), 0x80);
+	}
+
+	#[test]
+	fn test_rounding() {
+		// 1.0625 is halfway between 1.0 and 1.125; ties go to the even mantissa (1.0)
+		assert_eq!(F8E4M3FN::from_f32(1.0625).to_f32(), 1.0);
+		// 1.1875 is halfway between 1.125 and 1.25; ties go to the even mantissa (1.25)
+		assert_eq!(F8E4M3FN::from_f32(1.1875).to_f32(), 1.25);
+		assert_eq!(F8E4M3FN::from_f32(1.07).to_f32(), 1.125);
+		// values between the largest finite value and the next (unrepresentable) value round down
+		assert_eq!(F8E4M3FN::from_f32_unsaturated(460.0).to_f32(), 448.0);
+		// subnormals
+		assert_eq!(F8E4M3FN::from_f32(2.0_f32.powi(-10)).to_bits(), 0x00);
+		assert_eq!(F8E4M3FN::from_f32(3.0 * 2.0_f32.powi(-10)).to_bits(), 0x02);
+		assert_eq!(F8E4M3FN::from_f32(0.0137).to_bits(), 0x07);
+		assert_eq!(F8E4M3FN::from_f32(0.0155).to_bits(), 0x08);
+	}
+}
@@ -0,0 +1,390 @@
+//! Conversions between [`image`] images and [`Tensor`]s.
+//!
+//! ```
+//! # use image::{Rgb, RgbImage};
+//! # use ort::{tensor::{ImageLayout, ImageOptions}, value::Tensor};
+//! # fn main() -> ort::Result<()> {
+//! let image = RgbImage::from_pixel(4, 2, Rgb([255, 0, 51]));
+//!
+//! // Normalize to ImageNet mean/std, in NCHW layout.
+//! let options = ImageOptions::new().with_normalization([0.485, 0.456, 0.406], [0.229, 0.224, 0.225]);
+//! let tensor = Tensor::<f32>::from_image(&image, &options)?;
+//! assert_eq!(**tensor.shape(), [1, 3, 2, 4]);
+//!
+//! // Un-normalized 8-bit data in NHWC layout.
+//! let tensor = Tensor::<u8>::from_image(&image, &ImageOptions::new().with_layout(ImageLayout::NHWC))?;
+//! assert_eq!(**tensor.shape(), [1, 2, 4, 3]);
+//! assert_eq!(&tensor.extract_tensor().1[..3], [255, 0, 51]);
+//! # 	Ok(())
+//! # }
+//! ```
+
+use alloc::{format, vec, vec::Vec};
+use core::ops::Deref;
+
+use image::{DynamicImage, GrayAlphaImage, GrayImage, ImageBuffer, Pixel, Primitive, RgbImage, RgbaImage};
+use smallvec::{SmallVec, smallvec};
+
+use super::TensorElementType;
+use crate::{
+	error::{Error, ErrorCode, Result},
+	value::{Tensor, TensorValueTypeMarker, Value}
+};
+
+/// The order of dimensions of an image tensor.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[allow(clippy::upper_case_acronyms)]
+pub enum ImageLayout {
+	/// `[batch, channels, height, width]`; used by most models exported from PyTorch.
+	#[default]
+	NCHW,
+	/// `[batch, height, width, channels]`; used by most models exported from TensorFlow.
+	NHWC
+}
+
+/// The order of color channels of an image tensor.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[allow(clippy::upper_case_acronyms)]
+pub enum ChannelOrder {
+	/// Channels are in the same order as the image's pixels, i.e. red, green, blue (then alpha, if present).
+	#[default]
+	RGB,
+	/// Swaps the red & blue channels; used by models trained on images loaded with OpenCV. Has no effect on grayscale
+	/// images.
+	BGR
+}
+
+/// Options controlling how images are converted to & from tensors with [`Tensor::from_image`] and
+/// [`Tensor::try_extract_image`].
+///
+/// By default, images are converted to `NCHW` tensors in RGB order, and `f32` tensors hold values in the range
+/// `[0, 1]`.
+#[derive(Debug, Clone)]
+pub struct ImageOptions {
+	layout: ImageLayout,
+	channel_order: ChannelOrder,
+	rescale: bool,
+	mean: SmallVec<f32, 4>,
+	std: SmallVec<f32, 4>
+}
+
+impl Default for ImageOptions {
+	fn default() -> Self {
+		Self {
+			layout: ImageLayout::default(),
+			channel_order: ChannelOrder::default(),
+			rescale: true,
+			mean: smallvec![0.0],
+			std: smallvec![1.0]
+		}
+	}
+}
+
+impl ImageOptions {
+	/// Creates the default options: an `NCHW` layout in RGB order, with pixel values rescaled to `[0, 1]` and no
+	/// normalization.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Configures the order of the tensor's dimensions. Defaults to [`ImageLayout::NCHW`].
+	#[must_use]
+	pub fn with_layout(mut self, layout: ImageLayout) -> Self {
+		self.layout = layout;
+		self
+	}
+
+	/// Configures the order of the tensor's color channels. Defaults to [`ChannelOrder::RGB`].
+	#[must_use]
+	pub fn with_channel_order(mut self, channel_order: ChannelOrder) -> Self {
+		self.channel_order = channel_order;
+		self
+	}
+
+	/// Configures whether pixel values are rescaled to the range `[0, 1]` (by dividing by the maximum value of the
+	/// image's subpixel type, e.g. 255 for 8-bit images) before normalization. Defaults to `true`. Only applies to
+	/// `f32` tensors.
+	#[must_use]
+	pub fn with_rescale(mut self, rescale: bool) -> Self {
+		self.rescale = rescale;
+		self
+	}
+
+	/// Normalizes each channel of `f32` tensors as `(value - mean) / std`, applied after rescaling. `mean` and `std`
+	/// should either have one element per channel (in RGB order, regardless of [`ImageOptions::with_channel_order`]),
+	/// or a single element which applies to all channels.
+	#[must_use]
+	pub fn with_normalization(mut self, mean: impl AsRef<[f32]>, std: impl AsRef<[f32]>) -> Self {
+		self.mean = SmallVec::from_slice(mean.as_ref());
+		self.std = SmallVec::from_slice(std.as_ref());
+		self
+	}
+
+	fn shape(&self, channels: usize, height: usize, width: usize) -> [usize; 4] {
+		match self.layout {
+			ImageLayout::NCHW => [1, channels, height, width],
+			ImageLayout::NHWC => [1, height, width, channels]
+		}
+	}
+
+	/// Returns the index of the given channel & pixel in a tensor of this layout.
+	fn index(&self, channel: usize, y: usize, x: usize, [channels, height, width]: [usize; 3]) -> usize {
+		match self.layout {
+			ImageLayout::NCHW => (channel * height + y) * width + x,
+			ImageLayout::NHWC => (y * width + x) * channels + channel
+		}
+	}
+
+	/// Maps a channel index in the tensor to the corresponding channel index in the image, or vice versa.
+	fn swizzle(&self, channel: usize, channels: usize) -> usize {
+		match self.channel_order {
+			ChannelOrder::BGR if channels >= 3 && channel < 3 => 2 - channel,
+			_ => channel
+		}
+	}
+
+	/// Returns the per-channel `(mean, std)`, indexed by image channel.
+	fn normalization(&self, channels: usize) -> Result<(SmallVec<f32, 4>, SmallVec<f32, 4>)> {
+		let expand = |values: &SmallVec<f32, 4>, name: &str| match values.len() {
+			1 => Ok(smallvec![values[0]; channels]),
+			len if len == channels => Ok(values.clone()),
+			len => Err(Error::new_with_code(
+				ErrorCode::InvalidArgument,
+				format!("Image normalization {name} has {len} elements, but the image has {channels} channels")
+			))
+		};
+		Ok((expand(&self.mean, "mean")?, expand(&self.std, "std")?))
+	}
+}
+
+/// Copies the pixels of `image` into a tensor-ordered buffer, converting each subpixel with `convert(value, channel)`,
+/// where `channel` is the index of the channel in the image.
+fn image_to_vec<P, C, T>(image: &ImageBuffer<P, C>, options: &ImageOptions, convert: impl Fn(P::Subpixel, usize) -> T) -> ([usize; 4], Vec<T>)
+where
+	P: Pixel,
+	C: Deref<Target = [P::Subpixel]>,
+	T: Copy + Default
+{
+	let channels = P::CHANNEL_COUNT as usize;
+	let (width, height) = (image.width() as usize, image.height() as usize);
+	let raw: &[P::Subpixel] = image;
+
+	let mut data = vec![T::default(); channels * height * width];
+	for y in 0..height {
+		for x in 0..width {
+			let pixel = &raw[(y * width + x) * channels..][..channels];
+			for channel in 0..channels {
+				let image_channel = options.swizzle(channel, channels);
+				data[options.index(channel, y, x, [channels, height, width])] = convert(pixel[image_channel], image_channel);
+			}
+		}
+	}
+	(options.shape(channels, height, width), data)
+}
+
+impl Tensor<f32> {
+	/// Creates an `f32` tensor from an image, with the layout, channel order & normalization specified by `options`.
+	///
+	/// The tensor always has a batch dimension of 1, and one channel per channel of the image's pixel type; convert
+	/// images to the pixel type the model expects (e.g. with [`DynamicImage::to_rgb8`]) beforehand, or use
+	/// [`Tensor::from_dynamic_image`].
+	///
+	/// ```
+	/// # use image::{Luma, GrayImage};
+	/// # use ort::{tensor::ImageOptions, value::Tensor};
+	/// # fn main() -> ort::Result<()> {
+	/// let image = GrayImage::from_pixel(2, 2, Luma([255]));
+	/// let tensor = Tensor::<f32>::from_image(&image, &ImageOptions::new().with_normalization([0.5], [0.5]))?;
+	/// assert_eq!(**tensor.shape(), [1, 1, 2, 2]);
+	/// assert_eq!(tensor.extract_tensor().1, [1.0; 4]);
+	/// # 	Ok(())
+	/// # }
+	/// ```
+	pub fn from_image<P, C>(image: &ImageBuffer<P, C>, options: &ImageOptions) -> Result<Tensor<f32>>
+	where
+		P: Pixel,
+		C: Deref<Target = [P::Subpixel]>
+	{
+		let (mean, std) = options.normalization(P::CHANNEL_COUNT as usize)?;
+		let scale = if options.rescale { subpixel_to_f32(P::Subpixel::DEFAULT_MAX_VALUE) } else { 1.0 };
+		let (shape, data) = image_to_vec(image, options, |value, channel| (subpixel_to_f32(value) / scale - mean[channel]) / std[channel]);
+		Tensor::from_array((shape, data))
+	}
+
+	/// Creates an `f32` tensor from an RGB image. If `image` is not an RGB image, it is first converted to one, so the
+	/// resulting tensor always has 3 channels. See [`Tensor::from_image`].
+	pub fn from_dynamic_image(image: &DynamicImage, options: &ImageOptions) -> Result<Tensor<f32>> {
+		match image {
+			DynamicImage::ImageRgb8(image) => Self::from_image(image, options),
+			DynamicImage::ImageRgb16(image) => Self::from_image(image, options),
+			DynamicImage::ImageRgb32F(image) => Self::from_image(image, options),
+			DynamicImage::ImageLuma16(_) | DynamicImage::ImageLumaA16(_) | DynamicImage::ImageRgba16(_) => Self::from_image(&image.to_rgb16(), options),
+			DynamicImage::ImageRgba32F(_) => Self::from_image(&image.to_rgb32f(), options),
+			_ => Self::from_image(&image.to_rgb8(), options)
+		}
+	}
+}
+
+impl Tensor<u8> {
+	/// Creates a `u8` tensor from an 8-bit image, with the layout & channel order specified by `options`. Pixel values
+	/// are copied as-is; rescaling & normalization options are ignored.
+	///
+	/// The tensor always has a batch dimension of 1, and one channel per channel of the image's pixel type.
+	pub fn from_image<P, C>(image: &ImageBuffer<P, C>, options: &ImageOptions) -> Result<Tensor<u8>>
+	where
+		P: Pixel<Subpixel = u8>,
+		C: Deref<Target = [u8]>
+	{
+		let (shape, data) = image_to_vec(image, options, |value, _| value);
+		Tensor::from_array((shape, data))
+	}
+
+	/// Creates a `u8` tensor from an RGB image. If `image` is not an 8-bit RGB image, it is first converted to one, so
+	/// the resulting tensor always has 3 channels. See [`Tensor::from_image`].
+	pub fn from_dynamic_image(image: &DynamicImage, options: &ImageOptions) -> Result<Tensor<u8>> {
+		match image {
+			DynamicImage::ImageRgb8(image) => Self::from_image(image, options),
+			_ => Self::from_image(&image.to_rgb8(), options)
+		}
+	}
+}
+
+fn subpixel_to_f32<S: Primitive>(value: S) -> f32 {
+	value.to_f32().unwrap_or_default()
+}
+
+impl<Type: TensorValueTypeMarker + ?Sized> Value<Type> {
+	/// Converts an `f32` or `u8` tensor to an 8-bit image, reversing the conversion performed by
+	/// [`Tensor::from_image`] with the same `options`.
+	///
+	/// The tensor's shape must match the configured [`ImageLayout`], though the batch dimension (which must be 1) may
+	/// be omitted. Tensors of shape `[height, width]` are treated as grayscale images. The resulting image is a
+	/// grayscale, grayscale + alpha, RGB, or RGBA image depending on whether the tensor has 1, 2, 3, or 4 channels.
+	///
+	/// For `f32` tensors, normalization is reversed, values are multiplied by 255 (if rescaling is enabled), and the
+	/// result is rounded & clamped to `[0, 255]`.
+	///
+	/// ```
+	/// # use image::{Rgb, RgbImage};
+	/// # use ort::{tensor::ImageOptions, value::Tensor};
+	/// # fn main() -> ort::Result<()> {
+	/// let image = RgbImage::from_pixel(4, 2, Rgb([255, 0, 51]));
+	/// let options = ImageOptions::new().with_normalization([0.5], [0.5]);
+	/// let tensor = Tensor::<f32>::from_image(&image, &options)?;
+	///
+	/// let roundtrip = tensor.try_extract_image(&options)?;
+	/// assert_eq!(roundtrip.as_rgb8(), Some(&image));
+	/// # 	Ok(())
+	/// # }
+	/// ```
+	///
+	/// # Errors
+	/// Returns an error if the value is not a tensor of `f32`s or `u8`s, or if its shape does not describe a single
+	/// image with 1 to 4 channels.
+	pub fn try_extract_image(&self, options: &ImageOptions) -> Result<DynamicImage> {
+		let ty = self.dtype().tensor_type();
+		match ty {
+			Some(TensorElementType::Float32) => {
+				let (shape, data) = self.try_extract_tensor::<f32>()?;
+				let dims = image_dimensions(shape, options)?;
+				let (mean, std) = options.normalization(dims[0])?;
+				let scale = if options.rescale { 255.0 } else { 1.0 };
+				tensor_to_image(data, dims, options, |value, channel| {
+					let value = (value * std[channel] + mean[channel]) * scale + 0.5;
+					// `as` saturates & maps NaN to 0
+					value as u8
+				})
+			}
+			Some(TensorElementType::Uint8) => {
+				let (shape, data) = self.try_extract_tensor::<u8>()?;
+				tensor_to_image(data, image_dimensions(shape, options)?, options, |value, _| value)
+			}
+			_ => Err(Error::new_with_code(
+				ErrorCode::InvalidArgument,
+				format!("Cannot convert value of type {} to an image; expected a tensor of f32 or u8", self.dtype())
+			))
+		}
+	}
+}
+
+/// Returns the `[channels, height, width]` of the image described by a tensor of the given shape.
+fn image_dimensions(shape: &[i64], options: &ImageOptions) -> Result<[usize; 3]> {
+	let unbatched = match shape {
+		[1, rest @ ..] if shape.len() == 4 => rest,
+		shape => shape
+	};
+	let dims = match (unbatched, options.layout) {
+		(&[height, width], _) => Some([1, height, width]),
+		(&[channels, height, width], ImageLayout::NCHW) | (&[height, width, channels], ImageLayout::NHWC) => Some([channels, height, width]),
+		_ => None
+	};
+	match dims {
+		Some([channels, height, width]) if (1..=4).contains(&channels) && height >= 0 && width >= 0 => Ok([channels as usize, height as usize, width as usize]),
+		_ => Err(Error::new_with_code(
+			ErrorCode::InvalidArgument,
+			format!("Cannot convert tensor of shape {shape:?} to an image with layout {:?}", options.layout)
+		))
+	}
+}
+
+fn tensor_to_image<T: Copy>(data: &[T], dims: [usize; 3], options: &ImageOptions, convert: impl Fn(T, usize) -> u8) -> Result<DynamicImage> {
+	let [channels, height, width] = dims;
+	let mut raw = Vec::with_capacity(channels * height * width);
+	for y in 0..height {
+		for x in 0..width {
+			for image_channel in 0..channels {
+				let channel = options.swizzle(image_channel, channels);
+				raw.push(convert(data[options.index(channel, y, x, dims)], image_channel));
+			}
+		}
+	}
+
+	let (width, height) = (width as u32, height as u32);
+	Ok(match channels {
+		1 => GrayImage::from_raw(width, height, raw).map(DynamicImage::ImageLuma8),
+		2 => GrayAlphaImage::from_raw(width, height, raw).map(DynamicImage::ImageLumaA8),
+		3 => RgbImage::from_raw(width, height, raw).map(DynamicImage::ImageRgb8),
+		_ => RgbaImage::from_raw(width, height, raw).map(DynamicImage::ImageRgba8)
+	}
+	.expect("buffer should be large enough"))
+}
+
+#[cfg(test)]
+mod tests {
+	use image::{Rgb, RgbImage};
+
+	use super::{ChannelOrder, ImageLayout, ImageOptions};
+	use crate::value::Tensor;
+
+	#[test]
+	fn test_layouts() -> crate::Result<()> {
+		let image = RgbImage::from_fn(3, 2, |x, y| Rgb([x as u8, y as u8, 10]));
+
+		let nchw = Tensor::<u8>::from_image(&image, &ImageOptions::new())?;
+		assert_eq!(**nchw.shape(), [1, 3, 2, 3]);
+		assert_eq!(nchw.extract_tensor().1, [0, 1, 2, 0, 1, 2, 0, 0, 0, 1, 1, 1, 10, 10, 10, 10, 10, 10]);
+
+		let options = ImageOptions::new().with_layout(ImageLayout::NHWC).with_channel_order(ChannelOrder::BGR);
+		let nhwc = Tensor::<u8>::from_image(&image, &options)?;
+		assert_eq!(**nhwc.shape(), [1, 2, 3, 3]);
+		assert_eq!(&nhwc.extract_tensor().1[..6], [10, 0, 0, 10, 0, 1]);
+		assert_eq!(nhwc.try_extract_image(&options)?.as_rgb8(), Some(&image));
+		Ok(())
+	}
+
+	#[test]
+	fn test_normalization() -> crate::Result<()> {
+		let image = RgbImage::from_pixel(1, 1, Rgb([0, 51, 255]));
+
+		let tensor = Tensor::<f32>::from_image(
+			&image,
+			&ImageOptions::new()
+				.with_rescale(false)
+				.with_normalization([0.0, 1.0, 5.0], [1.0, 2.0, 5.0])
+		)?;
+		assert_eq!(tensor.extract_tensor().1, [0.0, 25.0, 50.0]);
+
+		assert!(Tensor::<f32>::from_image(&image, &ImageOptions::new().with_normalization([0.0, 0.0], [1.0])).is_err());
+		Ok(())
+	}
+}
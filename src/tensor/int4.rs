@@ -0,0 +1,210 @@
+//! Packed 4-bit integer element types.
+//!
+//! ONNX stores 4-bit integers packed two to a byte, with the first element in the low nibble and the second element
+//! in the high nibble. A tensor of `N` 4-bit elements is thus backed by `N.div_ceil(2)` [`Int4x2`]s/[`Uint4x2`]s; if
+//! `N` is odd, the high nibble of the last byte is padding.
+//!
+//! Because the number of Rust elements differs from the number of tensor elements, these tensors are created with
+//! [`Tensor::from_packed`] rather than [`Tensor::from_array`], and can only be extracted as flat slices (e.g.
+//! [`Tensor::try_extract_tensor`]), not `ndarray` arrays.
+//!
+//! [`Tensor::from_packed`]: crate::value::Tensor::from_packed
+//! [`Tensor::from_array`]: crate::value::Tensor::from_array
+//! [`Tensor::try_extract_tensor`]: crate::value::Tensor::try_extract_tensor
+
+use alloc::{boxed::Box, format, vec::Vec};
+use core::fmt;
+
+use super::{IntoTensorElementType, PrimitiveTensorElementType, TensorElementType};
+use crate::{
+	error::{Error, ErrorCode, Result},
+	memory::MemoryInfo,
+	value::{Tensor, ToShape, tensor_from_array}
+};
+
+/// Rounds `value` (which must be within the range of an `i32`) to the nearest integer, with ties to even, as in the
+/// ONNX `Cast` operator.
+fn round_ties_even(value: f32) -> i32 {
+	let truncated = value as i32;
+	let floor = if truncated as f32 > value { truncated - 1 } else { truncated };
+	let fraction = value - floor as f32;
+	if fraction > 0.5 || (fraction == 0.5 && floor % 2 != 0) { floor + 1 } else { floor }
+}
+
+macro_rules! int4_type {
+	($(#[$meta:meta])* $name:ident, $variant:ident, $int:ty, $min:expr, $max:expr) => {
+		$(#[$meta])*
+		#[repr(transparent)]
+		#[derive(Default, Clone, Copy, PartialEq, Eq, Hash)]
+		pub struct $name(pub u8);
+
+		impl $name {
+			/// The smallest value a single element can hold.
+			pub const MIN: $int = $min;
+			/// The largest value a single element can hold.
+			pub const MAX: $int = $max;
+
+			/// Packs two elements into one byte. Values outside of [`Self::MIN`]..=[`Self::MAX`] saturate.
+			#[must_use]
+			pub fn new(first: $int, second: $int) -> Self {
+				let first = first.clamp(Self::MIN, Self::MAX) as u8 & 0x0F;
+				let second = second.clamp(Self::MIN, Self::MAX) as u8 & 0x0F;
+				Self(first | (second << 4))
+			}
+
+			/// Returns the first element, stored in the low nibble.
+			#[must_use]
+			pub fn first(self) -> $int {
+				Self::unpack_nibble(self.0 & 0x0F)
+			}
+
+			/// Returns the second element, stored in the high nibble.
+			#[must_use]
+			pub fn second(self) -> $int {
+				Self::unpack_nibble(self.0 >> 4)
+			}
+
+			/// Converts two `f32`s to 4-bit integers, rounding to the nearest integer (ties to even) and saturating
+			/// out-of-range values, as in the ONNX `Cast` operator. NaN converts to 0.
+			#[must_use]
+			pub fn from_f32(first: f32, second: f32) -> Self {
+				let convert = |value: f32| -> $int {
+					if value.is_nan() {
+						0
+					} else {
+						round_ties_even(value.clamp(Self::MIN as f32, Self::MAX as f32)) as $int
+					}
+				};
+				Self::new(convert(first), convert(second))
+			}
+
+			/// Converts both elements to `f32`s.
+			#[must_use]
+			pub fn to_f32(self) -> [f32; 2] {
+				[f32::from(self.first()), f32::from(self.second())]
+			}
+
+			/// Packs a slice of 4-bit values (which saturate if out of range) into `values.len().div_ceil(2)` bytes.
+			#[must_use]
+			pub fn pack(values: &[$int]) -> Vec<Self> {
+				values.chunks(2).map(|pair| Self::new(pair[0], pair.get(1).copied().unwrap_or(0))).collect()
+			}
+
+			/// Unpacks the first `len` elements of `packed`.
+			///
+			/// # Panics
+			/// Panics if `packed` holds fewer than `len` elements.
+			#[must_use]
+			pub fn unpack(packed: &[Self], len: usize) -> Vec<$int> {
+				assert!(len <= packed.len() * 2, "cannot unpack {len} elements from {} bytes", packed.len());
+				(0..len).map(|i| if i % 2 == 0 { packed[i / 2].first() } else { packed[i / 2].second() }).collect()
+			}
+		}
+
+		impl fmt::Debug for $name {
+			fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+				f.debug_tuple(stringify!($name)).field(&self.first()).field(&self.second()).finish()
+			}
+		}
+
+		impl IntoTensorElementType for $name {
+			fn into_tensor_element_type() -> TensorElementType {
+				TensorElementType::$variant
+			}
+
+			private_impl!();
+		}
+
+		impl PrimitiveTensorElementType for $name {
+			private_impl!();
+		}
+
+		impl Tensor<$name> {
+			/// Creates a tensor of 4-bit integers from packed data. `data` must contain exactly
+			/// `shape.num_elements().div_ceil(2)` bytes.
+			///
+			/// ```
+			#[doc = concat!("# use ort::{tensor::", stringify!($name), ", value::Tensor};")]
+			/// # fn main() -> ort::Result<()> {
+			#[doc = concat!("let data = ", stringify!($name), "::pack(&[1, 2, 3]);")]
+			#[doc = concat!("let tensor = Tensor::<", stringify!($name), ">::from_packed([3_usize], data)?;")]
+			///
+			/// let (shape, data) = tensor.extract_tensor();
+			/// assert_eq!(**shape, [3]);
+			#[doc = concat!("assert_eq!(", stringify!($name), "::unpack(data, 3), [1, 2, 3]);")]
+			/// # 	Ok(())
+			/// # }
+			/// ```
+			pub fn from_packed(shape: impl ToShape, data: Vec<$name>) -> Result<Tensor<$name>> {
+				let shape = shape.to_shape(None)?;
+				let expected_len = shape.num_elements().div_ceil(2);
+				if data.len() != expected_len {
+					return Err(Error::new_with_code(
+						ErrorCode::InvalidArgument,
+						format!("Cannot create a tensor of shape {shape} from {} packed bytes; expected {expected_len}", data.len())
+					));
+				}
+
+				let mut data = Box::new(data);
+				let ptr = data.as_mut_ptr().cast();
+				tensor_from_array(MemoryInfo::default(), shape, ptr, 1, TensorElementType::$variant, Some(data))
+					.map(|tensor| unsafe { tensor.transmute_type() })
+			}
+		}
+	};
+}
+
+impl Int4x2 {
+	fn unpack_nibble(nibble: u8) -> i8 {
+		// sign-extend from 4 bits
+		((nibble << 4) as i8) >> 4
+	}
+}
+
+impl Uint4x2 {
+	fn unpack_nibble(nibble: u8) -> u8 {
+		nibble
+	}
+}
+
+int4_type!(
+	/// Two packed signed 4-bit integers. Corresponds to [`TensorElementType::Int4`].
+	Int4x2,
+	Int4,
+	i8,
+	-8,
+	7
+);
+int4_type!(
+	/// Two packed unsigned 4-bit integers. Corresponds to [`TensorElementType::Uint4`].
+	Uint4x2,
+	Uint4,
+	u8,
+	0,
+	15
+);
+
+#[cfg(test)]
+mod tests {
+	use super::{Int4x2, Uint4x2};
+
+	#[test]
+	fn test_pack() {
+		let packed = Int4x2::pack(&[-8, 7, -1, 20, 3]);
+		assert_eq!(packed.len(), 3);
+		assert_eq!(packed[0].0, 0x78);
+		assert_eq!(Int4x2::unpack(&packed, 5), [-8, 7, -1, 7, 3]);
+
+		let packed = Uint4x2::pack(&[1, 15, 16]);
+		assert_eq!(packed[0].0, 0xF1);
+		assert_eq!(Uint4x2::unpack(&packed, 3), [1, 15, 15]);
+	}
+
+	#[test]
+	fn test_f32() {
+		assert_eq!(Int4x2::from_f32(2.5, -2.5).to_f32(), [2.0, -2.0]);
+		assert_eq!(Int4x2::from_f32(3.5, 100.0).to_f32(), [4.0, 7.0]);
+		assert_eq!(Int4x2::from_f32(f32::NEG_INFINITY, f32::NAN).to_f32(), [-8.0, 0.0]);
+		assert_eq!(Uint4x2::from_f32(-1.0, 14.6).to_f32(), [0.0, 15.0]);
+	}
+}
@@ -1,7 +1,17 @@
 //! Traits and types related to [`Tensor`](crate::value::Tensor)s.
 
+#[cfg(feature = "arrow")]
+mod arrow;
+pub mod dlpack;
+mod float16;
+mod float8;
+#[cfg(feature = "image")]
+mod image;
+mod int4;
 #[cfg(feature = "ndarray")]
 mod ndarray;
+#[cfg(feature = "npy")]
+mod npy;
 mod types;
 
 use alloc::{string::String, vec::Vec};
@@ -12,9 +22,22 @@ use core::{
 
 use smallvec::{SmallVec, smallvec};
 
+#[cfg(feature = "arrow")]
+pub(crate) use self::arrow::{array_to_value, value_to_array};
+pub(crate) use self::float16::{bf16_to_f32, f16_to_f32, f32_to_bf16, f32_to_f16};
+#[cfg(feature = "image")]
+#[cfg_attr(docsrs, doc(cfg(feature = "image")))]
+pub use self::image::{ChannelOrder, ImageLayout, ImageOptions};
 #[cfg(feature = "ndarray")]
 pub use self::ndarray::ArrayExtensions;
-pub use self::types::{IntoTensorElementType, PrimitiveTensorElementType, TensorElementType, Utf8Data};
+#[cfg(feature = "npy")]
+#[cfg_attr(docsrs, doc(cfg(feature = "npy")))]
+pub use self::npy::{read_npz, write_npz};
+pub use self::{
+	float8::{F8E4M3FN, F8E4M3FNUZ, F8E5M2, F8E5M2FNUZ},
+	int4::{Int4x2, Uint4x2},
+	types::{IntoTensorElementType, PrimitiveTensorElementType, TensorElementType, Utf8Data}
+};
 
 #[derive(Default, Clone, PartialEq, Eq)]
 pub struct Shape {
@@ -0,0 +1,531 @@
+//! Reading & writing tensors in NumPy's [`.npy`](https://numpy.org/doc/stable/reference/generated/numpy.lib.format.html)
+//! & `.npz` formats.
+
+use alloc::{
+	format,
+	string::{String, ToString},
+	vec,
+	vec::Vec
+};
+use core::{ops::Deref, ptr};
+use std::io::{Read, Seek, Write};
+
+use super::{PrimitiveTensorElementType, Shape, TensorElementType};
+use crate::{
+	error::{Error, ErrorCode, Result},
+	memory::Allocator,
+	value::{DowncastableTarget, DynTensor, DynTensorValueType, Tensor, TensorValueType, TensorValueTypeMarker, Value, ValueType, ValueTypeMarker}
+};
+
+const MAGIC: &[u8] = b"\x93NUMPY";
+
+fn io_error(e: std::io::Error) -> Error {
+	Error::new(format!("Failed to read/write NumPy array: {e}"))
+}
+
+fn invalid(message: impl Into<String>) -> Error {
+	Error::new_with_code(ErrorCode::InvalidArgument, message.into())
+}
+
+/// The data type of a NumPy array, as described by the `descr` field of its header.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Descr {
+	Primitive {
+		ty: TensorElementType,
+		big_endian: bool
+	},
+	/// A fixed-width UTF-32 string of the given number of characters.
+	Unicode {
+		len: usize,
+		big_endian: bool
+	}
+}
+
+impl Descr {
+	fn parse(descr: &str) -> Result<Self> {
+		let unsupported = || Error::new_with_code(ErrorCode::NotImplemented, format!("Unsupported NumPy dtype `{descr}`"));
+		let (big_endian, kind) = match descr.as_bytes().first() {
+			Some(b'>') => (true, &descr[1..]),
+			Some(b'<' | b'|' | b'=') => (false, &descr[1..]),
+			_ => (false, descr)
+		};
+		if let Some(len) = kind.strip_prefix('U') {
+			return Ok(Descr::Unicode {
+				// each character takes 4 bytes; see `element_size`
+				len: len
+					.parse()
+					.ok()
+					.filter(|len: &usize| len.checked_mul(4).is_some())
+					.ok_or_else(unsupported)?,
+				big_endian
+			});
+		}
+		let ty = match kind {
+			"b1" | "?" => TensorElementType::Bool,
+			"i1" => TensorElementType::Int8,
+			"i2" => TensorElementType::Int16,
+			"i4" => TensorElementType::Int32,
+			"i8" => TensorElementType::Int64,
+			"u1" => TensorElementType::Uint8,
+			"u2" => TensorElementType::Uint16,
+			"u4" => TensorElementType::Uint32,
+			"u8" => TensorElementType::Uint64,
+			"f2" => TensorElementType::Float16,
+			"f4" => TensorElementType::Float32,
+			"f8" => TensorElementType::Float64,
+			"c8" => TensorElementType::Complex64,
+			"c16" => TensorElementType::Complex128,
+			_ => return Err(unsupported())
+		};
+		Ok(Descr::Primitive { ty, big_endian })
+	}
+
+	fn of(ty: TensorElementType) -> Result<&'static str> {
+		Ok(match ty {
+			TensorElementType::Bool => "|b1",
+			TensorElementType::Int8 => "|i1",
+			TensorElementType::Int16 => "<i2",
+			TensorElementType::Int32 => "<i4",
+			TensorElementType::Int64 => "<i8",
+			TensorElementType::Uint8 => "|u1",
+			TensorElementType::Uint16 => "<u2",
+			TensorElementType::Uint32 => "<u4",
+			TensorElementType::Uint64 => "<u8",
+			TensorElementType::Float16 => "<f2",
+			TensorElementType::Float32 => "<f4",
+			TensorElementType::Float64 => "<f8",
+			TensorElementType::Complex64 => "<c8",
+			TensorElementType::Complex128 => "<c16",
+			ty => return Err(Error::new_with_code(ErrorCode::NotImplemented, format!("Tensors of type {ty} cannot be represented in NumPy arrays")))
+		})
+	}
+
+	/// The size of a single element, in bytes.
+	fn element_size(&self) -> usize {
+		match self {
+			Descr::Primitive { ty, .. } => ty.byte_size(1),
+			Descr::Unicode { len, .. } => len * 4
+		}
+	}
+}
+
+/// The parsed header of a `.npy` file.
+struct Header {
+	descr: Descr,
+	fortran_order: bool,
+	shape: Vec<i64>
+}
+
+impl Header {
+	fn read(reader: &mut impl Read) -> Result<Self> {
+		let mut preamble = [0; 8];
+		reader.read_exact(&mut preamble).map_err(io_error)?;
+		if &preamble[..6] != MAGIC {
+			return Err(invalid("Not a NumPy array; missing magic string"));
+		}
+		let header_len = match preamble[6] {
+			1 => {
+				let mut len = [0; 2];
+				reader.read_exact(&mut len).map_err(io_error)?;
+				u16::from_le_bytes(len) as usize
+			}
+			2 | 3 => {
+				let mut len = [0; 4];
+				reader.read_exact(&mut len).map_err(io_error)?;
+				u32::from_le_bytes(len) as usize
+			}
+			version => return Err(Error::new_with_code(ErrorCode::NotImplemented, format!("Unsupported NumPy format version {version}")))
+		};
+		let header = read_bytes(reader, header_len, "header")?;
+		let header = String::from_utf8_lossy(&header);
+
+		let descr = dict_value(&header, "descr")?;
+		let descr = descr
+			.strip_prefix(['\'', '"'])
+			.and_then(|d| d.split(['\'', '"']).next())
+			.ok_or_else(|| invalid(format!("Invalid `descr` in NumPy header: {header}")))?;
+		let fortran_order = match dict_value(&header, "fortran_order")? {
+			v if v.starts_with("True") => true,
+			v if v.starts_with("False") => false,
+			_ => return Err(invalid(format!("Invalid `fortran_order` in NumPy header: {header}")))
+		};
+		let shape = dict_value(&header, "shape")?
+			.strip_prefix('(')
+			.and_then(|s| s.split(')').next())
+			.ok_or_else(|| invalid(format!("Invalid `shape` in NumPy header: {header}")))?
+			.split(',')
+			.map(str::trim)
+			.filter(|d| !d.is_empty())
+			.map(|d| d.trim_end_matches('L').parse::<i64>())
+			.collect::<Result<Vec<_>, _>>()
+			.map_err(|_| invalid(format!("Invalid `shape` in NumPy header: {header}")))?;
+
+		Ok(Self {
+			descr: Descr::parse(descr)?,
+			fortran_order,
+			shape
+		})
+	}
+
+	fn write(&self, writer: &mut impl Write) -> Result<()> {
+		let descr = match self.descr {
+			Descr::Primitive { ty, .. } => Descr::of(ty)?.to_string(),
+			Descr::Unicode { len, .. } => format!("<U{len}")
+		};
+		let shape = match self.shape.as_slice() {
+			[dim] => format!("({dim},)"),
+			shape => format!("({})", shape.iter().map(i64::to_string).collect::<Vec<_>>().join(", "))
+		};
+		let mut header = format!("{{'descr': '{descr}', 'fortran_order': False, 'shape': {shape}, }}");
+
+		// the header is padded with spaces & terminated with a newline so that the data is 64-byte aligned
+		let (version, preamble_len) = if header.len() + 11 <= u16::MAX as usize { (1, 10) } else { (2, 12) };
+		let padding = (64 - (preamble_len + header.len() + 1) % 64) % 64;
+		header.extend(core::iter::repeat(' ').take(padding));
+		header.push('\n');
+
+		writer.write_all(MAGIC).map_err(io_error)?;
+		writer.write_all(&[version, 0]).map_err(io_error)?;
+		if version == 1 {
+			writer.write_all(&(header.len() as u16).to_le_bytes()).map_err(io_error)?;
+		} else {
+			writer.write_all(&(header.len() as u32).to_le_bytes()).map_err(io_error)?;
+		}
+		writer.write_all(header.as_bytes()).map_err(io_error)
+	}
+}
+
+/// Reads `len` bytes of the file's `what`. Unlike `read_exact`, this only allocates memory as data is actually read, so
+/// a corrupt length in the file can't cause a huge allocation.
+fn read_bytes(reader: &mut impl Read, len: usize, what: &str) -> Result<Vec<u8>> {
+	let mut data = Vec::new();
+	reader.take(len as u64).read_to_end(&mut data).map_err(io_error)?;
+	if data.len() < len {
+		return Err(invalid(format!("NumPy {what} should be {len} bytes, but only {} bytes remain in the file", data.len())));
+	}
+	Ok(data)
+}
+
+/// Returns the number of elements in an array of the given shape, or an error if it overflows.
+fn num_elements(shape: &[i64]) -> Result<usize> {
+	shape
+		.iter()
+		.try_fold(1_usize, |n, &d| n.checked_mul(d as usize))
+		.ok_or_else(|| invalid(format!("NumPy array of shape {shape:?} is too large")))
+}
+
+/// Returns the remainder of the header after the `key` entry's colon.
+fn dict_value<'h>(header: &'h str, key: &str) -> Result<&'h str> {
+	['\'', '"']
+		.iter()
+		.find_map(|quote| header.find(&format!("{quote}{key}{quote}")).map(|i| &header[i + key.len() + 2..]))
+		.and_then(|rest| rest.trim_start().strip_prefix(':'))
+		.map(str::trim_start)
+		.ok_or_else(|| invalid(format!("NumPy header is missing `{key}`: {header}")))
+}
+
+/// Converts the data of an array stored in Fortran (column-major) order to C (row-major) order.
+fn fortran_to_c(data: &[u8], shape: &[i64], element_size: usize) -> Result<Vec<u8>> {
+	let num_elements = num_elements(shape)?;
+	if num_elements.checked_mul(element_size) != Some(data.len()) {
+		return Err(invalid(format!("NumPy array of shape {shape:?} does not match its data size of {} bytes", data.len())));
+	}
+	if num_elements == 0 {
+		return Ok(Vec::new());
+	}
+
+	let shape: Vec<usize> = shape.iter().map(|&d| d as usize).collect();
+	// strides of each dimension (in elements) in Fortran order; these can't overflow since they're bounded by
+	// `num_elements`
+	let mut strides = vec![1; shape.len()];
+	for i in 1..shape.len() {
+		strides[i] = strides[i - 1] * shape[i - 1];
+	}
+
+	let mut out = Vec::with_capacity(data.len());
+	let mut index = vec![0; shape.len()];
+	for _ in 0..num_elements {
+		let offset: usize = index.iter().zip(&strides).map(|(i, s)| i * s).sum::<usize>() * element_size;
+		out.extend_from_slice(&data[offset..offset + element_size]);
+		// increment the multi-dimensional index, last dimension first
+		for (i, dim) in index.iter_mut().zip(&shape).rev() {
+			*i += 1;
+			if *i < *dim {
+				break;
+			}
+			*i = 0;
+		}
+	}
+	Ok(out)
+}
+
+fn read_tensor(mut reader: impl Read) -> Result<DynTensor> {
+	let header = Header::read(&mut reader)?;
+	if header.shape.iter().any(|&d| d < 0) {
+		return Err(invalid(format!("Invalid NumPy array shape {:?}", header.shape)));
+	}
+	let num_elements = num_elements(&header.shape)?;
+	let element_size = header.descr.element_size();
+	let data_len = num_elements
+		.checked_mul(element_size)
+		.ok_or_else(|| invalid(format!("NumPy array of shape {:?} is too large", header.shape)))?;
+	let mut data = read_bytes(&mut reader, data_len, "array data")?;
+	if header.fortran_order && header.shape.len() > 1 {
+		data = fortran_to_c(&data, &header.shape, element_size)?;
+	}
+
+	match header.descr {
+		Descr::Unicode { len, big_endian } => {
+			let strings = if len == 0 {
+				vec![String::new(); num_elements]
+			} else {
+				data.chunks_exact(element_size)
+					.map(|element| {
+						element
+							.chunks_exact(4)
+							.map(|c| {
+								let c = [c[0], c[1], c[2], c[3]];
+								if big_endian { u32::from_be_bytes(c) } else { u32::from_le_bytes(c) }
+							})
+							.take_while(|&c| c != 0)
+							.map(|c| char::from_u32(c).ok_or_else(|| invalid(format!("Invalid UTF-32 code point {c:#x} in NumPy string array"))))
+							.collect::<Result<String>>()
+					})
+					.collect::<Result<Vec<_>>>()?
+			};
+			Tensor::from_string_array((header.shape, &*strings)).map(Tensor::upcast)
+		}
+		Descr::Primitive { ty, big_endian } => {
+			if big_endian {
+				// complex numbers are stored as 2 floats, each of which is swapped separately
+				let unit = if matches!(ty, TensorElementType::Complex64 | TensorElementType::Complex128) {
+					element_size / 2
+				} else {
+					element_size
+				};
+				data.chunks_exact_mut(unit).for_each(<[u8]>::reverse);
+			}
+			let mut tensor = DynTensor::new(&Allocator::default(), ty, Shape::from(header.shape))?;
+			if !data.is_empty() {
+				unsafe { ptr::copy_nonoverlapping(data.as_ptr(), tensor.data_ptr_mut()?.cast::<u8>(), data.len()) };
+			}
+			Ok(tensor)
+		}
+	}
+}
+
+impl DynTensor {
+	/// Reads a tensor from a NumPy `.npy` file. The type of the tensor is determined by the file's header.
+	///
+	/// All numeric & boolean NumPy dtypes which have a corresponding [`TensorElementType`] are supported, in either
+	/// byte order, as are fixed-width unicode string arrays (`<U{n}`), which are read as string tensors. Arrays stored
+	/// in Fortran order are converted to C order.
+	///
+	/// ```
+	/// # use ort::value::{DynTensor, Tensor};
+	/// # fn main() -> ort::Result<()> {
+	/// let tensor = Tensor::from_array(([2usize, 2], vec![1.0_f32, 2.0, 3.0, 4.0]))?;
+	/// let mut npy = Vec::new();
+	/// tensor.write_npy(&mut npy)?;
+	///
+	/// let tensor = DynTensor::from_npy(&*npy)?;
+	/// assert_eq!(tensor.try_extract_tensor::<f32>()?.1, [1.0, 2.0, 3.0, 4.0]);
+	/// # 	Ok(())
+	/// # }
+	/// ```
+	pub fn from_npy(reader: impl Read) -> Result<DynTensor> {
+		read_tensor(reader)
+	}
+}
+
+impl<T: PrimitiveTensorElementType + core::fmt::Debug> Tensor<T> {
+	/// Reads a tensor from a NumPy `.npy` file, returning an error if the file's dtype does not correspond to `T`.
+	///
+	/// See [`DynTensor::from_npy`] for details on supported arrays.
+	///
+	/// ```
+	/// # use ort::value::Tensor;
+	/// # fn main() -> ort::Result<()> {
+	/// # let mut npy = Vec::new();
+	/// # Tensor::from_array(([3usize], vec![1_i64, 2, 3]))?.write_npy(&mut npy)?;
+	/// let tensor = Tensor::<i64>::from_npy(&*npy)?;
+	/// assert_eq!(tensor.extract_tensor().1, [1, 2, 3]);
+	/// # 	Ok(())
+	/// # }
+	/// ```
+	pub fn from_npy(reader: impl Read) -> Result<Tensor<T>> {
+		let tensor = read_tensor(reader)?;
+		if !TensorValueType::<T>::can_downcast(tensor.dtype()) {
+			return Err(invalid(format!("Cannot read NumPy array of type {} as a Tensor<{}>", tensor.dtype(), T::into_tensor_element_type())));
+		}
+		Ok(unsafe { tensor.transmute_type() })
+	}
+}
+
+impl<Type: TensorValueTypeMarker + ?Sized> Value<Type> {
+	/// Writes this tensor to a NumPy `.npy` file in C order.
+	///
+	/// String tensors are written as fixed-width unicode (`<U{n}`) arrays. Tensors whose type has no NumPy equivalent,
+	/// like `bfloat16` or 8-bit floats, cannot be written & will return an error, as will values which are not tensors.
+	pub fn write_npy(&self, mut writer: impl Write) -> Result<()> {
+		let ValueType::Tensor { ty, shape, .. } = self.dtype() else {
+			return Err(invalid(format!("Cannot write value of type {} as a NumPy array", self.dtype())));
+		};
+		if *ty == TensorElementType::String {
+			let (_, strings) = self.try_extract_strings()?;
+			let len = strings.iter().map(|s| s.chars().count()).max().unwrap_or(0);
+			Header {
+				descr: Descr::Unicode { len, big_endian: false },
+				fortran_order: false,
+				shape: shape.to_vec()
+			}
+			.write(&mut writer)?;
+			let mut data = Vec::with_capacity(strings.len() * len * 4);
+			for string in &strings {
+				let chars = string.chars().count();
+				data.extend(string.chars().flat_map(|c| u32::from(c).to_le_bytes()));
+				data.resize(data.len() + (len - chars) * 4, 0);
+			}
+			return writer.write_all(&data).map_err(io_error);
+		}
+
+		Header {
+			descr: Descr::Primitive { ty: *ty, big_endian: false },
+			fortran_order: false,
+			shape: shape.to_vec()
+		}
+		.write(&mut writer)?;
+		writer.write_all(self.raw_bytes()?).map_err(io_error)
+	}
+}
+
+/// Reads every array in a NumPy `.npz` archive (as created by `numpy.savez` or `numpy.savez_compressed`), returning
+/// pairs of array names & tensors in the order they appear in the archive.
+///
+/// The result can be passed directly to [`Session::run`](crate::session::Session::run):
+/// ```no_run
+/// # use ort::{session::Session, tensor::{read_npz, write_npz}};
+/// # fn main() -> ort::Result<()> {
+/// let session = Session::builder()?.commit_from_file("model.onnx")?;
+/// let inputs = read_npz(std::fs::File::open("inputs.npz").unwrap())?;
+/// let outputs = session.run(inputs)?;
+/// write_npz(std::fs::File::create("outputs.npz").unwrap(), &outputs)?;
+/// # 	Ok(())
+/// # }
+/// ```
+///
+/// See [`DynTensor::from_npy`] for details on supported arrays.
+pub fn read_npz(reader: impl Read + Seek) -> Result<Vec<(String, DynTensor)>> {
+	let mut archive = zip::ZipArchive::new(reader).map_err(|e| invalid(format!("Failed to read `.npz` archive: {e}")))?;
+	let mut tensors = Vec::with_capacity(archive.len());
+	for i in 0..archive.len() {
+		let file = archive.by_index(i).map_err(|e| invalid(format!("Failed to read `.npz` archive: {e}")))?;
+		let name = file.name();
+		let name = name.strip_suffix(".npy").unwrap_or(name).to_string();
+		let tensor = read_tensor(file).map_err(|e| Error::new_with_code(e.code(), format!("Failed to read array `{name}`: {e}")))?;
+		tensors.push((name, tensor));
+	}
+	Ok(tensors)
+}
+
+/// Writes tensors to an uncompressed NumPy `.npz` archive, like `numpy.savez`. Each item is a pair of an array name &
+/// a tensor; a [`SessionOutputs`](crate::session::SessionOutputs) can be passed directly to write all of a session's
+/// outputs.
+///
+/// Returns an error if any value is not a tensor. See [`Value::write_npy`] for details on how tensors are written.
+pub fn write_npz<K: AsRef<str>, V: Deref<Target = Value<T>>, T: ValueTypeMarker + ?Sized>(
+	writer: impl Write + Seek,
+	values: impl IntoIterator<Item = (K, V)>
+) -> Result<()> {
+	let mut archive = zip::ZipWriter::new(writer);
+	let options = zip::write::SimpleFileOptions::default().compression_method(zip::CompressionMethod::Stored);
+	for (name, value) in values {
+		let name = name.as_ref();
+		let value = value.view().into_dyn();
+		let tensor = value
+			.downcast_ref::<DynTensorValueType>()
+			.map_err(|_| invalid(format!("Cannot write value `{name}` of type {} to a NumPy array", value.dtype())))?;
+		archive
+			.start_file(format!("{name}.npy"), options)
+			.map_err(|e| Error::new(format!("Failed to write `.npz` archive: {e}")))?;
+		tensor.write_npy(&mut archive)?;
+	}
+	archive.finish().map_err(|e| Error::new(format!("Failed to write `.npz` archive: {e}")))?;
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use alloc::{vec, vec::Vec};
+
+	use super::{Descr, Header, fortran_to_c, read_tensor};
+	use crate::{ErrorCode, tensor::TensorElementType};
+
+	#[test]
+	fn test_header() -> crate::Result<()> {
+		let header = Header {
+			descr: Descr::Primitive {
+				ty: TensorElementType::Float32,
+				big_endian: false
+			},
+			fortran_order: false,
+			shape: vec![3]
+		};
+		let mut bytes = vec![];
+		header.write(&mut bytes)?;
+		assert_eq!(bytes.len() % 64, 0);
+		assert!(bytes[10..].starts_with(b"{'descr': '<f4', 'fortran_order': False, 'shape': (3,), }  "));
+		assert_eq!(bytes.last(), Some(&b'\n'));
+
+		let bytes = b"\x93NUMPY\x01\x00\x49\x00{'descr': '>c16', 'fortran_order': True, 'shape': (2, 3), }             \n";
+		let header = Header::read(&mut &bytes[..])?;
+		assert_eq!(
+			header.descr,
+			Descr::Primitive {
+				ty: TensorElementType::Complex128,
+				big_endian: true
+			}
+		);
+		assert!(header.fortran_order);
+		assert_eq!(header.shape, [2, 3]);
+		Ok(())
+	}
+
+	#[test]
+	fn test_fortran_to_c() -> crate::Result<()> {
+		// [[0, 1, 2], [3, 4, 5]] in column-major order
+		let data = [0, 3, 1, 4, 2, 5];
+		assert_eq!(fortran_to_c(&data, &[2, 3], 1)?, [0, 1, 2, 3, 4, 5]);
+		assert!(fortran_to_c(&data, &[1 << 40, 1 << 40], 1).is_err());
+		assert!(fortran_to_c(&[], &[0, 1 << 40, 1 << 40], 1)?.is_empty());
+		Ok(())
+	}
+
+	#[test]
+	fn test_read_invalid_size() -> crate::Result<()> {
+		let npy = |shape: &[i64]| -> crate::Result<Vec<u8>> {
+			let mut npy = vec![];
+			Header {
+				descr: Descr::Primitive {
+					ty: TensorElementType::Float32,
+					big_endian: false
+				},
+				fortran_order: true,
+				shape: shape.to_vec()
+			}
+			.write(&mut npy)?;
+			npy.extend_from_slice(&[0; 16]);
+			Ok(npy)
+		};
+		// shapes whose size overflows, or exceeds the data actually present, are rejected before allocating
+		for shape in [&[1 << 62, 4][..], &[1 << 32, 1 << 32], &[1 << 40], &[2, 3]] {
+			assert!(matches!(read_tensor(&*npy(shape)?), Err(e) if e.code() == ErrorCode::InvalidArgument), "{shape:?}");
+		}
+
+		// as are headers longer than the file
+		let mut npy = b"\x93NUMPY\x02\x00\xff\xff\xff\xff".to_vec();
+		npy.extend_from_slice(&[b' '; 64]);
+		assert!(matches!(read_tensor(&*npy), Err(e) if e.code() == ErrorCode::InvalidArgument));
+		Ok(())
+	}
+}
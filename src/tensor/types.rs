@@ -45,9 +45,9 @@ pub enum TensorElementType {
 	/// 8-bit floating point number with 5 exponent bits and 2 mantissa bits, with only NaN values, no infinite
 	/// values, and no negative zero.
 	Float8E5M2FNUZ,
-	/// 4-bit unsigned integer.
+	/// 4-bit unsigned integer, packed two to a byte as [`Uint4x2`](crate::tensor::Uint4x2).
 	Uint4,
-	/// 4-bit signed integer.
+	/// 4-bit signed integer, packed two to a byte as [`Int4x2`](crate::tensor::Int4x2).
 	Int4,
 	Undefined
 }
@@ -56,14 +56,14 @@ impl TensorElementType {
 	/// Returns the size in bytes that a container of this type occupies according to its total capacity.
 	pub fn byte_size(&self, container_capacity: usize) -> usize {
 		match self {
-			TensorElementType::Uint4 | TensorElementType::Int4 => container_capacity / 2,
+			TensorElementType::Uint4 | TensorElementType::Int4 => container_capacity.div_ceil(2),
 			TensorElementType::Bool | TensorElementType::Int8 | TensorElementType::Uint8 => container_capacity,
 			TensorElementType::Int16 | TensorElementType::Uint16 => container_capacity * 2,
 			TensorElementType::Int32 | TensorElementType::Uint32 => container_capacity * 4,
 			TensorElementType::Int64 | TensorElementType::Uint64 => container_capacity * 8,
 			TensorElementType::String => 0, // unsure what to do about this...
 			TensorElementType::Float8E4M3FN | TensorElementType::Float8E4M3FNUZ | TensorElementType::Float8E5M2 | TensorElementType::Float8E5M2FNUZ => {
-				container_capacity * 4
+				container_capacity
 			}
 			TensorElementType::Float16 | TensorElementType::Bfloat16 => container_capacity * 2,
 			TensorElementType::Float32 => container_capacity * 4,
@@ -73,6 +73,17 @@ impl TensorElementType {
 			TensorElementType::Undefined => 0
 		}
 	}
+
+	/// Returns `true` if multiple elements of this type are packed into a single byte.
+	pub(crate) fn is_packed(&self) -> bool {
+		matches!(self, TensorElementType::Uint4 | TensorElementType::Int4)
+	}
+
+	/// Returns the number of Rust elements (e.g. [`Int4x2`](crate::tensor::Int4x2)s for packed types) backing a tensor
+	/// with `num_elements` elements.
+	pub(crate) fn container_len(&self, num_elements: usize) -> usize {
+		if self.is_packed() { num_elements.div_ceil(2) } else { num_elements }
+	}
 }
 
 impl fmt::Display for TensorElementType {
@@ -179,7 +179,7 @@ impl Checkpoint {
 			trainsys![unsafe GetParameter(self.ptr.as_ptr(), name.as_ptr(), allocator.ptr().cast_mut(), &mut value_ptr)?; nonNull(value_ptr)];
 			Ok(value_ptr)
 		})?;
-		Ok(unsafe { DynTensor::from_ptr(NonNull::new_unchecked(value_ptr), None) })
+		unsafe { DynTensor::from_ptr(NonNull::new_unchecked(value_ptr), None) }
 	}
 
 	pub fn update_parameter<T: ValueTypeMarker>(&mut self, name: impl AsRef<str>, value: &Value<T>) -> Result<()> {
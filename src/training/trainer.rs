@@ -15,7 +15,7 @@ use crate::{
 	session::{RunOptions, SessionInputValue, SessionInputs, SessionOutputs, builder::SessionBuilder},
 	tensor::IntoTensorElementType,
 	util::with_cstr_ptr_array,
-	value::{Tensor, Value}
+	value::{DynValue, Tensor, Value}
 };
 
 #[derive(Debug)]
@@ -182,7 +182,10 @@ impl Trainer {
 				// but for now, nobody should be using the loss tensor past the lifetime of the trainer... right...? 😣
 				Value::from_ptr(NonNull::new(tensor_ptr).expect("OrtValue ptr returned from session Run should not be null"), None)
 			})
-			.collect();
+			// wrap every output before bailing on an error, so none are leaked
+			.collect::<Vec<Result<DynValue>>>()
+			.into_iter()
+			.collect::<Result<_>>()?;
 
 		Ok(SessionOutputs::new(self.train_output_names.iter().map(String::as_str).collect(), outputs))
 	}
@@ -243,7 +246,10 @@ impl Trainer {
 				// but for now, nobody should be using the loss tensor past the lifetime of the trainer... right...? 😣
 				Value::from_ptr(NonNull::new(tensor_ptr).expect("OrtValue ptr returned from session Run should not be null"), None)
 			})
-			.collect();
+			// wrap every output before bailing on an error, so none are leaked
+			.collect::<Vec<Result<DynValue>>>()
+			.into_iter()
+			.collect::<Result<_>>()?;
 
 		Ok(SessionOutputs::new(self.eval_output_names.iter().map(String::as_str).collect(), outputs))
 	}
@@ -1,6 +1,5 @@
-use alloc::{boxed::Box, format, string::String, sync::Arc, vec, vec::Vec};
+use alloc::{boxed::Box, format, string::String, sync::Arc, vec::Vec};
 use core::{
-	ffi::c_void,
 	fmt::{self, Debug},
 	hash::Hash,
 	marker::PhantomData,
@@ -13,14 +12,14 @@ use std::collections::HashMap;
 
 use super::{
 	DowncastableTarget, DynValue, Value, ValueInner, ValueRef, ValueRefMut, ValueType, ValueTypeMarker,
-	impl_tensor::{DynTensor, Tensor}
+	impl_tensor::{DynTensor, Tensor, tensor_from_array}
 };
 use crate::{
 	AsPointer, ErrorCode,
 	error::{Error, Result},
-	memory::Allocator,
+	memory::{Allocator, MemoryInfo},
 	ortsys,
-	tensor::{IntoTensorElementType, PrimitiveTensorElementType, TensorElementType}
+	tensor::{IntoTensorElementType, Shape, TensorElementType}
 };
 
 pub trait MapValueTypeMarker: ValueTypeMarker {
@@ -85,97 +84,33 @@ pub type MapRef<'v, K, V> = ValueRef<'v, MapValueType<K, V>>;
 pub type MapRefMut<'v, K, V> = ValueRefMut<'v, MapValueType<K, V>>;
 
 impl<Type: MapValueTypeMarker + ?Sized> Value<Type> {
-	pub fn try_extract_key_values<K: IntoTensorElementType + Clone + Hash + Eq, V: PrimitiveTensorElementType + Clone>(&self) -> Result<Vec<(K, V)>> {
+	/// Attempts to extract the key-value pairs of this map. Keys & values may be any primitive type, or [`String`].
+	///
+	/// ```
+	/// # use ort::value::Map;
+	/// # fn main() -> ort::Result<()> {
+	/// let labels = Map::<i64, String>::new([(0, "cat".to_string()), (1, "dog".to_string())])?.into_dyn();
+	///
+	/// let mut key_values = labels.try_extract_key_values::<i64, String>()?;
+	/// key_values.sort();
+	/// assert_eq!(key_values, [(0, "cat".to_string()), (1, "dog".to_string())]);
+	/// # 	Ok(())
+	/// # }
+	/// ```
+	pub fn try_extract_key_values<K: IntoTensorElementType + Clone + Hash + Eq, V: IntoTensorElementType + Clone>(&self) -> Result<Vec<(K, V)>> {
 		match self.dtype() {
 			ValueType::Map { key, value } => {
-				let k_type = K::into_tensor_element_type();
-				if k_type != *key {
-					return Err(Error::new_with_code(ErrorCode::InvalidArgument, format!("Cannot extract Map<{:?}, _> (value has K type {:?})", k_type, key)));
-				}
-				let v_type = V::into_tensor_element_type();
-				if v_type != *value {
-					return Err(Error::new_with_code(
-						ErrorCode::InvalidArgument,
-						format!("Cannot extract Map<{}, {}> from Map<{}, {}>", K::into_tensor_element_type(), V::into_tensor_element_type(), k_type, v_type)
-					));
+				let (k_type, v_type) = (K::into_tensor_element_type(), V::into_tensor_element_type());
+				if k_type != *key || v_type != *value {
+					return Err(Error::new_with_code(ErrorCode::InvalidArgument, format!("Cannot extract Map<{k_type}, {v_type}> from Map<{key}, {value}>")));
 				}
 
-				let allocator = Allocator::default();
-
-				let mut key_tensor_ptr = ptr::null_mut();
-				ortsys![unsafe GetValue(self.ptr(), 0, allocator.ptr().cast_mut(), &mut key_tensor_ptr)?; nonNull(key_tensor_ptr)];
-				let key_value: DynTensor = unsafe { Value::from_ptr(NonNull::new_unchecked(key_tensor_ptr), None) };
-				if K::into_tensor_element_type() != TensorElementType::String {
-					let dtype = key_value.dtype();
-					let (key_tensor_shape, key_tensor) = match dtype {
-						ValueType::Tensor { ty, shape, .. } => {
-							let mem = key_value.memory_info();
-							if !mem.is_cpu_accessible() {
-								return Err(Error::new(format!(
-									"Cannot extract from value on device `{}`, which is not CPU accessible",
-									mem.allocation_device().as_str()
-								)));
-							}
-
-							if *ty == K::into_tensor_element_type() {
-								let mut output_array_ptr: *mut K = ptr::null_mut();
-								let output_array_ptr_ptr: *mut *mut K = &mut output_array_ptr;
-								let output_array_ptr_ptr_void: *mut *mut c_void = output_array_ptr_ptr.cast();
-								ortsys![unsafe GetTensorMutableData(key_tensor_ptr, output_array_ptr_ptr_void)?; nonNull(output_array_ptr)];
-
-								(shape, unsafe { slice::from_raw_parts(output_array_ptr, shape.num_elements()) })
-							} else {
-								return Err(Error::new_with_code(
-									ErrorCode::InvalidArgument,
-									format!(
-										"Cannot extract Map<{}, {}> from Map<{}, {}>",
-										K::into_tensor_element_type(),
-										V::into_tensor_element_type(),
-										k_type,
-										v_type
-									)
-								));
-							}
-						}
-						_ => unreachable!()
-					};
-
-					let mut value_tensor_ptr = ptr::null_mut();
-					ortsys![unsafe GetValue(self.ptr(), 1, allocator.ptr().cast_mut(), &mut value_tensor_ptr)?; nonNull(value_tensor_ptr)];
-					let value_value: DynTensor = unsafe { Value::from_ptr(NonNull::new_unchecked(value_tensor_ptr), None) };
-					let (value_tensor_shape, value_tensor) = value_value.try_extract_tensor::<V>()?;
-
-					assert_eq!(key_tensor_shape.len(), 1);
-					assert_eq!(value_tensor_shape.len(), 1);
-					assert_eq!(key_tensor_shape[0], value_tensor_shape[0]);
-
-					let mut vec = Vec::with_capacity(key_tensor_shape[0] as _);
-					for i in 0..key_tensor_shape[0] as usize {
-						vec.push((key_tensor[i].clone(), value_tensor[i].clone()));
-					}
-					Ok(vec)
-				} else {
-					let (key_tensor_shape, key_tensor) = key_value.try_extract_strings()?;
-					// SAFETY: `IntoTensorElementType` is a private trait, and we only map the `String` type to `TensorElementType::String`,
-					// so at this point, `K` is **always** the `String` type, and this transmute really does nothing but please the type
-					// checker.
-					let key_tensor: Vec<K> = unsafe { mem::transmute(key_tensor) };
-
-					let mut value_tensor_ptr = ptr::null_mut();
-					ortsys![unsafe GetValue(self.ptr(), 1, allocator.ptr().cast_mut(), &mut value_tensor_ptr)?; nonNull(value_tensor_ptr)];
-					let value_value: DynTensor = unsafe { Value::from_ptr(NonNull::new_unchecked(value_tensor_ptr), None) };
-					let (value_tensor_shape, value_tensor) = value_value.try_extract_tensor::<V>()?;
-
-					assert_eq!(key_tensor_shape.len(), 1);
-					assert_eq!(value_tensor_shape.len(), 1);
-					assert_eq!(key_tensor_shape[0], value_tensor_shape[0]);
-
-					let mut vec = Vec::with_capacity(key_tensor_shape[0] as _);
-					for i in 0..key_tensor_shape[0] as usize {
-						vec.push((key_tensor[i].clone(), value_tensor[i].clone()));
-					}
-					Ok(vec.into_iter().collect())
+				let (keys, values) = self.key_value_tensors()?;
+				let (keys, values) = (extract_elements::<K>(&keys)?, extract_elements::<V>(&values)?);
+				if keys.len() != values.len() {
+					return Err(Error::new_with_code(ErrorCode::InvalidArgument, format!("Map has {} keys but {} values", keys.len(), values.len())));
 				}
+				Ok(keys.into_iter().zip(values).collect())
 			}
 			t => Err(Error::new_with_code(
 				ErrorCode::InvalidArgument,
@@ -186,13 +121,63 @@ impl<Type: MapValueTypeMarker + ?Sized> Value<Type> {
 
 	#[cfg(feature = "std")]
 	#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
-	pub fn try_extract_map<K: IntoTensorElementType + Clone + Hash + Eq, V: PrimitiveTensorElementType + Clone>(&self) -> Result<HashMap<K, V>> {
+	pub fn try_extract_map<K: IntoTensorElementType + Clone + Hash + Eq, V: IntoTensorElementType + Clone>(&self) -> Result<HashMap<K, V>> {
 		self.try_extract_key_values().map(|c| c.into_iter().collect())
 	}
+
+	/// Returns the 1-dimensional tensors of this map's keys & values respectively.
+	pub(crate) fn key_value_tensors(&self) -> Result<(DynTensor, DynTensor)> {
+		let allocator = Allocator::default();
+		let get = |index: i32| -> Result<DynTensor> {
+			let mut tensor_ptr = ptr::null_mut();
+			ortsys![unsafe GetValue(self.ptr(), index, allocator.ptr().cast_mut(), &mut tensor_ptr)?; nonNull(tensor_ptr)];
+			unsafe { Value::from_ptr(NonNull::new_unchecked(tensor_ptr), None) }
+		};
+		Ok((get(0)?, get(1)?))
+	}
+}
+
+/// Copies the elements of a tensor of keys or values, whose element type must be `T`.
+fn extract_elements<T: IntoTensorElementType + Clone>(tensor: &DynTensor) -> Result<Vec<T>> {
+	let ty = T::into_tensor_element_type();
+	if ty == TensorElementType::String {
+		let (_, strings) = tensor.try_extract_strings()?;
+		// SAFETY: `IntoTensorElementType` is a private trait, and we only map the `String` type to `TensorElementType::String`,
+		// so at this point, `T` is **always** the `String` type, and this transmute really does nothing but please the type
+		// checker.
+		return Ok(unsafe { mem::transmute::<Vec<String>, Vec<T>>(strings) });
+	}
+
+	if tensor.dtype().tensor_type() != Some(ty) {
+		return Err(Error::new_with_code(ErrorCode::InvalidArgument, format!("Cannot extract Tensor<{ty}> from {}", tensor.dtype())));
+	}
+	let len = tensor.shape().num_elements();
+	if len == 0 {
+		// the data of an empty tensor may not be aligned for `T`
+		return Ok(Vec::new());
+	}
+	let data = tensor.raw_bytes()?;
+	Ok(unsafe { slice::from_raw_parts(data.as_ptr().cast::<T>(), len) }.to_vec())
 }
 
-impl<K: PrimitiveTensorElementType + Debug + Clone + Hash + Eq + 'static, V: PrimitiveTensorElementType + Debug + Clone + 'static> Value<MapValueType<K, V>> {
-	/// Creates a [`Map`] from an iterable emitting `K` and `V`.
+/// Creates a 1-dimensional tensor of keys or values.
+fn elements_tensor<T: IntoTensorElementType + Debug + Clone + 'static>(elements: Vec<T>) -> Result<Tensor<T>> {
+	let ty = T::into_tensor_element_type();
+	let shape = Shape::new([elements.len() as i64]);
+	let tensor = if ty == TensorElementType::String {
+		// SAFETY: see `extract_elements`
+		let strings = unsafe { mem::transmute::<Vec<T>, Vec<String>>(elements) };
+		Tensor::from_strings(shape, strings)?.upcast()
+	} else {
+		let mut elements = elements;
+		let data = elements.as_mut_ptr();
+		tensor_from_array(MemoryInfo::default(), shape, data.cast(), mem::size_of::<T>(), ty, Some(Box::new(elements)))?
+	};
+	Ok(unsafe { tensor.transmute_type() })
+}
+
+impl<K: IntoTensorElementType + Debug + Clone + Hash + Eq + 'static, V: IntoTensorElementType + Debug + Clone + 'static> Value<MapValueType<K, V>> {
+	/// Creates a [`Map`] from an iterable emitting `K` and `V`. Keys & values may be any primitive type, or [`String`].
 	///
 	/// ```
 	/// # use std::collections::HashMap;
@@ -204,42 +189,23 @@ impl<K: PrimitiveTensorElementType + Debug + Clone + Hash + Eq + 'static, V: Pri
 	/// map.insert(2, 3.0);
 	///
 	/// let value = Map::<i64, f32>::new(map)?;
-	///
 	/// assert_eq!(*value.extract_map().get(&0).unwrap(), 1.0);
-	/// # 	Ok(())
-	/// # }
-	/// ```
-	pub fn new(data: impl IntoIterator<Item = (K, V)>) -> Result<Self> {
-		let (keys, values): (Vec<K>, Vec<V>) = data.into_iter().unzip();
-		Self::new_kv(Tensor::from_array((vec![keys.len()], keys))?, Tensor::from_array((vec![values.len()], values))?)
-	}
-}
-
-impl<V: PrimitiveTensorElementType + Debug + Clone + 'static> Value<MapValueType<String, V>> {
-	/// Creates a [`Map`] from an iterable emitting `K` and `V`.
 	///
-	/// ```
-	/// # use std::collections::HashMap;
-	/// # use ort::value::Map;
-	/// # fn main() -> ort::Result<()> {
 	/// let mut map = HashMap::<String, f32>::new();
 	/// map.insert("one".to_string(), 1.0);
 	/// map.insert("two".to_string(), 2.0);
 	/// map.insert("three".to_string(), 3.0);
 	///
 	/// let value = Map::<String, f32>::new(map)?;
-	///
 	/// assert_eq!(*value.extract_map().get("one").unwrap(), 1.0);
 	/// # 	Ok(())
 	/// # }
 	/// ```
-	pub fn new(data: impl IntoIterator<Item = (String, V)>) -> Result<Self> {
-		let (keys, values): (Vec<String>, Vec<V>) = data.into_iter().unzip();
-		Self::new_kv(Tensor::from_string_array((vec![keys.len()], keys.as_slice()))?, Tensor::from_array((vec![values.len()], values))?)
+	pub fn new(data: impl IntoIterator<Item = (K, V)>) -> Result<Self> {
+		let (keys, values): (Vec<K>, Vec<V>) = data.into_iter().unzip();
+		Self::new_kv(elements_tensor(keys)?, elements_tensor(values)?)
 	}
-}
 
-impl<K: IntoTensorElementType + Debug + Clone + Hash + Eq + 'static, V: IntoTensorElementType + Debug + Clone + 'static> Value<MapValueType<K, V>> {
 	/// Creates a [`Map`] from two tensors of keys & values respectively.
 	///
 	/// ```
@@ -256,6 +222,18 @@ impl<K: IntoTensorElementType + Debug + Clone + Hash + Eq + 'static, V: IntoTens
 	/// # }
 	/// ```
 	pub fn new_kv(keys: Tensor<K>, values: Tensor<V>) -> Result<Self> {
+		let map = DynMap::new_dyn_kv(keys.upcast(), values.upcast())?;
+		Ok(unsafe { map.transmute_type() })
+	}
+}
+
+impl DynMap {
+	/// Creates a [`DynMap`] from two 1-dimensional tensors of keys & values respectively.
+	pub(crate) fn new_dyn_kv(keys: DynTensor, values: DynTensor) -> Result<Self> {
+		let (Some(key), Some(value)) = (keys.dtype().tensor_type(), values.dtype().tensor_type()) else {
+			return Err(Error::new_with_code(ErrorCode::InvalidArgument, "Map keys & values must be tensors"));
+		};
+
 		let mut value_ptr = ptr::null_mut();
 		let values: [DynValue; 2] = [keys.into_dyn(), values.into_dyn()];
 		let value_ptrs: Vec<*const ort_sys::OrtValue> = values.iter().map(|c| c.ptr()).collect();
@@ -266,10 +244,7 @@ impl<K: IntoTensorElementType + Debug + Clone + Hash + Eq + 'static, V: IntoTens
 		Ok(Value {
 			inner: Arc::new(ValueInner {
 				ptr: unsafe { NonNull::new_unchecked(value_ptr) },
-				dtype: ValueType::Map {
-					key: K::into_tensor_element_type(),
-					value: V::into_tensor_element_type()
-				},
+				dtype: ValueType::Map { key, value },
 				drop: true,
 				memory_info: None,
 				_backing: Some(Box::new(values))
@@ -279,7 +254,7 @@ impl<K: IntoTensorElementType + Debug + Clone + Hash + Eq + 'static, V: IntoTens
 	}
 }
 
-impl<K: IntoTensorElementType + Debug + Clone + Hash + Eq, V: PrimitiveTensorElementType + Debug + Clone> Value<MapValueType<K, V>> {
+impl<K: IntoTensorElementType + Debug + Clone + Hash + Eq, V: IntoTensorElementType + Debug + Clone> Value<MapValueType<K, V>> {
 	pub fn extract_key_values(&self) -> Vec<(K, V)> {
 		self.try_extract_key_values().expect("Failed to extract map")
 	}
@@ -0,0 +1,134 @@
+use alloc::{boxed::Box, sync::Arc};
+use core::{
+	fmt::{self, Debug, Display},
+	marker::PhantomData
+};
+
+use super::{DowncastableTarget, DynValueTypeMarker, Value, ValueRef, ValueRefMut, ValueType, ValueTypeMarker, format_value_type};
+use crate::{AsPointer, error::Result, memory::Allocator, ortsys, tensor::TensorElementType, value::DynTensor};
+
+/// Marker for an optional value which may contain a value of type `T`.
+///
+/// An optional which contains a value is represented by that value itself, and so has the same [`ValueType`]; an empty
+/// optional has a [`ValueType::Optional`]. This mirrors how ONNX Runtime treats optionals: any value can be passed to
+/// an `optional(T)` input, and an `optional(T)` output which contains a value is just a value of type `T`.
+#[derive(Debug)]
+pub struct OptionalValueType<T: ValueTypeMarker + ?Sized = DynValueTypeMarker>(PhantomData<T>);
+impl<T: ValueTypeMarker + ?Sized> ValueTypeMarker for OptionalValueType<T> {
+	fn fmt(f: &mut fmt::Formatter) -> fmt::Result {
+		f.write_str("Optional<")?;
+		format_value_type::<T>().fmt(f)?;
+		f.write_str(">")
+	}
+
+	private_impl!();
+}
+
+impl<T: ValueTypeMarker + DowncastableTarget + ?Sized> DowncastableTarget for OptionalValueType<T> {
+	fn can_downcast(dtype: &ValueType) -> bool {
+		match dtype {
+			ValueType::Optional(ty) => T::can_downcast(ty),
+			ty => T::can_downcast(ty)
+		}
+	}
+
+	private_impl!();
+}
+
+/// An optional [`Value`] which may contain a value of type `T`.
+///
+/// ```
+/// # use ort::value::{Optional, Tensor, TensorValueType, ValueType};
+/// # fn main() -> ort::Result<()> {
+/// let tensor = Tensor::from_array(([3usize], vec![1.0_f32, 2.0, 3.0].into_boxed_slice()))?;
+/// let some = Optional::some(tensor);
+/// assert!(some.is_some());
+///
+/// let none = Optional::<TensorValueType<f32>>::none(some.dtype())?;
+/// assert!(none.is_none());
+/// assert!(matches!(none.dtype(), ValueType::Optional(_)));
+/// # 	Ok(())
+/// # }
+/// ```
+pub type Optional<T = DynValueTypeMarker> = Value<OptionalValueType<T>>;
+/// An optional [`Value`] which may contain a value of any type.
+pub type DynOptional = Optional<DynValueTypeMarker>;
+
+pub type OptionalRef<'v, T = DynValueTypeMarker> = ValueRef<'v, OptionalValueType<T>>;
+pub type OptionalRefMut<'v, T = DynValueTypeMarker> = ValueRefMut<'v, OptionalValueType<T>>;
+
+impl<T: ValueTypeMarker + ?Sized> Value<OptionalValueType<T>> {
+	/// Creates an optional containing `value`.
+	pub fn some(value: Value<T>) -> Self {
+		unsafe { value.transmute_type() }
+	}
+
+	/// Creates an empty optional. `ty` is the type of the value the optional could contain, or the
+	/// [`ValueType::Optional`] itself, so the type of a session input can be used directly:
+	///
+	/// ```no_run
+	/// # use ort::{session::Session, value::DynOptional};
+	/// # fn main() -> ort::Result<()> {
+	/// # 	let session = Session::builder()?.commit_from_file("model.onnx")?;
+	/// let outputs = session.run(ort::inputs![DynOptional::none(&session.inputs[0].input_type)?])?;
+	/// # 	Ok(())
+	/// # }
+	/// ```
+	///
+	/// ONNX Runtime provides no way to create an empty optional value, so when used as a session input, the input is
+	/// instead omitted, which ONNX Runtime treats as empty.
+	pub fn none(ty: &ValueType) -> Result<Self> {
+		let dtype = match ty {
+			ValueType::Optional(_) => ty.clone(),
+			ty => ValueType::Optional(Box::new(ty.clone()))
+		};
+		// The placeholder ensures the value has a valid `OrtValue` to point to in case it is used with other APIs.
+		let mut placeholder = DynTensor::new(&Allocator::default(), TensorElementType::Float32, [0_usize])?;
+		Arc::get_mut(&mut placeholder.inner).expect("value should not be shared").dtype = dtype;
+		Ok(unsafe { placeholder.transmute_type() })
+	}
+
+	/// Returns `true` if this optional contains a value.
+	pub fn is_some(&self) -> bool {
+		!matches!(self.dtype(), ValueType::Optional(_))
+	}
+
+	/// Returns `true` if this optional is empty.
+	pub fn is_none(&self) -> bool {
+		!self.is_some()
+	}
+
+	/// Returns the contained value, or `None` if the optional is empty.
+	pub fn into_inner(self) -> Option<Value<T>> {
+		if self.is_some() { Some(unsafe { self.transmute_type() }) } else { None }
+	}
+
+	/// Returns a reference to the contained value, or `None` if the optional is empty.
+	pub fn as_inner(&self) -> Option<ValueRef<'_, T>> {
+		if self.is_some() {
+			Some(ValueRef::new(unsafe { Value::clone_of(self).transmute_type() }))
+		} else {
+			None
+		}
+	}
+}
+
+impl<T: ValueTypeMarker + ?Sized> From<Value<T>> for Value<OptionalValueType<T>> {
+	fn from(value: Value<T>) -> Self {
+		Self::some(value)
+	}
+}
+
+impl<Type: ValueTypeMarker + ?Sized> Value<Type> {
+	/// Returns `true` if this is an empty optional created with [`Optional::none`], which must be omitted from session
+	/// inputs.
+	pub(crate) fn is_none_placeholder(&self) -> bool {
+		if !matches!(self.dtype(), ValueType::Optional(_)) {
+			return false;
+		}
+		// Empty optionals returned by ONNX Runtime report `HasValue` as false; our placeholders are allocated tensors.
+		let mut has_value = 0;
+		ortsys![unsafe HasValue(self.ptr(), &mut has_value).expect("infallible")];
+		has_value != 0
+	}
+}
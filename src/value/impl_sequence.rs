@@ -1,16 +1,20 @@
 use alloc::{boxed::Box, format, sync::Arc, vec::Vec};
 use core::{
 	fmt::{self, Debug, Display},
+	hash::Hash,
 	marker::PhantomData,
 	ptr::{self, NonNull}
 };
+#[cfg(feature = "std")]
+use std::collections::HashMap;
 
-use super::{DowncastableTarget, Value, ValueInner, ValueRef, ValueRefMut, ValueType, ValueTypeMarker, format_value_type};
+use super::{DowncastableTarget, Map, MapValueType, Value, ValueInner, ValueRef, ValueRefMut, ValueType, ValueTypeMarker, format_value_type};
 use crate::{
 	AsPointer, ErrorCode,
 	error::{Error, Result},
 	memory::Allocator,
-	ortsys
+	ortsys,
+	tensor::IntoTensorElementType
 };
 
 pub trait SequenceValueTypeMarker: ValueTypeMarker {
@@ -87,7 +91,7 @@ impl<Type: SequenceValueTypeMarker + Sized> Value<Type> {
 					let mut value_ptr = ptr::null_mut();
 					ortsys![unsafe GetValue(self.ptr(), i as _, allocator.ptr().cast_mut(), &mut value_ptr)?; nonNull(value_ptr)];
 
-					let mut value = ValueRef::new(unsafe { Value::from_ptr(NonNull::new_unchecked(value_ptr), None) });
+					let mut value = ValueRef::new(unsafe { Value::from_ptr(NonNull::new_unchecked(value_ptr), None) }?);
 					value.upgradable = false;
 
 					let value_type = value.dtype();
@@ -105,6 +109,33 @@ impl<Type: SequenceValueTypeMarker + Sized> Value<Type> {
 			t => Err(Error::new(format!("Cannot extract Sequence<{}> from {t}", format_value_type::<OtherType>())))
 		}
 	}
+
+	/// Attempts to extract a sequence of maps, like the probabilities output by scikit-learn classifiers converted with
+	/// `skl2onnx`, into a `Vec` of [`HashMap`]s.
+	///
+	/// ```
+	/// # use std::collections::HashMap;
+	/// # use ort::{memory::Allocator, value::{MapValueType, Sequence}};
+	/// # fn main() -> ort::Result<()> {
+	/// let probabilities =
+	/// 	Sequence::<MapValueType<i64, f32>>::from_maps([[(0, 0.25), (1, 0.75)], [(0, 0.9), (1, 0.1)]])?.upcast();
+	///
+	/// let maps = probabilities.try_extract_sequence_of_maps::<i64, f32>(&Allocator::default())?;
+	/// assert_eq!(maps[1], HashMap::from([(0, 0.9), (1, 0.1)]));
+	/// # 	Ok(())
+	/// # }
+	/// ```
+	#[cfg(feature = "std")]
+	#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+	pub fn try_extract_sequence_of_maps<K: IntoTensorElementType + Debug + Clone + Hash + Eq, V: IntoTensorElementType + Debug + Clone>(
+		&self,
+		allocator: &Allocator
+	) -> Result<Vec<HashMap<K, V>>> {
+		self.try_extract_sequence::<MapValueType<K, V>>(allocator)?
+			.iter()
+			.map(|map| map.try_extract_map())
+			.collect()
+	}
 }
 
 impl<T: ValueTypeMarker + DowncastableTarget + Debug + Sized + 'static> Value<SequenceValueType<T>> {
@@ -152,6 +183,30 @@ impl<T: ValueTypeMarker + DowncastableTarget + Debug + Sized + 'static> Value<Se
 	}
 }
 
+impl<K: IntoTensorElementType + Debug + Clone + Hash + Eq + 'static, V: IntoTensorElementType + Debug + Clone + 'static>
+	Value<SequenceValueType<MapValueType<K, V>>>
+{
+	/// Creates a [`Sequence`] of [`Map`]s, where each map is created from an iterable emitting `K` and `V`, like a
+	/// [`HashMap<K, V>`](std::collections::HashMap).
+	///
+	/// ```
+	/// # use std::collections::HashMap;
+	/// # use ort::{memory::Allocator, value::{MapValueType, Sequence}};
+	/// # fn main() -> ort::Result<()> {
+	/// let maps = vec![HashMap::from([(1_i64, "one".to_string())]), HashMap::from([(2, "two".to_string())])];
+	/// let value = Sequence::<MapValueType<i64, String>>::from_maps(maps)?;
+	/// assert_eq!(value.extract_sequence(&Allocator::default()).len(), 2);
+	/// # 	Ok(())
+	/// # }
+	/// ```
+	///
+	/// [`Map`]: crate::value::Map
+	pub fn from_maps<M: IntoIterator<Item = (K, V)>>(maps: impl IntoIterator<Item = M>) -> Result<Self> {
+		let maps = maps.into_iter().map(Map::<K, V>::new).collect::<Result<Vec<_>>>()?;
+		Self::new(maps)
+	}
+}
+
 impl<T: ValueTypeMarker + DowncastableTarget + Debug + Sized> Value<SequenceValueType<T>> {
 	pub fn extract_sequence(&self, allocator: &Allocator) -> Vec<ValueRef<'_, T>> {
 		self.try_extract_sequence(allocator).expect("Failed to extract sequence")
@@ -0,0 +1,326 @@
+//! [`serde`] support for [`Value`]s.
+//!
+//! Values serialize to a self-describing form containing each tensor's shape, element type, and data:
+//!
+//! ```
+//! # use ort::value::{DynValue, Tensor};
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let tensor = Tensor::from_array(([2usize], vec![1.0_f32, 2.0]))?;
+//! let json = serde_json::to_string(&tensor)?;
+//! assert_eq!(json, r#"{"tensor":{"shape":[2],"data":{"f32":[1.0,2.0]}}}"#);
+//!
+//! let value: DynValue = serde_json::from_str(&json)?;
+//! assert_eq!(value.try_extract_tensor::<f32>()?.1, [1.0, 2.0]);
+//! # 	Ok(())
+//! # }
+//! ```
+//!
+//! Sequences serialize as `{"sequence":[...]}`, and maps as `{"map":{"keys":{...},"values":{...}}}`, where `keys` and
+//! `values` are both 1-dimensional tensors. Tensor element types are named as in [`TensorElementType`]'s `Display`
+//! implementation. `f16`/`bf16` data (with the `half` feature) is serialized as `f32`s, complex numbers (with the
+//! `num-complex` feature) as `[re, im]` pairs, and 8-bit float & 4-bit integer data as raw bytes.
+//!
+//! Deserialized values are always allocated on the CPU with the default [`Allocator`].
+
+use alloc::{borrow::Cow, format, string::String, vec::Vec};
+use core::fmt::Debug;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer, de, ser};
+
+use super::{
+	DowncastableTarget, DynMap, DynTensor, DynValue, DynValueTypeMarker, Sequence, Tensor, TensorValueTypeMarker, Value, ValueRef, ValueRefMut, ValueType,
+	ValueTypeMarker
+};
+use crate::{
+	error::{Error, ErrorCode, Result},
+	memory::Allocator,
+	tensor::{F8E4M3FN, F8E4M3FNUZ, F8E5M2, F8E5M2FNUZ, Int4x2, PrimitiveTensorElementType, TensorElementType, Uint4x2}
+};
+
+/// The serialized form of a value. Must be kept in sync with [`DeserializedValue`].
+#[derive(Serialize)]
+#[serde(rename = "Value", rename_all = "snake_case")]
+enum SerializedValue<'v> {
+	Tensor(TensorRepr<'v>),
+	Sequence(Vec<ValueRef<'v, DynValueTypeMarker>>),
+	Map { keys: TensorRepr<'v>, values: TensorRepr<'v> }
+}
+
+#[derive(Deserialize)]
+#[serde(rename = "Value", rename_all = "snake_case")]
+enum DeserializedValue {
+	Tensor(TensorRepr<'static>),
+	Sequence(Vec<DeserializedValue>),
+	Map { keys: TensorRepr<'static>, values: TensorRepr<'static> }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename = "Tensor")]
+struct TensorRepr<'v> {
+	shape: Cow<'v, [i64]>,
+	data: TensorData<'v>
+}
+
+#[derive(Serialize, Deserialize)]
+enum TensorData<'v> {
+	#[serde(rename = "f32")]
+	Float32(Cow<'v, [f32]>),
+	#[serde(rename = "u8")]
+	Uint8(Cow<'v, [u8]>),
+	#[serde(rename = "i8")]
+	Int8(Cow<'v, [i8]>),
+	#[serde(rename = "u16")]
+	Uint16(Cow<'v, [u16]>),
+	#[serde(rename = "i16")]
+	Int16(Cow<'v, [i16]>),
+	#[serde(rename = "i32")]
+	Int32(Cow<'v, [i32]>),
+	#[serde(rename = "i64")]
+	Int64(Cow<'v, [i64]>),
+	String(Vec<String>),
+	#[serde(rename = "bool")]
+	Bool(Cow<'v, [bool]>),
+	#[cfg(feature = "half")]
+	#[serde(rename = "f16")]
+	Float16(Vec<f32>),
+	#[serde(rename = "f64")]
+	Float64(Cow<'v, [f64]>),
+	#[serde(rename = "u32")]
+	Uint32(Cow<'v, [u32]>),
+	#[serde(rename = "u64")]
+	Uint64(Cow<'v, [u64]>),
+	#[cfg(feature = "half")]
+	#[serde(rename = "bf16")]
+	Bfloat16(Vec<f32>),
+	#[cfg(feature = "num-complex")]
+	#[serde(rename = "c64")]
+	Complex64(Vec<[f32; 2]>),
+	#[cfg(feature = "num-complex")]
+	#[serde(rename = "c128")]
+	Complex128(Vec<[f64; 2]>),
+	#[serde(rename = "f8_e4m3fn")]
+	Float8E4M3FN(Vec<u8>),
+	#[serde(rename = "f8_e4m3fnuz")]
+	Float8E4M3FNUZ(Vec<u8>),
+	#[serde(rename = "f8_e5m2")]
+	Float8E5M2(Vec<u8>),
+	#[serde(rename = "f8_e5m2fnuz")]
+	Float8E5M2FNUZ(Vec<u8>),
+	#[serde(rename = "u4")]
+	Uint4(Vec<u8>),
+	#[serde(rename = "i4")]
+	Int4(Vec<u8>)
+}
+
+impl<'v> TensorRepr<'v> {
+	fn from_tensor<Type: TensorValueTypeMarker + ?Sized>(tensor: &'v Value<Type>) -> Result<Self> {
+		macro_rules! borrowed {
+			($t:ty) => {
+				Cow::Borrowed(tensor.try_extract_tensor::<$t>()?.1)
+			};
+		}
+		macro_rules! bytes {
+			($t:ty) => {
+				tensor.try_extract_tensor::<$t>()?.1.iter().map(|x| x.0).collect()
+			};
+		}
+
+		let ValueType::Tensor { ty, shape, .. } = tensor.dtype() else {
+			return Err(Error::new_with_code(ErrorCode::InvalidArgument, format!("Cannot serialize {} as a tensor", tensor.dtype())));
+		};
+		let memory_info = tensor.memory_info();
+		if !memory_info.is_cpu_accessible() {
+			return Err(Error::new(format!("Cannot serialize tensor on device `{}`, which is not CPU accessible", memory_info.allocation_device().as_str())));
+		}
+
+		let data = match ty {
+			TensorElementType::Float32 => TensorData::Float32(borrowed!(f32)),
+			TensorElementType::Uint8 => TensorData::Uint8(borrowed!(u8)),
+			TensorElementType::Int8 => TensorData::Int8(borrowed!(i8)),
+			TensorElementType::Uint16 => TensorData::Uint16(borrowed!(u16)),
+			TensorElementType::Int16 => TensorData::Int16(borrowed!(i16)),
+			TensorElementType::Int32 => TensorData::Int32(borrowed!(i32)),
+			TensorElementType::Int64 => TensorData::Int64(borrowed!(i64)),
+			TensorElementType::String => TensorData::String(tensor.try_extract_strings()?.1),
+			TensorElementType::Bool => TensorData::Bool(borrowed!(bool)),
+			#[cfg(feature = "half")]
+			TensorElementType::Float16 => TensorData::Float16(tensor.try_extract_tensor::<half::f16>()?.1.iter().map(|x| x.to_f32()).collect()),
+			TensorElementType::Float64 => TensorData::Float64(borrowed!(f64)),
+			TensorElementType::Uint32 => TensorData::Uint32(borrowed!(u32)),
+			TensorElementType::Uint64 => TensorData::Uint64(borrowed!(u64)),
+			#[cfg(feature = "half")]
+			TensorElementType::Bfloat16 => TensorData::Bfloat16(tensor.try_extract_tensor::<half::bf16>()?.1.iter().map(|x| x.to_f32()).collect()),
+			#[cfg(feature = "num-complex")]
+			TensorElementType::Complex64 => TensorData::Complex64(
+				tensor
+					.try_extract_tensor::<num_complex::Complex32>()?
+					.1
+					.iter()
+					.map(|x| [x.re, x.im])
+					.collect()
+			),
+			#[cfg(feature = "num-complex")]
+			TensorElementType::Complex128 => TensorData::Complex128(
+				tensor
+					.try_extract_tensor::<num_complex::Complex64>()?
+					.1
+					.iter()
+					.map(|x| [x.re, x.im])
+					.collect()
+			),
+			TensorElementType::Float8E4M3FN => TensorData::Float8E4M3FN(bytes!(F8E4M3FN)),
+			TensorElementType::Float8E4M3FNUZ => TensorData::Float8E4M3FNUZ(bytes!(F8E4M3FNUZ)),
+			TensorElementType::Float8E5M2 => TensorData::Float8E5M2(bytes!(F8E5M2)),
+			TensorElementType::Float8E5M2FNUZ => TensorData::Float8E5M2FNUZ(bytes!(F8E5M2FNUZ)),
+			TensorElementType::Uint4 => TensorData::Uint4(bytes!(Uint4x2)),
+			TensorElementType::Int4 => TensorData::Int4(bytes!(Int4x2)),
+			ty => {
+				return Err(Error::new_with_code(ErrorCode::NotImplemented, format!("Serializing tensors of type {ty} is not supported")));
+			}
+		};
+		Ok(Self { shape: Cow::Borrowed(shape), data })
+	}
+
+	fn into_tensor(self) -> Result<DynTensor> {
+		let shape = self.shape.into_owned();
+		match self.data {
+			TensorData::Float32(data) => allocate(shape, &data),
+			TensorData::Uint8(data) => allocate(shape, &data),
+			TensorData::Int8(data) => allocate(shape, &data),
+			TensorData::Uint16(data) => allocate(shape, &data),
+			TensorData::Int16(data) => allocate(shape, &data),
+			TensorData::Int32(data) => allocate(shape, &data),
+			TensorData::Int64(data) => allocate(shape, &data),
+			TensorData::String(data) => Tensor::from_string_array((shape, &*data)).map(Tensor::upcast),
+			TensorData::Bool(data) => allocate(shape, &data),
+			#[cfg(feature = "half")]
+			TensorData::Float16(data) => allocate(shape, &data.into_iter().map(half::f16::from_f32).collect::<Vec<_>>()),
+			TensorData::Float64(data) => allocate(shape, &data),
+			TensorData::Uint32(data) => allocate(shape, &data),
+			TensorData::Uint64(data) => allocate(shape, &data),
+			#[cfg(feature = "half")]
+			TensorData::Bfloat16(data) => allocate(shape, &data.into_iter().map(half::bf16::from_f32).collect::<Vec<_>>()),
+			#[cfg(feature = "num-complex")]
+			TensorData::Complex64(data) => allocate(shape, &data.into_iter().map(|[re, im]| num_complex::Complex32::new(re, im)).collect::<Vec<_>>()),
+			#[cfg(feature = "num-complex")]
+			TensorData::Complex128(data) => allocate(shape, &data.into_iter().map(|[re, im]| num_complex::Complex64::new(re, im)).collect::<Vec<_>>()),
+			TensorData::Float8E4M3FN(data) => allocate(shape, &data.into_iter().map(F8E4M3FN).collect::<Vec<_>>()),
+			TensorData::Float8E4M3FNUZ(data) => allocate(shape, &data.into_iter().map(F8E4M3FNUZ).collect::<Vec<_>>()),
+			TensorData::Float8E5M2(data) => allocate(shape, &data.into_iter().map(F8E5M2).collect::<Vec<_>>()),
+			TensorData::Float8E5M2FNUZ(data) => allocate(shape, &data.into_iter().map(F8E5M2FNUZ).collect::<Vec<_>>()),
+			TensorData::Uint4(data) => allocate(shape, &data.into_iter().map(Uint4x2).collect::<Vec<_>>()),
+			TensorData::Int4(data) => allocate(shape, &data.into_iter().map(Int4x2).collect::<Vec<_>>())
+		}
+	}
+}
+
+/// Allocates a tensor with the default allocator and copies `data` into it.
+fn allocate<T: PrimitiveTensorElementType + Debug + Clone>(shape: Vec<i64>, data: &[T]) -> Result<DynTensor> {
+	let mut tensor = Tensor::<T>::new(&Allocator::default(), shape)?;
+	let (shape, buffer) = tensor.extract_tensor_mut();
+	if buffer.len() != data.len() {
+		return Err(Error::new_with_code(
+			ErrorCode::InvalidArgument,
+			format!("Cannot create a tensor of shape {shape} from {} elements; expected {}", data.len(), buffer.len())
+		));
+	}
+	buffer.clone_from_slice(data);
+	Ok(tensor.upcast())
+}
+
+impl DeserializedValue {
+	fn into_value(self) -> Result<DynValue> {
+		match self {
+			Self::Tensor(tensor) => tensor.into_tensor().map(Value::into_dyn),
+			Self::Sequence(values) => {
+				if values.is_empty() {
+					return Err(Error::new_with_code(ErrorCode::InvalidArgument, "Cannot create an empty sequence"));
+				}
+				let values = values.into_iter().map(DeserializedValue::into_value).collect::<Result<Vec<_>>>()?;
+				Sequence::new(values).map(Value::into_dyn)
+			}
+			Self::Map { keys, values } => DynMap::new_dyn_kv(keys.into_tensor()?, values.into_tensor()?).map(Value::into_dyn)
+		}
+	}
+}
+
+#[cfg_attr(docsrs, doc(cfg(feature = "serde")))]
+impl<Type: ValueTypeMarker + ?Sized> Serialize for Value<Type> {
+	fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		let value = self.view().into_dyn();
+		let map_tensors;
+		let repr = match value.dtype() {
+			ValueType::Tensor { .. } => SerializedValue::Tensor(TensorRepr::from_tensor(&*value).map_err(ser::Error::custom)?),
+			ValueType::Sequence(_) => SerializedValue::Sequence(value.try_extract_sequence(&Allocator::default()).map_err(ser::Error::custom)?),
+			ValueType::Map { .. } => {
+				map_tensors = value.key_value_tensors().map_err(ser::Error::custom)?;
+				SerializedValue::Map {
+					keys: TensorRepr::from_tensor(&map_tensors.0).map_err(ser::Error::custom)?,
+					values: TensorRepr::from_tensor(&map_tensors.1).map_err(ser::Error::custom)?
+				}
+			}
+			ty => return Err(ser::Error::custom(format!("Cannot serialize value of type {ty}")))
+		};
+		repr.serialize(serializer)
+	}
+}
+
+#[cfg_attr(docsrs, doc(cfg(feature = "serde")))]
+impl<Type: ValueTypeMarker + ?Sized> Serialize for ValueRef<'_, Type> {
+	fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		(**self).serialize(serializer)
+	}
+}
+
+#[cfg_attr(docsrs, doc(cfg(feature = "serde")))]
+impl<Type: ValueTypeMarker + ?Sized> Serialize for ValueRefMut<'_, Type> {
+	fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		(**self).serialize(serializer)
+	}
+}
+
+#[cfg_attr(docsrs, doc(cfg(feature = "serde")))]
+impl<'de, Type: ValueTypeMarker + DowncastableTarget + ?Sized> Deserialize<'de> for Value<Type> {
+	fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		let value = DeserializedValue::deserialize(deserializer)?.into_value().map_err(de::Error::custom)?;
+		value.downcast().map_err(de::Error::custom)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::collections::HashMap;
+
+	use crate::value::{DynValue, Map, Sequence, Tensor};
+
+	#[test]
+	fn test_tensor_roundtrip() -> Result<(), Box<dyn std::error::Error>> {
+		let tensor = Tensor::from_array(([2usize, 2], vec![1_i64, 2, 3, 4]))?;
+		let json = serde_json::to_string(&tensor)?;
+		assert_eq!(json, r#"{"tensor":{"shape":[2,2],"data":{"i64":[1,2,3,4]}}}"#);
+		let tensor: Tensor<i64> = serde_json::from_str(&json)?;
+		assert_eq!(**tensor.shape(), [2, 2]);
+		assert_eq!(tensor.extract_tensor().1, [1, 2, 3, 4]);
+
+		let tensor = Tensor::from_string_array(([2usize], &["hello", "world"][..]))?;
+		let tensor: Tensor<String> = serde_json::from_str(&serde_json::to_string(&tensor)?)?;
+		assert_eq!(tensor.try_extract_strings()?.1, ["hello", "world"]);
+
+		assert!(serde_json::from_str::<Tensor<f32>>(r#"{"tensor":{"shape":[3],"data":{"f32":[1.0]}}}"#).is_err());
+		assert!(serde_json::from_str::<Tensor<f32>>(r#"{"tensor":{"shape":[1],"data":{"i32":[1]}}}"#).is_err());
+		Ok(())
+	}
+
+	#[test]
+	fn test_sequence_map_roundtrip() -> Result<(), Box<dyn std::error::Error>> {
+		let sequence = Sequence::new([Tensor::from_array(([1usize], vec![1.0_f32]))?, Tensor::from_array(([2usize], vec![2.0_f32, 3.0]))?])?;
+		let value: DynValue = serde_json::from_str(&serde_json::to_string(&sequence)?)?;
+		let tensors = value.try_extract_sequence::<crate::value::TensorValueType<f32>>(&crate::memory::Allocator::default())?;
+		assert_eq!(tensors[1].extract_tensor().1, [2.0, 3.0]);
+
+		let map = Map::<i64, f32>::new([(1, 0.5), (2, 1.5)])?;
+		let map: Map<i64, f32> = serde_json::from_str(&serde_json::to_string(&map)?)?;
+		assert_eq!(map.extract_map(), HashMap::from([(1, 0.5), (2, 1.5)]));
+		Ok(())
+	}
+}
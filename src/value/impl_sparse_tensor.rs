@@ -0,0 +1,698 @@
+use alloc::{format, sync::Arc, vec};
+use core::{
+	ffi::c_void,
+	fmt::{self, Debug},
+	marker::PhantomData,
+	ptr::{self, NonNull},
+	slice
+};
+
+use super::{DowncastableTarget, Value, ValueInner, ValueRef, ValueRefMut, ValueType, ValueTypeMarker, r#type::extract_data_type_from_tensor_info};
+use crate::{
+	AsPointer,
+	error::{Error, ErrorCode, Result},
+	memory::{Allocator, MemoryInfo},
+	ortsys,
+	tensor::{IntoTensorElementType, PrimitiveTensorElementType, Shape, SymbolicDimensions, TensorElementType},
+	value::Tensor
+};
+
+pub trait SparseTensorValueTypeMarker: ValueTypeMarker {
+	private_trait!();
+}
+
+#[derive(Debug)]
+pub struct DynSparseTensorValueType;
+impl ValueTypeMarker for DynSparseTensorValueType {
+	fn fmt(f: &mut fmt::Formatter) -> fmt::Result {
+		f.write_str("DynSparseTensor")
+	}
+
+	private_impl!();
+}
+impl SparseTensorValueTypeMarker for DynSparseTensorValueType {
+	private_impl!();
+}
+
+impl DowncastableTarget for DynSparseTensorValueType {
+	fn can_downcast(dtype: &ValueType) -> bool {
+		matches!(dtype, ValueType::SparseTensor { .. })
+	}
+
+	private_impl!();
+}
+
+#[derive(Debug)]
+pub struct SparseTensorValueType<T: IntoTensorElementType + Debug>(PhantomData<T>);
+impl<T: IntoTensorElementType + Debug> ValueTypeMarker for SparseTensorValueType<T> {
+	fn fmt(f: &mut fmt::Formatter) -> fmt::Result {
+		f.write_str("SparseTensor<")?;
+		<TensorElementType as fmt::Display>::fmt(&T::into_tensor_element_type(), f)?;
+		f.write_str(">")
+	}
+
+	private_impl!();
+}
+impl<T: IntoTensorElementType + Debug> SparseTensorValueTypeMarker for SparseTensorValueType<T> {
+	private_impl!();
+}
+
+impl<T: IntoTensorElementType + Debug> DowncastableTarget for SparseTensorValueType<T> {
+	fn can_downcast(dtype: &ValueType) -> bool {
+		match dtype {
+			ValueType::SparseTensor { ty, .. } => *ty == T::into_tensor_element_type(),
+			_ => false
+		}
+	}
+
+	private_impl!();
+}
+
+/// A sparse tensor [`Value`] whose data type is unknown.
+pub type DynSparseTensor = Value<DynSparseTensorValueType>;
+/// A strongly-typed sparse tensor [`Value`].
+pub type SparseTensor<T> = Value<SparseTensorValueType<T>>;
+
+/// A reference to a sparse tensor [`Value`] whose data type is unknown.
+pub type DynSparseTensorRef<'v> = ValueRef<'v, DynSparseTensorValueType>;
+/// A mutable reference to a sparse tensor [`Value`] whose data type is unknown.
+pub type DynSparseTensorRefMut<'v> = ValueRefMut<'v, DynSparseTensorValueType>;
+/// A reference to a strongly-typed sparse tensor [`Value`].
+pub type SparseTensorRef<'v, T> = ValueRef<'v, SparseTensorValueType<T>>;
+/// A mutable reference to a strongly-typed sparse tensor [`Value`].
+pub type SparseTensorRefMut<'v, T> = ValueRefMut<'v, SparseTensorValueType<T>>;
+
+/// The format in which the indices of a [`SparseTensor`] are stored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SparseFormat {
+	/// Coordinate format; see [`CooParts`].
+	Coo,
+	/// Compressed sparse row format; see [`CsrParts`].
+	Csr,
+	/// Block-sparse format; see [`BlockSparseParts`].
+	BlockSparse
+}
+
+impl SparseFormat {
+	fn from_sys(format: ort_sys::OrtSparseFormat) -> Option<Self> {
+		match format {
+			ort_sys::OrtSparseFormat::ORT_SPARSE_COO => Some(Self::Coo),
+			ort_sys::OrtSparseFormat::ORT_SPARSE_CSRC => Some(Self::Csr),
+			ort_sys::OrtSparseFormat::ORT_SPARSE_BLOCK_SPARSE => Some(Self::BlockSparse),
+			ort_sys::OrtSparseFormat::ORT_SPARSE_UNDEFINED => None
+		}
+	}
+}
+
+/// The parts of a sparse tensor in coordinate (COO) format.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CooParts<'a, T> {
+	/// The indices of each non-zero value. This can either contain a linear index into the (flattened) dense tensor for
+	/// each value, or the full coordinates of each value, i.e. a `[values.len(), rank]` array in row-major order.
+	pub indices: &'a [i64],
+	/// The non-zero values.
+	pub values: &'a [T]
+}
+
+/// The parts of a 2-dimensional sparse tensor in compressed sparse row (CSR) format.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CsrParts<'a, T> {
+	/// The column index of each value.
+	pub inner_indices: &'a [i64],
+	/// The offsets into `inner_indices`/`values` at which each row starts, plus a final element equal to the number of
+	/// values. This has a length of `rows + 1`.
+	pub outer_indices: &'a [i64],
+	/// The non-zero values, ordered by row.
+	pub values: &'a [T]
+}
+
+/// The parts of a 2-dimensional sparse tensor in block-sparse format.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BlockSparseParts<'a, T> {
+	/// The shape of `indices`, which should be `[2, num_blocks]`.
+	pub indices_shape: Shape,
+	/// The block row indices of each block, followed by the block column indices of each block.
+	pub indices: &'a [i32],
+	/// The shape of `values`, which should be `[num_blocks, block_rows, block_columns]`.
+	pub values_shape: Shape,
+	/// The values of each block, in row-major order.
+	pub values: &'a [T]
+}
+
+impl<T: PrimitiveTensorElementType + Debug> SparseTensor<T> {
+	/// Creates a sparse tensor with the given dense shape from [`CooParts`]. The indices & values are copied.
+	///
+	/// ```
+	/// # use ort::value::{CooParts, SparseTensor};
+	/// # fn main() -> ort::Result<()> {
+	/// // [[0, 1, 0],
+	/// //  [0, 0, 2]]
+	/// let tensor = SparseTensor::<f32>::from_coo(
+	/// 	[2_usize, 3],
+	/// 	CooParts {
+	/// 		indices: &[1, 5],
+	/// 		values: &[1.0, 2.0]
+	/// 	}
+	/// )?;
+	/// // indices can also be given as coordinates
+	/// let tensor = SparseTensor::<f32>::from_coo(
+	/// 	[2_usize, 3],
+	/// 	CooParts {
+	/// 		indices: &[0, 1, 1, 2],
+	/// 		values: &[1.0, 2.0]
+	/// 	}
+	/// )?;
+	///
+	/// let coo = tensor.try_extract_coo::<f32>()?;
+	/// assert_eq!(coo.values, [1.0, 2.0]);
+	/// # 	Ok(())
+	/// # }
+	/// ```
+	pub fn from_coo(dense_shape: impl Into<Shape>, parts: CooParts<'_, T>) -> Result<Self> {
+		let value = Self::new_empty(dense_shape.into())?;
+		let values_shape = [parts.values.len() as i64];
+		ortsys![
+			unsafe FillSparseTensorCoo(
+				value.ptr().cast_mut(),
+				MemoryInfo::default().ptr(),
+				values_shape.as_ptr(),
+				values_shape.len(),
+				parts.values.as_ptr().cast::<c_void>(),
+				parts.indices.as_ptr(),
+				parts.indices.len()
+			)?
+		];
+		Ok(value)
+	}
+
+	/// Creates a 2-dimensional sparse tensor with the given dense shape from [`CsrParts`]. The indices & values are
+	/// copied.
+	///
+	/// ```
+	/// # use ort::value::{CsrParts, SparseTensor};
+	/// # fn main() -> ort::Result<()> {
+	/// // [[0, 1, 0],
+	/// //  [0, 0, 2]]
+	/// let tensor = SparseTensor::<f32>::from_csr(
+	/// 	[2_usize, 3],
+	/// 	CsrParts {
+	/// 		inner_indices: &[1, 2],
+	/// 		outer_indices: &[0, 1, 2],
+	/// 		values: &[1.0, 2.0]
+	/// 	}
+	/// )?;
+	/// # 	Ok(())
+	/// # }
+	/// ```
+	pub fn from_csr(dense_shape: impl Into<Shape>, parts: CsrParts<'_, T>) -> Result<Self> {
+		let value = Self::new_empty(dense_shape.into())?;
+		let values_shape = [parts.values.len() as i64];
+		ortsys![
+			unsafe FillSparseTensorCsr(
+				value.ptr().cast_mut(),
+				MemoryInfo::default().ptr(),
+				values_shape.as_ptr(),
+				values_shape.len(),
+				parts.values.as_ptr().cast::<c_void>(),
+				parts.inner_indices.as_ptr(),
+				parts.inner_indices.len(),
+				parts.outer_indices.as_ptr(),
+				parts.outer_indices.len()
+			)?
+		];
+		Ok(value)
+	}
+
+	/// Creates a 2-dimensional sparse tensor with the given dense shape from [`BlockSparseParts`]. The indices &
+	/// values are copied.
+	///
+	/// ```
+	/// # use ort::{tensor::Shape, value::{BlockSparseParts, SparseTensor}};
+	/// # fn main() -> ort::Result<()> {
+	/// // [[0, 0, 1, 2],
+	/// //  [0, 0, 3, 4]]
+	/// let tensor = SparseTensor::<f32>::from_block_sparse(
+	/// 	[2_usize, 4],
+	/// 	BlockSparseParts {
+	/// 		indices_shape: Shape::new([2, 1]),
+	/// 		indices: &[0, 1],
+	/// 		values_shape: Shape::new([1, 2, 2]),
+	/// 		values: &[1.0, 2.0, 3.0, 4.0]
+	/// 	}
+	/// )?;
+	/// # 	Ok(())
+	/// # }
+	/// ```
+	pub fn from_block_sparse(dense_shape: impl Into<Shape>, parts: BlockSparseParts<'_, T>) -> Result<Self> {
+		if parts.values_shape.num_elements() != parts.values.len() || parts.indices_shape.num_elements() != parts.indices.len() {
+			return Err(Error::new_with_code(ErrorCode::InvalidArgument, "Block-sparse values/indices do not match their shapes"));
+		}
+
+		let value = Self::new_empty(dense_shape.into())?;
+		ortsys![
+			unsafe FillSparseTensorBlockSparse(
+				value.ptr().cast_mut(),
+				MemoryInfo::default().ptr(),
+				parts.values_shape.as_ptr(),
+				parts.values_shape.len(),
+				parts.values.as_ptr().cast::<c_void>(),
+				parts.indices_shape.as_ptr(),
+				parts.indices_shape.len(),
+				parts.indices.as_ptr()
+			)?
+		];
+		Ok(value)
+	}
+
+	/// Creates a sparse tensor in COO format from the non-zero elements of a dense array.
+	///
+	/// ```
+	/// # use ort::value::SparseTensor;
+	/// # fn main() -> ort::Result<()> {
+	/// let array = ndarray::array![[0.0_f32, 1.0, 0.0], [0.0, 0.0, 2.0]];
+	/// let tensor = SparseTensor::from_dense_array(array.view())?;
+	///
+	/// let coo = tensor.try_extract_coo::<f32>()?;
+	/// assert_eq!(coo.indices, [1, 5]);
+	/// assert_eq!(coo.values, [1.0, 2.0]);
+	/// # 	Ok(())
+	/// # }
+	/// ```
+	#[cfg(feature = "ndarray")]
+	#[cfg_attr(docsrs, doc(cfg(feature = "ndarray")))]
+	pub fn from_dense_array<D: ndarray::Dimension>(array: ndarray::ArrayView<'_, T, D>) -> Result<Self>
+	where
+		T: Clone + Default + PartialEq
+	{
+		let zero = T::default();
+		let (indices, values): (alloc::vec::Vec<i64>, alloc::vec::Vec<T>) = array
+			.iter()
+			.enumerate()
+			.filter(|(_, x)| **x != zero)
+			.map(|(i, x)| (i as i64, x.clone()))
+			.unzip();
+		let dense_shape: Shape = array.shape().iter().map(|&d| d as i64).collect();
+		Self::from_coo(dense_shape, CooParts { indices: &indices, values: &values })
+	}
+
+	fn new_empty(dense_shape: Shape) -> Result<Self> {
+		new_sparse_tensor(T::into_tensor_element_type(), dense_shape)
+	}
+
+	/// Converts from a strongly-typed [`SparseTensor<T>`] to a type-erased [`DynSparseTensor`].
+	#[inline]
+	pub fn upcast(self) -> DynSparseTensor {
+		unsafe { self.transmute_type() }
+	}
+
+	/// Converts from a strongly-typed [`SparseTensor<T>`] to a reference to a type-erased [`DynSparseTensor`].
+	#[inline]
+	pub fn upcast_ref(&self) -> DynSparseTensorRef<'_> {
+		DynSparseTensorRef::new(Value {
+			inner: Arc::clone(&self.inner),
+			_markers: PhantomData
+		})
+	}
+
+	/// Converts from a strongly-typed [`SparseTensor<T>`] to a mutable reference to a type-erased [`DynSparseTensor`].
+	#[inline]
+	pub fn upcast_mut(&mut self) -> DynSparseTensorRefMut<'_> {
+		DynSparseTensorRefMut::new(Value {
+			inner: Arc::clone(&self.inner),
+			_markers: PhantomData
+		})
+	}
+}
+
+#[cfg(feature = "std")]
+impl DynSparseTensor {
+	/// Creates a sparse tensor in COO format with no values, i.e. one whose dense representation is all zeros.
+	pub(crate) fn new_empty_coo(ty: TensorElementType, dense_shape: Shape) -> Result<Self> {
+		let value = new_sparse_tensor(ty, dense_shape)?;
+		let values_shape = [0_i64];
+		ortsys![
+			unsafe FillSparseTensorCoo(
+				value.ptr().cast_mut(),
+				MemoryInfo::default().ptr(),
+				values_shape.as_ptr(),
+				values_shape.len(),
+				NonNull::<u64>::dangling().as_ptr().cast::<c_void>(),
+				NonNull::<i64>::dangling().as_ptr(),
+				0
+			)?
+		];
+		Ok(value)
+	}
+}
+
+fn new_sparse_tensor<Type: SparseTensorValueTypeMarker + ?Sized>(ty: TensorElementType, dense_shape: Shape) -> Result<Value<Type>> {
+	let mut value_ptr: *mut ort_sys::OrtValue = ptr::null_mut();
+	ortsys![
+		unsafe CreateSparseTensorAsOrtValue(Allocator::default().ptr().cast_mut(), dense_shape.as_ptr(), dense_shape.len(), ty.into(), &mut value_ptr)?;
+		nonNull(value_ptr)
+	];
+	let rank = dense_shape.len();
+	Ok(Value {
+		inner: Arc::new(ValueInner {
+			ptr: unsafe { NonNull::new_unchecked(value_ptr) },
+			dtype: ValueType::SparseTensor {
+				ty,
+				shape: dense_shape,
+				dimension_symbols: SymbolicDimensions::empty(rank)
+			},
+			drop: true,
+			memory_info: None,
+			_backing: None
+		}),
+		_markers: PhantomData
+	})
+}
+
+impl<Type: SparseTensorValueTypeMarker + ?Sized> Value<Type> {
+	/// Returns the format this sparse tensor's indices are stored in, or `None` if the sparse tensor has not been
+	/// filled with data.
+	pub fn sparse_format(&self) -> Result<Option<SparseFormat>> {
+		let mut format = ort_sys::OrtSparseFormat::ORT_SPARSE_UNDEFINED;
+		ortsys![unsafe GetSparseTensorFormat(self.ptr(), &mut format)?];
+		Ok(SparseFormat::from_sys(format))
+	}
+
+	/// Returns the shape of the dense tensor this sparse tensor represents.
+	pub fn dense_shape(&self) -> Result<&Shape> {
+		match self.dtype() {
+			ValueType::SparseTensor { shape, .. } => Ok(shape),
+			t => Err(Error::new_with_code(ErrorCode::InvalidArgument, format!("Cannot get the dense shape of {t}")))
+		}
+	}
+
+	/// Attempts to extract the parts of a sparse tensor in COO format.
+	///
+	/// # Errors
+	/// May return an error if:
+	/// - This is a [`DynValue`](crate::value::DynValue), and the value is not actually a sparse tensor.
+	/// - The provided type `T` does not match the tensor's element type.
+	/// - The sparse tensor is not in COO format.
+	pub fn try_extract_coo<T: PrimitiveTensorElementType>(&self) -> Result<CooParts<'_, T>> {
+		let (_, values) = self.sparse_values::<T>(SparseFormat::Coo)?;
+		let (_, indices) = self.sparse_indices::<i64>(ort_sys::OrtSparseIndicesFormat::ORT_SPARSE_COO_INDICES)?;
+		Ok(CooParts { indices, values })
+	}
+
+	/// Attempts to extract the parts of a sparse tensor in CSR format.
+	///
+	/// # Errors
+	/// May return an error if:
+	/// - This is a [`DynValue`](crate::value::DynValue), and the value is not actually a sparse tensor.
+	/// - The provided type `T` does not match the tensor's element type.
+	/// - The sparse tensor is not in CSR format.
+	pub fn try_extract_csr<T: PrimitiveTensorElementType>(&self) -> Result<CsrParts<'_, T>> {
+		let (_, values) = self.sparse_values::<T>(SparseFormat::Csr)?;
+		let (_, inner_indices) = self.sparse_indices::<i64>(ort_sys::OrtSparseIndicesFormat::ORT_SPARSE_CSR_INNER_INDICES)?;
+		let (_, outer_indices) = self.sparse_indices::<i64>(ort_sys::OrtSparseIndicesFormat::ORT_SPARSE_CSR_OUTER_INDICES)?;
+		Ok(CsrParts { inner_indices, outer_indices, values })
+	}
+
+	/// Attempts to extract the parts of a sparse tensor in block-sparse format.
+	///
+	/// # Errors
+	/// May return an error if:
+	/// - This is a [`DynValue`](crate::value::DynValue), and the value is not actually a sparse tensor.
+	/// - The provided type `T` does not match the tensor's element type.
+	/// - The sparse tensor is not in block-sparse format.
+	pub fn try_extract_block_sparse<T: PrimitiveTensorElementType>(&self) -> Result<BlockSparseParts<'_, T>> {
+		let (values_shape, values) = self.sparse_values::<T>(SparseFormat::BlockSparse)?;
+		let (indices_shape, indices) = self.sparse_indices::<i32>(ort_sys::OrtSparseIndicesFormat::ORT_SPARSE_BLOCK_SPARSE_INDICES)?;
+		Ok(BlockSparseParts {
+			indices_shape,
+			indices,
+			values_shape,
+			values
+		})
+	}
+
+	/// Converts this sparse tensor to a dense [`Tensor`], filling unspecified elements with `T::default()`.
+	///
+	/// ```
+	/// # use ort::value::{CsrParts, SparseTensor};
+	/// # fn main() -> ort::Result<()> {
+	/// let tensor = SparseTensor::<i32>::from_csr(
+	/// 	[2_usize, 3],
+	/// 	CsrParts {
+	/// 		inner_indices: &[1, 2],
+	/// 		outer_indices: &[0, 1, 2],
+	/// 		values: &[1, 2]
+	/// 	}
+	/// )?;
+	///
+	/// let dense = tensor.try_to_dense::<i32>()?;
+	/// assert_eq!(dense.extract_tensor().1, [0, 1, 0, 0, 0, 2]);
+	/// # 	Ok(())
+	/// # }
+	/// ```
+	pub fn try_to_dense<T: PrimitiveTensorElementType + Debug + Clone + Default + 'static>(&self) -> Result<Tensor<T>> {
+		let shape = self.dense_shape()?.clone();
+		let mut dense = vec![T::default(); shape.num_elements()];
+		let out_of_bounds = || Error::new_with_code(ErrorCode::InvalidArgument, "Sparse tensor index is out of bounds");
+		// Converts an index to a `usize` less than `len`, or errors if it is out of bounds.
+		let bounded = |index: i64, len: usize| usize::try_from(index).ok().filter(|&i| i < len).ok_or_else(out_of_bounds);
+		let two_dimensional = || match *shape {
+			[rows, columns] => Ok((rows as usize, columns as usize)),
+			_ => Err(Error::new_with_code(ErrorCode::InvalidArgument, format!("Expected sparse tensor to be 2-dimensional, got shape {shape}")))
+		};
+
+		match self.sparse_format()? {
+			None => {}
+			Some(SparseFormat::Coo) => {
+				let CooParts { indices, values } = self.try_extract_coo::<T>()?;
+				let rank = shape.len();
+				let linear = indices.len() == values.len();
+				for (i, value) in values.iter().enumerate() {
+					let index = if linear {
+						bounded(indices[i], dense.len())?
+					} else {
+						let coordinates = indices.get(i * rank..(i + 1) * rank).ok_or_else(out_of_bounds)?;
+						coordinates
+							.iter()
+							.zip(shape.iter())
+							.try_fold(0, |index, (&c, &d)| Ok::<_, Error>(index * d as usize + bounded(c, d as usize)?))?
+					};
+					dense[index] = value.clone();
+				}
+			}
+			Some(SparseFormat::Csr) => {
+				let (rows, columns) = two_dimensional()?;
+				let CsrParts { inner_indices, outer_indices, values } = self.try_extract_csr::<T>()?;
+				if outer_indices.len() > rows + 1 {
+					return Err(out_of_bounds());
+				}
+				for (row, range) in outer_indices.windows(2).enumerate() {
+					let (start, end) = (bounded(range[0], values.len() + 1)?, bounded(range[1], values.len() + 1)?);
+					let row_indices = inner_indices.get(start..end).ok_or_else(out_of_bounds)?;
+					for (&column, value) in row_indices.iter().zip(&values[start..end]) {
+						dense[row * columns + bounded(column, columns)?] = value.clone();
+					}
+				}
+			}
+			Some(SparseFormat::BlockSparse) => {
+				let (rows, columns) = two_dimensional()?;
+				let BlockSparseParts { indices, values_shape, values, .. } = self.try_extract_block_sparse::<T>()?;
+				let [num_blocks, block_rows, block_columns] = *values_shape else {
+					return Err(Error::new_with_code(
+						ErrorCode::InvalidArgument,
+						format!("Expected block-sparse values to be 3-dimensional, got shape {values_shape}")
+					));
+				};
+				let (num_blocks, block_rows, block_columns) = (num_blocks as usize, block_rows as usize, block_columns as usize);
+				// checked even if there are no blocks, since `chunks_exact` panics on a chunk size of 0
+				let block_size = block_rows.checked_mul(block_columns).filter(|&size| size > 0).ok_or_else(|| {
+					Error::new_with_code(ErrorCode::InvalidArgument, format!("Expected block-sparse blocks to be non-empty, got shape {values_shape}"))
+				})?;
+				if indices.len() != 2 * num_blocks {
+					return Err(out_of_bounds());
+				}
+				for (block, block_values) in values.chunks_exact(block_size).enumerate() {
+					let block_row = bounded(indices[block].into(), rows.div_ceil(block_rows))?;
+					let block_column = bounded(indices[num_blocks + block].into(), columns.div_ceil(block_columns))?;
+					for (i, row_values) in block_values.chunks_exact(block_columns).enumerate() {
+						let row = block_row * block_rows + i;
+						let column = block_column * block_columns;
+						if row >= rows || column + block_columns > columns {
+							return Err(out_of_bounds());
+						}
+						dense[row * columns + column..row * columns + column + block_columns].clone_from_slice(row_values);
+					}
+				}
+			}
+		}
+
+		Tensor::from_array((shape, dense))
+	}
+
+	fn sparse_values<T: PrimitiveTensorElementType>(&self, expected_format: SparseFormat) -> Result<(Shape, &[T])> {
+		match self.dtype() {
+			ValueType::SparseTensor { ty, .. } if *ty == T::into_tensor_element_type() => {}
+			ValueType::SparseTensor { ty, .. } => {
+				return Err(Error::new_with_code(
+					ErrorCode::InvalidArgument,
+					format!("Cannot extract SparseTensor<{}> from SparseTensor<{ty}>", T::into_tensor_element_type())
+				));
+			}
+			t => return Err(Error::new_with_code(ErrorCode::InvalidArgument, format!("Cannot extract a sparse tensor from {t}")))
+		}
+		match self.sparse_format()? {
+			Some(format) if format == expected_format => {}
+			format => {
+				return Err(Error::new_with_code(
+					ErrorCode::InvalidArgument,
+					format!("Expected sparse tensor in {expected_format:?} format, but it is in {format:?} format")
+				));
+			}
+		}
+
+		let mut info_ptr: *mut ort_sys::OrtTensorTypeAndShapeInfo = ptr::null_mut();
+		ortsys![unsafe GetSparseTensorValuesTypeAndShape(self.ptr(), &mut info_ptr)?; nonNull(info_ptr)];
+		let shape = info_shape(info_ptr);
+
+		let mut values_ptr: *const c_void = ptr::null();
+		ortsys![unsafe GetSparseTensorValues(self.ptr(), &mut values_ptr)?];
+		let values = if shape.num_elements() == 0 {
+			&[]
+		} else {
+			unsafe { slice::from_raw_parts(values_ptr.cast::<T>(), shape.num_elements()) }
+		};
+		Ok((shape, values))
+	}
+
+	fn sparse_indices<I>(&self, format: ort_sys::OrtSparseIndicesFormat) -> Result<(Shape, &[I])> {
+		let mut info_ptr: *mut ort_sys::OrtTensorTypeAndShapeInfo = ptr::null_mut();
+		ortsys![unsafe GetSparseTensorIndicesTypeShape(self.ptr(), format, &mut info_ptr)?; nonNull(info_ptr)];
+		let shape = info_shape(info_ptr);
+
+		let mut len = 0;
+		let mut indices_ptr: *const c_void = ptr::null();
+		ortsys![unsafe GetSparseTensorIndices(self.ptr(), format, &mut len, &mut indices_ptr)?];
+		let indices = if len == 0 { &[] } else { unsafe { slice::from_raw_parts(indices_ptr.cast::<I>(), len) } };
+		Ok((shape, indices))
+	}
+}
+
+/// Reads the shape from, and releases, an `OrtTensorTypeAndShapeInfo`.
+fn info_shape(info_ptr: *mut ort_sys::OrtTensorTypeAndShapeInfo) -> Shape {
+	let ty = unsafe { extract_data_type_from_tensor_info(info_ptr) };
+	ortsys![unsafe ReleaseTensorTypeAndShapeInfo(info_ptr)];
+	match ty {
+		ValueType::Tensor { shape, .. } => shape,
+		_ => unreachable!()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{BlockSparseParts, CooParts, CsrParts, SparseFormat, SparseTensor};
+	use crate::{
+		tensor::Shape,
+		value::{DynSparseTensorValueType, SparseTensorValueType, ValueType}
+	};
+
+	#[test]
+	fn test_coo_roundtrip() -> crate::Result<()> {
+		let tensor = SparseTensor::<f32>::from_coo(
+			[3_usize, 3],
+			CooParts {
+				indices: &[0, 0, 1, 2],
+				values: &[1.0, 2.0]
+			}
+		)?;
+		assert!(matches!(tensor.dtype(), ValueType::SparseTensor { .. }));
+		assert_eq!(tensor.sparse_format()?, Some(SparseFormat::Coo));
+
+		let coo = tensor.try_extract_coo::<f32>()?;
+		assert_eq!(coo.indices, [0, 0, 1, 2]);
+		assert_eq!(coo.values, [1.0, 2.0]);
+		assert!(tensor.try_extract_coo::<i32>().is_err());
+		assert!(tensor.try_extract_csr::<f32>().is_err());
+
+		let dense = tensor.try_to_dense::<f32>()?;
+		assert_eq!(dense.extract_tensor().1, [1.0, 0.0, 0.0, 0.0, 0.0, 2.0, 0.0, 0.0, 0.0]);
+		Ok(())
+	}
+
+	#[test]
+	fn test_block_sparse_to_dense() -> crate::Result<()> {
+		let tensor = SparseTensor::<i32>::from_block_sparse(
+			[2_usize, 4],
+			BlockSparseParts {
+				indices_shape: Shape::new([2, 1]),
+				indices: &[0, 1],
+				values_shape: Shape::new([1, 2, 2]),
+				values: &[1, 2, 3, 4]
+			}
+		)?;
+		let dense = tensor.try_to_dense::<i32>()?;
+		assert_eq!(dense.extract_tensor().1, [0, 0, 1, 2, 0, 0, 3, 4]);
+		Ok(())
+	}
+
+	#[test]
+	fn test_to_dense_out_of_bounds() {
+		let negative_column = SparseTensor::<i32>::from_csr(
+			[2_usize, 2],
+			CsrParts {
+				inner_indices: &[-1],
+				outer_indices: &[0, 0, 1],
+				values: &[1]
+			}
+		);
+		assert!(negative_column.and_then(|t| t.try_to_dense::<i32>()).is_err());
+
+		let column_past_end = SparseTensor::<i32>::from_csr(
+			[2_usize, 2],
+			CsrParts {
+				inner_indices: &[2],
+				outer_indices: &[0, 1, 1],
+				values: &[1]
+			}
+		);
+		assert!(column_past_end.and_then(|t| t.try_to_dense::<i32>()).is_err());
+
+		let empty_blocks = SparseTensor::<i32>::from_block_sparse(
+			[2_usize, 2],
+			BlockSparseParts {
+				indices_shape: Shape::new([2, 1]),
+				indices: &[0, 0],
+				values_shape: Shape::new([1, 0, 2]),
+				values: &[]
+			}
+		);
+		assert!(empty_blocks.and_then(|t| t.try_to_dense::<i32>()).is_err());
+
+		let no_empty_blocks = SparseTensor::<i32>::from_block_sparse(
+			[2_usize, 2],
+			BlockSparseParts {
+				indices_shape: Shape::new([2, 0]),
+				indices: &[],
+				values_shape: Shape::new([0, 0, 0]),
+				values: &[]
+			}
+		);
+		assert!(no_empty_blocks.and_then(|t| t.try_to_dense::<i32>()).is_err());
+	}
+
+	#[test]
+	fn test_downcast() -> crate::Result<()> {
+		let tensor = SparseTensor::<i64>::from_csr(
+			[2_usize, 2],
+			CsrParts {
+				inner_indices: &[1],
+				outer_indices: &[0, 1, 1],
+				values: &[7]
+			}
+		)?
+		.into_dyn();
+		assert!(tensor.downcast_ref::<SparseTensorValueType<f32>>().is_err());
+		let tensor = tensor.downcast::<DynSparseTensorValueType>()?;
+		assert_eq!(**tensor.dense_shape()?, [2, 2]);
+		assert_eq!(tensor.try_extract_csr::<i64>()?.values, [7]);
+		Ok(())
+	}
+}
@@ -0,0 +1,222 @@
+use alloc::format;
+use core::{cmp::Ordering, fmt::Debug, slice};
+
+use super::{DynTensor, Tensor, TensorValueTypeMarker, display::Element};
+use crate::{
+	error::{Error, ErrorCode, Result},
+	memory::Allocator,
+	tensor::{F8E4M3FN, F8E4M3FNUZ, F8E5M2, F8E5M2FNUZ, IntoTensorElementType, PrimitiveTensorElementType, TensorElementType, f32_to_bf16, f32_to_f16},
+	value::{Value, ValueType}
+};
+
+impl DynTensor {
+	/// Converts this tensor's elements to another element type, returning a new tensor allocated on the CPU.
+	///
+	/// Any numeric or boolean element type can be cast to any other, following these rules:
+	/// - Floating-point values cast to an integer type are rounded towards zero, and saturate to the integer type's
+	///   bounds. NaN casts to `0`.
+	/// - Integer values cast to a narrower integer type saturate to its bounds, e.g. `300_i32` casts to `255_u8` and
+	///   `-1_i64` casts to `0_u32`.
+	/// - Values cast to a floating-point type are rounded to the nearest representable value (ties to even). Values out
+	///   of range become infinite, except for 8-bit float types, which saturate to their largest finite value like
+	///   [`F8E4M3FN::from_f32`].
+	/// - Any non-zero value (including NaN) casts to `true`, and `true` casts to `1`.
+	/// - Complex values cast to a real type discard their imaginary part; real values cast to a complex type have an
+	///   imaginary part of `0`.
+	///
+	/// ```
+	/// # use ort::{tensor::TensorElementType, value::Tensor};
+	/// # fn main() -> ort::Result<()> {
+	/// let tensor = Tensor::from_array(([4usize], vec![-1.5_f32, 0.7, 300.0, f32::NAN]))?.upcast();
+	/// let cast = tensor.cast(TensorElementType::Uint8)?;
+	/// assert_eq!(cast.try_extract_tensor::<u8>()?.1, [0, 0, 255, 0]);
+	/// # 	Ok(())
+	/// # }
+	/// ```
+	///
+	/// # Errors
+	/// Returns an error if either element type is [`TensorElementType::String`], or if this tensor's data is not
+	/// accessible from the CPU.
+	pub fn cast(&self, ty: TensorElementType) -> Result<DynTensor> {
+		cast_tensor(self, ty)
+	}
+}
+
+impl<T: IntoTensorElementType + Debug> Tensor<T> {
+	/// Converts this tensor's elements to type `U`, returning a new tensor allocated on the CPU. See
+	/// [`DynTensor::cast`] for the rules used to convert elements.
+	///
+	/// ```
+	/// # use ort::value::Tensor;
+	/// # fn main() -> ort::Result<()> {
+	/// let token_ids = Tensor::from_array(([3usize], vec![101_i64, 7592, 102]))?;
+	/// let cast = token_ids.cast::<i32>()?;
+	/// assert_eq!(cast.extract_tensor().1, [101, 7592, 102]);
+	/// # 	Ok(())
+	/// # }
+	/// ```
+	pub fn cast<U: PrimitiveTensorElementType + Debug>(&self) -> Result<Tensor<U>> {
+		cast_tensor(self, U::into_tensor_element_type()).map(|tensor| unsafe { tensor.transmute_type() })
+	}
+}
+
+/// Casts a tensor to element type `ty`; see [`DynTensor::cast`].
+pub(crate) fn cast_tensor<Type: TensorValueTypeMarker + ?Sized>(value: &Value<Type>, ty: TensorElementType) -> Result<DynTensor> {
+	let ValueType::Tensor { ty: source_ty, shape, .. } = value.dtype() else {
+		return Err(Error::new_with_code(ErrorCode::InvalidArgument, format!("Cannot cast value of type {}", value.dtype())));
+	};
+	if matches!(ty, TensorElementType::String | TensorElementType::Undefined) || matches!(source_ty, TensorElementType::String | TensorElementType::Undefined) {
+		return Err(Error::new_with_code(ErrorCode::InvalidArgument, format!("Cannot cast tensor of type {source_ty} to {ty}")));
+	}
+
+	let source = value.raw_bytes()?;
+	let len = shape.num_elements();
+	let mut tensor = DynTensor::new(&Allocator::default(), ty, shape.clone())?;
+	if len > 0 {
+		let target = unsafe { slice::from_raw_parts_mut(tensor.data_ptr_mut()?.cast::<u8>(), ty.byte_size(len)) };
+		for i in 0..len {
+			encode(ty, Element::decode(*source_ty, source, i), target, i);
+		}
+	}
+	Ok(tensor)
+}
+
+/// Writes `element`, converted to type `ty`, as the `index`th element of `data`.
+fn encode(ty: TensorElementType, element: Element, data: &mut [u8], index: usize) {
+	fn write<const N: usize>(data: &mut [u8], index: usize, bytes: [u8; N]) {
+		data[index * N..][..N].copy_from_slice(&bytes);
+	}
+
+	let int = |min: i128, max: i128| -> i128 {
+		let float = match element {
+			Element::Bool(b) => return i128::from(b),
+			Element::Int(i) => return i128::from(i).clamp(min, max),
+			Element::Uint(u) => return i128::from(u).clamp(min, max),
+			Element::F32(x) | Element::C32(x, _) => f64::from(x),
+			Element::F64(x) | Element::C64(x, _) => x
+		};
+		if float.is_nan() {
+			0
+		} else if float <= min as f64 {
+			min
+		} else if float >= max as f64 {
+			max
+		} else {
+			float as i128
+		}
+	};
+	let real_f32 = || match element {
+		Element::Bool(b) => f32::from(u8::from(b)),
+		Element::Int(i) => i as f32,
+		Element::Uint(u) => u as f32,
+		Element::F32(x) | Element::C32(x, _) => x,
+		Element::F64(x) | Element::C64(x, _) => x as f32
+	};
+	let real_f64 = || match element {
+		Element::Bool(b) => f64::from(u8::from(b)),
+		Element::Int(i) => i as f64,
+		Element::Uint(u) => u as f64,
+		Element::F32(x) | Element::C32(x, _) => f64::from(x),
+		Element::F64(x) | Element::C64(x, _) => x
+	};
+	// Like `real_f32`, but inexact values are rounded to odd rather than to nearest, i.e. to whichever neighbour has an odd
+	// significand. Narrowing that to a 16- or 8-bit float then rounds exactly as if the real part were converted directly;
+	// rounding to nearest in both steps could instead round twice, e.g. an `f64` just above the halfway point between two
+	// `f16`s would first be rounded onto the halfway point, and then down to the even `f16`.
+	let real_f32_odd = || {
+		let (rounded, exact) = match element {
+			Element::Int(i) => {
+				let rounded = i as f32;
+				(rounded, i128::from(i).cmp(&(rounded as i128)))
+			}
+			Element::Uint(u) => {
+				let rounded = u as f32;
+				(rounded, i128::from(u).cmp(&(rounded as i128)))
+			}
+			Element::F64(x) | Element::C64(x, _) if !x.is_nan() => {
+				let rounded = x as f32;
+				(rounded, x.total_cmp(&f64::from(rounded)))
+			}
+			_ => return real_f32()
+		};
+		if exact == Ordering::Equal {
+			return rounded;
+		}
+		// if the value was rounded away from zero, step back to the neighbour towards zero
+		let away_from_zero = if rounded.is_sign_negative() { exact.is_gt() } else { exact.is_lt() };
+		f32::from_bits((rounded.to_bits() - u32::from(away_from_zero)) | 1)
+	};
+	let imaginary = match element {
+		Element::C32(_, im) => f64::from(im),
+		Element::C64(_, im) => im,
+		_ => 0.0
+	};
+
+	match ty {
+		TensorElementType::Bool => data[index] = u8::from(real_f64() != 0.0 || imaginary != 0.0),
+		TensorElementType::Int8 => data[index] = int(i8::MIN.into(), i8::MAX.into()) as i8 as u8,
+		TensorElementType::Uint8 => data[index] = int(0, u8::MAX.into()) as u8,
+		TensorElementType::Int16 => write(data, index, (int(i16::MIN.into(), i16::MAX.into()) as i16).to_le_bytes()),
+		TensorElementType::Uint16 => write(data, index, (int(0, u16::MAX.into()) as u16).to_le_bytes()),
+		TensorElementType::Int32 => write(data, index, (int(i32::MIN.into(), i32::MAX.into()) as i32).to_le_bytes()),
+		TensorElementType::Uint32 => write(data, index, (int(0, u32::MAX.into()) as u32).to_le_bytes()),
+		TensorElementType::Int64 => write(data, index, (int(i64::MIN.into(), i64::MAX.into()) as i64).to_le_bytes()),
+		TensorElementType::Uint64 => write(data, index, (int(0, u64::MAX.into()) as u64).to_le_bytes()),
+		TensorElementType::Int4 | TensorElementType::Uint4 => {
+			let nibble = if ty == TensorElementType::Int4 { int(-8, 7) as u8 & 0x0F } else { int(0, 15) as u8 };
+			let byte = &mut data[index / 2];
+			*byte = if index % 2 == 0 { (*byte & 0xF0) | nibble } else { (*byte & 0x0F) | (nibble << 4) };
+		}
+		TensorElementType::Float16 => write(data, index, f32_to_f16(real_f32_odd()).to_le_bytes()),
+		TensorElementType::Bfloat16 => write(data, index, f32_to_bf16(real_f32_odd()).to_le_bytes()),
+		TensorElementType::Float8E4M3FN => data[index] = F8E4M3FN::from_f32(real_f32_odd()).to_bits(),
+		TensorElementType::Float8E4M3FNUZ => data[index] = F8E4M3FNUZ::from_f32(real_f32_odd()).to_bits(),
+		TensorElementType::Float8E5M2 => data[index] = F8E5M2::from_f32(real_f32_odd()).to_bits(),
+		TensorElementType::Float8E5M2FNUZ => data[index] = F8E5M2FNUZ::from_f32(real_f32_odd()).to_bits(),
+		TensorElementType::Float32 => write(data, index, real_f32().to_le_bytes()),
+		TensorElementType::Float64 => write(data, index, real_f64().to_le_bytes()),
+		TensorElementType::Complex64 => {
+			write(data, index * 2, (real_f64() as f32).to_le_bytes());
+			write(data, index * 2 + 1, (imaginary as f32).to_le_bytes());
+		}
+		TensorElementType::Complex128 => {
+			write(data, index * 2, real_f64().to_le_bytes());
+			write(data, index * 2 + 1, imaginary.to_le_bytes());
+		}
+		TensorElementType::String | TensorElementType::Undefined => unreachable!()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{Element, F8E4M3FN, TensorElementType, encode};
+
+	fn encode_u16(ty: TensorElementType, element: Element) -> u16 {
+		let mut data = [0; 2];
+		encode(ty, element, &mut data, 0);
+		u16::from_le_bytes(data)
+	}
+
+	#[test]
+	fn test_encode_rounds_once() {
+		// just above the halfway point between 1 and the next f16; converting through the nearest `f32` would land on the
+		// halfway point and round down to 1
+		assert_eq!(encode_u16(TensorElementType::Float16, Element::F64(1.0 + 2.0_f64.powi(-11) + 2.0_f64.powi(-40))), 0x3C01);
+		assert_eq!(encode_u16(TensorElementType::Float16, Element::F64(-1.0 - 2.0_f64.powi(-11) - 2.0_f64.powi(-40))), 0xBC01);
+		assert_eq!(encode_u16(TensorElementType::Float16, Element::F64(1.0 + 2.0_f64.powi(-11))), 0x3C00);
+		assert_eq!(encode_u16(TensorElementType::Float16, Element::F64(1e-50)), 0x0000);
+		assert_eq!(encode_u16(TensorElementType::Float16, Element::F64(1e300)), 0x7C00);
+		// 2^24 + 2^16 + 1 is just above the halfway point between 2^24 and the next bfloat16, but the nearest `f32` is 2^24 +
+		// 2^16
+		assert_eq!(encode_u16(TensorElementType::Bfloat16, Element::Int((1 << 24) + (1 << 16) + 1)), 0x4B81);
+		assert_eq!(encode_u16(TensorElementType::Bfloat16, Element::Uint((1 << 24) + (1 << 16))), 0x4B80);
+		assert_eq!(encode_u16(TensorElementType::Bfloat16, Element::F64(f64::NAN)) & 0x7FC0, 0x7FC0);
+
+		// 1.0625 is halfway between 1 and the next F8E4M3FN
+		let mut data = [0];
+		encode(TensorElementType::Float8E4M3FN, Element::F64(1.0625 + 2.0_f64.powi(-40)), &mut data, 0);
+		assert_eq!(data[0], F8E4M3FN::from_f32(1.125).to_bits());
+		encode(TensorElementType::Float8E4M3FN, Element::F64(1e300), &mut data, 0);
+		assert_eq!(data[0], F8E4M3FN::from_f32(f32::MAX).to_bits());
+	}
+}
@@ -1,7 +1,7 @@
 use alloc::{boxed::Box, ffi::CString, format, string::String, sync::Arc, vec::Vec};
 use core::{
 	any::Any,
-	ffi::c_void,
+	ffi::{c_char, c_void},
 	fmt::Debug,
 	marker::PhantomData,
 	mem::size_of,
@@ -11,7 +11,7 @@ use core::{
 #[cfg(feature = "ndarray")]
 use ndarray::{ArcArray, Array, ArrayView, ArrayViewMut, CowArray, Dimension};
 
-use super::{DynTensor, Tensor, TensorRef, TensorRefMut};
+use super::{DynTensor, Tensor, TensorRef, TensorRefMut, view::flat_index};
 use crate::{
 	AsPointer,
 	error::{Error, ErrorCode, Result},
@@ -86,6 +86,75 @@ impl Tensor<String> {
 			_markers: PhantomData
 		})
 	}
+
+	/// Construct a string tensor from a flat sequence of strings (`String`s or `&str`s) in row-major order, plus its
+	/// shape. Unlike [`Tensor::from_string_array`], the strings may be borrowed for any lifetime, and may contain NUL
+	/// characters.
+	///
+	/// ```
+	/// # use ort::value::Tensor;
+	/// # fn main() -> ort::Result<()> {
+	/// let text = String::from("the quick brown fox");
+	/// let words: Vec<&str> = text.split(' ').collect();
+	/// let tensor = Tensor::from_strings([2usize, 2], words)?;
+	/// assert_eq!(tensor.try_extract_strings()?.1, ["the", "quick", "brown", "fox"]);
+	/// # 	Ok(())
+	/// # }
+	/// ```
+	///
+	/// # Errors
+	/// Returns an error if the number of strings does not match the shape.
+	pub fn from_strings<S: Utf8Data>(shape: impl ToShape, strings: impl IntoIterator<Item = S>) -> Result<Tensor<String>> {
+		let strings: Vec<S> = strings.into_iter().collect();
+		let mut tensor = Tensor::new_strings(shape.to_shape(Some(strings.len()))?)?;
+		for (i, string) in strings.iter().enumerate() {
+			tensor.set_element(i, string.as_utf8_bytes())?;
+		}
+		Ok(tensor)
+	}
+
+	/// Construct a string tensor with the given shape, where each element is an empty string. Elements can then be
+	/// filled in with [`Tensor::set`].
+	///
+	/// This is the string counterpart of [`Tensor::new`]; string tensors are always allocated on the CPU.
+	///
+	/// ```
+	/// # use ort::value::Tensor;
+	/// # fn main() -> ort::Result<()> {
+	/// let mut tensor = Tensor::new_strings([2usize, 2])?;
+	/// tensor.set(&[0, 1], "hello")?;
+	/// tensor.set(&[1, 0], "world")?;
+	/// assert_eq!(tensor.try_extract_strings()?.1, ["", "hello", "world", ""]);
+	///
+	/// assert!(tensor.set(&[2, 0], "out of bounds").is_err());
+	/// # 	Ok(())
+	/// # }
+	/// ```
+	pub fn new_strings(shape: impl Into<Shape>) -> Result<Tensor<String>> {
+		let tensor = DynTensor::new(&Allocator::default(), TensorElementType::String, shape)?;
+		Ok(unsafe { tensor.transmute_type() })
+	}
+
+	/// Replaces the string at the given multi-dimensional index. See [`Tensor::new_strings`].
+	///
+	/// # Errors
+	/// Returns an error if the index is out of bounds.
+	pub fn set(&mut self, index: &[usize], value: &str) -> Result<()> {
+		let shape = self.shape();
+		let Some(flat_index) = flat_index(shape, index) else {
+			return Err(Error::new_with_code(ErrorCode::InvalidArgument, format!("Index {index:?} is out of bounds for tensor of shape {shape}")));
+		};
+		self.set_element(flat_index, value.as_bytes())
+	}
+
+	fn set_element(&mut self, index: usize, value: &[u8]) -> Result<()> {
+		let mut buffer: *mut c_char = ptr::null_mut();
+		ortsys![unsafe GetResizedStringTensorElementBuffer(self.ptr_mut(), index, value.len(), &mut buffer)?];
+		if !value.is_empty() {
+			unsafe { ptr::copy_nonoverlapping(value.as_ptr(), buffer.cast::<u8>(), value.len()) };
+		}
+		Ok(())
+	}
 }
 
 impl<T: PrimitiveTensorElementType + Debug> Tensor<T> {
@@ -137,15 +206,30 @@ impl<T: PrimitiveTensorElementType + Debug> Tensor<T> {
 	/// is not already. When creating a tensor from a `Vec` or boxed slice, the data is assumed to already be in
 	/// contiguous layout.
 	///
-	/// Creating string tensors requires a separate method; see [`Tensor::from_string_array`].
+	/// Creating string tensors requires a separate method; see [`Tensor::from_string_array`]. Likewise, tensors of
+	/// packed 4-bit integers must be created with [`Tensor::from_packed`].
 	pub fn from_array(input: impl OwnedTensorArrayData<T>) -> Result<Tensor<T>> {
+		reject_packed::<T>()?;
 		let TensorArrayDataParts { shape, ptr, guard } = input.into_parts()?;
 		tensor_from_array(MemoryInfo::default(), shape, ptr.as_ptr().cast(), size_of::<T>(), T::into_tensor_element_type(), guard)
 			.map(|tensor| unsafe { tensor.transmute_type() })
 	}
 }
 
-fn tensor_from_array(
+/// Returns an error if `T` is a packed type like [`Int4x2`](crate::tensor::Int4x2), where each Rust element holds more
+/// than one tensor element, since the shape of an array of `T`s does not describe the tensor it should become.
+fn reject_packed<T: PrimitiveTensorElementType>() -> Result<()> {
+	let ty = T::into_tensor_element_type();
+	if ty.is_packed() {
+		return Err(Error::new_with_code(
+			ErrorCode::InvalidArgument,
+			format!("Tensors of type {ty} cannot be created from arrays, since each element is packed with another; use `Tensor::from_packed` instead")
+		));
+	}
+	Ok(())
+}
+
+pub(crate) fn tensor_from_array(
 	memory_info: MemoryInfo,
 	shape: Shape,
 	data: *mut c_void,
@@ -159,7 +243,7 @@ fn tensor_from_array(
 		unsafe CreateTensorWithDataAsOrtValue(
 			memory_info.ptr(),
 			data,
-			shape.num_elements() * element_size,
+			element_type.container_len(shape.num_elements()) * element_size,
 			shape.as_ptr(),
 			shape.len(),
 			element_type.into(),
@@ -215,6 +299,7 @@ impl<'a, T: PrimitiveTensorElementType + Debug> TensorRef<'a, T> {
 	/// When passing an [`ndarray`] type, the data **must** have a contiguous memory layout, or else an error will be
 	/// returned. See [`ndarray::ArrayBase::as_standard_layout`] to convert an array to a contiguous layout.
 	pub fn from_array_view(input: impl TensorArrayData<T> + 'a) -> Result<TensorRef<'a, T>> {
+		reject_packed::<T>()?;
 		let (shape, data, guard) = input.ref_parts()?;
 		tensor_from_array(MemoryInfo::default(), shape, data.as_ptr() as *mut _, size_of::<T>(), T::into_tensor_element_type(), guard).map(|tensor| {
 			let mut tensor: TensorRef<'_, T> = TensorRef::new(unsafe { tensor.transmute_type() });
@@ -254,6 +339,7 @@ impl<'a, T: PrimitiveTensorElementType + Debug> TensorRefMut<'a, T> {
 	/// When passing an [`ndarray`] type, the data **must** have a contiguous memory layout, or else an error will be
 	/// returned. See [`ndarray::ArrayBase::as_standard_layout`] to convert an array to a contiguous layout.
 	pub fn from_array_view_mut(mut input: impl TensorArrayDataMut<T>) -> Result<TensorRefMut<'a, T>> {
+		reject_packed::<T>()?;
 		let (shape, data, guard) = input.ref_parts_mut()?;
 		tensor_from_array(MemoryInfo::default(), shape, data.as_ptr() as *mut _, size_of::<T>(), T::into_tensor_element_type(), guard).map(|tensor| {
 			let mut tensor: TensorRefMut<'_, T> = TensorRefMut::new(unsafe { tensor.transmute_type() });
@@ -0,0 +1,238 @@
+use alloc::{
+	format,
+	string::{String, ToString},
+	vec::Vec
+};
+use core::fmt::{self, Write};
+
+use super::TensorValueTypeMarker;
+use crate::{
+	tensor::{F8E4M3FN, F8E4M3FNUZ, F8E5M2, F8E5M2FNUZ, TensorElementType, bf16_to_f32, f16_to_f32},
+	value::{Value, ValueType}
+};
+
+/// Tensors with more elements than this are summarized, showing only the first & last [`EDGE_ITEMS`] of each axis.
+const THRESHOLD: usize = 1000;
+const EDGE_ITEMS: usize = 3;
+
+/// A single element of a tensor, decoded from its raw bytes.
+#[derive(Debug, Clone, Copy)]
+pub(super) enum Element {
+	Bool(bool),
+	Int(i64),
+	Uint(u64),
+	F32(f32),
+	F64(f64),
+	C32(f32, f32),
+	C64(f64, f64)
+}
+
+impl Element {
+	/// Decodes the `index`th element of a (non-string) tensor of type `ty` from its raw little-endian bytes.
+	pub(super) fn decode(ty: TensorElementType, data: &[u8], index: usize) -> Element {
+		fn read<const N: usize>(data: &[u8], index: usize) -> [u8; N] {
+			data[index * N..][..N].try_into().expect("slice has N bytes")
+		}
+
+		match ty {
+			TensorElementType::Bool => Element::Bool(data[index] != 0),
+			TensorElementType::Int8 => Element::Int(i64::from(data[index] as i8)),
+			TensorElementType::Uint8 => Element::Uint(u64::from(data[index])),
+			TensorElementType::Int16 => Element::Int(i64::from(i16::from_le_bytes(read(data, index)))),
+			TensorElementType::Uint16 => Element::Uint(u64::from(u16::from_le_bytes(read(data, index)))),
+			TensorElementType::Int32 => Element::Int(i64::from(i32::from_le_bytes(read(data, index)))),
+			TensorElementType::Uint32 => Element::Uint(u64::from(u32::from_le_bytes(read(data, index)))),
+			TensorElementType::Int64 => Element::Int(i64::from_le_bytes(read(data, index))),
+			TensorElementType::Uint64 => Element::Uint(u64::from_le_bytes(read(data, index))),
+			TensorElementType::Int4 | TensorElementType::Uint4 => {
+				let byte = data[index / 2];
+				let nibble = if index % 2 == 0 { byte & 0x0F } else { byte >> 4 };
+				if ty == TensorElementType::Int4 {
+					// sign-extend the 4-bit value
+					Element::Int(i64::from(((nibble << 4) as i8) >> 4))
+				} else {
+					Element::Uint(u64::from(nibble))
+				}
+			}
+			TensorElementType::Float16 => Element::F32(f16_to_f32(u16::from_le_bytes(read(data, index)))),
+			TensorElementType::Bfloat16 => Element::F32(bf16_to_f32(u16::from_le_bytes(read(data, index)))),
+			TensorElementType::Float8E4M3FN => Element::F32(F8E4M3FN(data[index]).to_f32()),
+			TensorElementType::Float8E4M3FNUZ => Element::F32(F8E4M3FNUZ(data[index]).to_f32()),
+			TensorElementType::Float8E5M2 => Element::F32(F8E5M2(data[index]).to_f32()),
+			TensorElementType::Float8E5M2FNUZ => Element::F32(F8E5M2FNUZ(data[index]).to_f32()),
+			TensorElementType::Float32 => Element::F32(f32::from_le_bytes(read(data, index))),
+			TensorElementType::Float64 => Element::F64(f64::from_le_bytes(read(data, index))),
+			TensorElementType::Complex64 => Element::C32(f32::from_le_bytes(read(&data[index * 8..], 0)), f32::from_le_bytes(read(&data[index * 8 + 4..], 0))),
+			TensorElementType::Complex128 => {
+				Element::C64(f64::from_le_bytes(read(&data[index * 16..], 0)), f64::from_le_bytes(read(&data[index * 16 + 8..], 0)))
+			}
+			TensorElementType::String | TensorElementType::Undefined => unreachable!()
+		}
+	}
+
+	/// Returns this element as an `f64`, or `None` for complex numbers.
+	#[cfg(feature = "std")]
+	pub(super) fn to_f64(self) -> Option<f64> {
+		match self {
+			Element::Bool(b) => Some(if b { 1.0 } else { 0.0 }),
+			Element::Int(i) => Some(i as f64),
+			Element::Uint(u) => Some(u as f64),
+			Element::F32(f) => Some(f64::from(f)),
+			Element::F64(f) => Some(f),
+			Element::C32(..) | Element::C64(..) => None
+		}
+	}
+}
+
+impl fmt::Display for Element {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		// floats use `Debug` formatting so that integral values are printed with a trailing `.0`, like NumPy's `1.`
+		match *self {
+			Element::Bool(b) => fmt::Display::fmt(&b, f),
+			Element::Int(i) => fmt::Display::fmt(&i, f),
+			Element::Uint(u) => fmt::Display::fmt(&u, f),
+			Element::F32(x) => fmt::Debug::fmt(&x, f),
+			Element::F64(x) => fmt::Debug::fmt(&x, f),
+			Element::C32(re, im) => {
+				fmt::Debug::fmt(&re, f)?;
+				if !im.is_sign_negative() {
+					f.write_char('+')?;
+				}
+				fmt::Debug::fmt(&im, f)?;
+				f.write_char('i')
+			}
+			Element::C64(re, im) => {
+				fmt::Debug::fmt(&re, f)?;
+				if !im.is_sign_negative() {
+					f.write_char('+')?;
+				}
+				fmt::Debug::fmt(&im, f)?;
+				f.write_char('i')
+			}
+		}
+	}
+}
+
+/// Writes a tensor's elements in nested brackets, like NumPy. `elements` holds the formatted elements which will be
+/// displayed, in order; elements hidden by summarization are skipped entirely.
+struct Layout<'a> {
+	shape: &'a [usize],
+	summarize: bool,
+	width: usize,
+	elements: core::slice::Iter<'a, String>
+}
+
+impl Layout<'_> {
+	fn write(&mut self, f: &mut fmt::Formatter<'_>, axis: usize) -> fmt::Result {
+		if axis == self.shape.len() {
+			let element = self.elements.next().expect("element count should match shape");
+			return write!(f, "{element:>width$}", width = self.width);
+		}
+
+		f.write_char('[')?;
+		let separator = if axis == self.shape.len() - 1 {
+			String::from(", ")
+		} else {
+			// like NumPy, blocks of 3 or more dimensions are also separated by blank lines
+			let newlines = "\n".repeat(self.shape.len() - axis - 1);
+			format!(",{newlines}{:indent$}", "", indent = axis + 1)
+		};
+		for (i, hidden) in visible_indices(self.shape[axis], self.summarize).enumerate() {
+			if i > 0 {
+				f.write_str(&separator)?;
+			}
+			if hidden {
+				f.write_str("...")?;
+			} else {
+				self.write(f, axis + 1)?;
+			}
+		}
+		f.write_char(']')
+	}
+}
+
+/// Returns which indices along an axis of size `len` are displayed, with `true` marking the `...` placeholder for
+/// hidden indices.
+fn visible_indices(len: usize, summarize: bool) -> impl Iterator<Item = bool> {
+	let (shown, hidden) = if summarize && len > 2 * EDGE_ITEMS { (2 * EDGE_ITEMS, true) } else { (len, false) };
+	let head = if hidden { EDGE_ITEMS } else { shown };
+	(0..head).map(|_| false).chain(hidden.then_some(true)).chain((head..shown).map(|_| false))
+}
+
+/// Returns the flat indices of the elements which are displayed for a tensor of the given shape.
+fn visible_elements(shape: &[usize], summarize: bool) -> Vec<usize> {
+	let mut indices = alloc::vec![0];
+	for &dim in shape {
+		let axis: Vec<usize> = if summarize && dim > 2 * EDGE_ITEMS {
+			(0..EDGE_ITEMS).chain(dim - EDGE_ITEMS..dim).collect()
+		} else {
+			(0..dim).collect()
+		};
+		indices = indices.iter().flat_map(|&base| axis.iter().map(move |&i| base * dim + i)).collect();
+	}
+	indices
+}
+
+/// Formats tensors like NumPy does, with nested brackets for each dimension & elements aligned to the same width:
+///
+/// ```
+/// # use ort::value::Tensor;
+/// # fn main() -> ort::Result<()> {
+/// let tensor = Tensor::from_array(([2usize, 3], vec![1.0_f32, -2.5, 3.0, 4.0, 5.0, 6.0]))?;
+/// assert_eq!(tensor.to_string(), "[[ 1.0, -2.5,  3.0],\n [ 4.0,  5.0,  6.0]]");
+/// // precision is passed on to each element
+/// assert_eq!(format!("{tensor:.2}"), "[[ 1.00, -2.50,  3.00],\n [ 4.00,  5.00,  6.00]]");
+/// # 	Ok(())
+/// # }
+/// ```
+///
+/// Tensors with more than 1000 elements are summarized, showing only the first & last 3 items of each axis; use the
+/// alternate flag (`{:#}`) to display every element. Values which are not tensors, or whose data is not accessible from
+/// the CPU, are displayed as their type.
+impl<Type: TensorValueTypeMarker + ?Sized> fmt::Display for Value<Type> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		let ValueType::Tensor { ty, shape, .. } = self.dtype() else {
+			return write!(f, "<{}>", self.dtype());
+		};
+		let dims: Vec<usize> = shape.iter().map(|&d| d as usize).collect();
+		let summarize = !f.alternate() && shape.num_elements() > THRESHOLD;
+		let indices = visible_elements(&dims, summarize);
+
+		let precision = f.precision();
+		let format_element = |element: &dyn fmt::Display| match precision {
+			Some(precision) => format!("{element:.precision$}"),
+			None => element.to_string()
+		};
+		let elements: Vec<String> = if *ty == TensorElementType::String {
+			let Ok((_, strings)) = self.try_extract_strings() else {
+				return write!(f, "<{}>", self.dtype());
+			};
+			indices.iter().map(|&i| format!("{:?}", strings[i])).collect()
+		} else {
+			let Ok(data) = self.raw_bytes() else {
+				return write!(f, "<{} on device `{}`>", self.dtype(), self.memory_info().allocation_device().as_str());
+			};
+			indices.iter().map(|&i| format_element(&Element::decode(*ty, data, i))).collect()
+		};
+
+		Layout {
+			shape: &dims,
+			summarize,
+			width: elements.iter().map(|e| e.chars().count()).max().unwrap_or(0),
+			elements: elements.iter()
+		}
+		.write(f, 0)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::visible_elements;
+
+	#[test]
+	fn test_visible_elements() {
+		assert_eq!(visible_elements(&[2, 3], false), [0, 1, 2, 3, 4, 5]);
+		assert_eq!(visible_elements(&[], true), [0]);
+		assert_eq!(visible_elements(&[1, 8], true), [0, 1, 2, 5, 6, 7]);
+	}
+}
@@ -1,12 +1,7 @@
-use alloc::{
-	format,
-	string::{FromUtf8Error, String},
-	vec,
-	vec::Vec
-};
+use alloc::{format, string::String, vec, vec::Vec};
 use core::{ffi::c_void, fmt::Debug, ptr, slice};
 
-use super::{Tensor, TensorValueTypeMarker};
+use super::{Tensor, TensorValueTypeMarker, view::flat_index};
 use crate::{
 	AsPointer,
 	error::{Error, ErrorCode, Result},
@@ -48,7 +43,7 @@ impl<Type: TensorValueTypeMarker + ?Sized> Value<Type> {
 	#[cfg(feature = "ndarray")]
 	#[cfg_attr(docsrs, doc(cfg(feature = "ndarray")))]
 	pub fn try_extract_array<T: PrimitiveTensorElementType>(&self) -> Result<ndarray::ArrayViewD<'_, T>> {
-		extract_tensor(self.ptr().cast_mut(), self.dtype(), self.memory_info(), T::into_tensor_element_type())
+		extract_array_tensor(self.ptr().cast_mut(), self.dtype(), self.memory_info(), T::into_tensor_element_type())
 			.and_then(|(ptr, shape)| Ok(unsafe { ndarray::ArrayView::from_shape_ptr(shape.to_ixdyn(), data_ptr(ptr)?.cast::<T>()) }))
 	}
 
@@ -117,7 +112,7 @@ impl<Type: TensorValueTypeMarker + ?Sized> Value<Type> {
 	#[cfg(feature = "ndarray")]
 	#[cfg_attr(docsrs, doc(cfg(feature = "ndarray")))]
 	pub fn try_extract_array_mut<T: PrimitiveTensorElementType>(&mut self) -> Result<ndarray::ArrayViewMutD<'_, T>> {
-		extract_tensor(self.ptr_mut(), self.dtype(), self.memory_info(), T::into_tensor_element_type())
+		extract_array_tensor(self.ptr_mut(), self.dtype(), self.memory_info(), T::into_tensor_element_type())
 			.and_then(|(ptr, shape)| Ok(unsafe { ndarray::ArrayViewMut::from_shape_ptr(shape.to_ixdyn(), data_ptr(ptr)?.cast::<T>()) }))
 	}
 
@@ -150,8 +145,9 @@ impl<Type: TensorValueTypeMarker + ?Sized> Value<Type> {
 	///
 	/// [`DynValue`]: crate::value::DynValue
 	pub fn try_extract_tensor<T: PrimitiveTensorElementType>(&self) -> Result<(&Shape, &[T])> {
-		extract_tensor(self.ptr().cast_mut(), self.dtype(), self.memory_info(), T::into_tensor_element_type())
-			.and_then(|(ptr, shape)| Ok((shape, unsafe { slice::from_raw_parts(data_ptr(ptr)?.cast::<T>(), shape.num_elements()) })))
+		extract_tensor(self.ptr().cast_mut(), self.dtype(), self.memory_info(), T::into_tensor_element_type()).and_then(|(ptr, shape)| {
+			Ok((shape, unsafe { slice::from_raw_parts(data_ptr(ptr)?.cast::<T>(), T::into_tensor_element_type().container_len(shape.num_elements())) }))
+		})
 	}
 
 	/// Attempt to extract the underlying data into a view tuple, consisting of the tensor's shape and a
@@ -180,8 +176,9 @@ impl<Type: TensorValueTypeMarker + ?Sized> Value<Type> {
 	///
 	/// [`DynValue`]: crate::value::DynValue
 	pub fn try_extract_tensor_mut<T: PrimitiveTensorElementType>(&mut self) -> Result<(&Shape, &mut [T])> {
-		extract_tensor(self.ptr_mut(), self.dtype(), self.memory_info(), T::into_tensor_element_type())
-			.and_then(|(ptr, shape)| Ok((shape, unsafe { slice::from_raw_parts_mut(data_ptr(ptr)?.cast::<T>(), shape.num_elements()) })))
+		extract_tensor(self.ptr_mut(), self.dtype(), self.memory_info(), T::into_tensor_element_type()).and_then(|(ptr, shape)| {
+			Ok((shape, unsafe { slice::from_raw_parts_mut(data_ptr(ptr)?.cast::<T>(), T::into_tensor_element_type().container_len(shape.num_elements())) }))
+		})
 	}
 
 	/// Attempt to extract the underlying data into a Rust `ndarray`.
@@ -228,6 +225,29 @@ impl<Type: TensorValueTypeMarker + ?Sized> Value<Type> {
 		})
 	}
 
+	/// Attempt to extract the underlying string data into a [`StringTensorView`], which provides access to each string
+	/// as a `&str`.
+	///
+	/// Unlike [`Tensor::try_extract_strings`], which allocates a `String` for each element, the view copies the
+	/// contents of all strings into a single buffer; accessing or iterating over its elements does not allocate.
+	///
+	/// ```
+	/// # use ort::value::Tensor;
+	/// # fn main() -> ort::Result<()> {
+	/// let tensor = Tensor::from_string_array(([2usize, 2], &["a", "b", "c", "d"][..]))?.into_dyn();
+	///
+	/// let view = tensor.try_extract_string_view()?;
+	/// assert_eq!(**view.shape(), [2, 2]);
+	/// assert_eq!(view.get(&[1, 0]), Some("c"));
+	/// assert_eq!(view.iter().collect::<Vec<_>>(), ["a", "b", "c", "d"]);
+	/// # 	Ok(())
+	/// # }
+	/// ```
+	pub fn try_extract_string_view(&self) -> Result<StringTensorView<'_>> {
+		extract_tensor(self.ptr().cast_mut(), self.dtype(), self.memory_info(), TensorElementType::String)
+			.and_then(|(ptr, shape)| StringTensorView::new(ptr, shape))
+	}
+
 	/// Returns the shape of the tensor.
 	///
 	/// ```
@@ -273,6 +293,23 @@ fn extract_tensor<'t>(
 	}
 }
 
+/// Like [`extract_tensor`], but for extracting as an `ndarray` array, which isn't possible for packed element types.
+#[cfg(feature = "ndarray")]
+fn extract_array_tensor<'t>(
+	ptr: *mut ort_sys::OrtValue,
+	dtype: &'t ValueType,
+	memory_info: &MemoryInfo,
+	expected_ty: TensorElementType
+) -> Result<(*mut ort_sys::OrtValue, &'t Shape)> {
+	if expected_ty.is_packed() {
+		return Err(Error::new_with_code(
+			ErrorCode::InvalidArgument,
+			format!("Cannot extract a Tensor<{expected_ty}> as an array since its elements are packed; use `try_extract_tensor` instead")
+		));
+	}
+	extract_tensor(ptr, dtype, memory_info, expected_ty)
+}
+
 unsafe fn data_ptr(ptr: *mut ort_sys::OrtValue) -> Result<*mut c_void> {
 	let mut output_array_ptr: *mut c_void = ptr::null_mut();
 	ortsys![unsafe GetTensorMutableData(ptr, &mut output_array_ptr)?; nonNull(output_array_ptr)];
@@ -280,37 +317,80 @@ unsafe fn data_ptr(ptr: *mut ort_sys::OrtValue) -> Result<*mut c_void> {
 }
 
 fn extract_strings(ptr: *mut ort_sys::OrtValue, shape: &Shape) -> Result<Vec<String>> {
-	let len = shape.num_elements();
-	// Total length of string data, not including \0 suffix
-	let mut total_length = 0;
-	ortsys![unsafe GetStringTensorDataLength(ptr, &mut total_length)?];
-
-	// In the JNI impl of this, tensor_element_len was included in addition to total_length,
-	// but that seems contrary to the docs of GetStringTensorDataLength, and those extra bytes
-	// don't seem to be written to in practice either.
-	// If the string data actually did go farther, it would panic below when using the offset
-	// data to get slices for each string.
-	let mut string_contents = vec![0u8; total_length];
-	// one extra slot so that the total length can go in the last one, making all per-string
-	// length calculations easy
-	let mut offsets = vec![0; len + 1];
-
-	ortsys![unsafe GetStringTensorContent(ptr, string_contents.as_mut_ptr().cast(), total_length, offsets.as_mut_ptr(), len)?];
-
-	// final offset = overall length so that per-string length calculations work for the last string
-	debug_assert_eq!(0, offsets[len]);
-	offsets[len] = total_length;
-
-	let strings = offsets
-		// offsets has 1 extra offset past the end so that all windows work
-		.windows(2)
-		.map(|w| {
-			let slice = &string_contents[w[0]..w[1]];
-			String::from_utf8(slice.into())
-		})
-		.collect::<Result<Vec<String>, FromUtf8Error>>()
-		.map_err(Error::wrap)?;
-	Ok(strings)
+	Ok(StringTensorView::new(ptr, shape)?.iter().map(String::from).collect())
+}
+
+/// A view of the elements of a string tensor; see [`Tensor::try_extract_string_view`].
+///
+/// The contents of all strings are copied out of the tensor into a single buffer when the view is created, so the view
+/// does not reflect any later modifications to the tensor.
+#[derive(Debug, Clone)]
+pub struct StringTensorView<'t> {
+	shape: &'t Shape,
+	data: String,
+	/// The start of each string in `data`, plus a final entry holding the length of `data`.
+	offsets: Vec<usize>
+}
+
+impl<'t> StringTensorView<'t> {
+	fn new(ptr: *mut ort_sys::OrtValue, shape: &'t Shape) -> Result<Self> {
+		let len = shape.num_elements();
+		// Total length of string data, not including \0 suffix
+		let mut total_length = 0;
+		ortsys![unsafe GetStringTensorDataLength(ptr, &mut total_length)?];
+
+		// In the JNI impl of this, tensor_element_len was included in addition to total_length,
+		// but that seems contrary to the docs of GetStringTensorDataLength, and those extra bytes
+		// don't seem to be written to in practice either.
+		let mut data = vec![0u8; total_length];
+		// one extra slot so that the total length can go in the last one, making all per-string
+		// length calculations easy
+		let mut offsets = vec![0; len + 1];
+
+		ortsys![unsafe GetStringTensorContent(ptr, data.as_mut_ptr().cast(), total_length, offsets.as_mut_ptr(), len)?];
+
+		// final offset = overall length so that per-string length calculations work for the last string
+		debug_assert_eq!(0, offsets[len]);
+		offsets[len] = total_length;
+
+		// Each string is validated separately so that every offset is known to lie on a character boundary; if the offsets
+		// went past the end of the data, this would panic instead of reading out of bounds.
+		for w in offsets.windows(2) {
+			core::str::from_utf8(&data[w[0]..w[1]]).map_err(Error::wrap)?;
+		}
+		// SAFETY: a concatenation of valid UTF-8 strings is valid UTF-8
+		let data = unsafe { String::from_utf8_unchecked(data) };
+		Ok(Self { shape, data, offsets })
+	}
+
+	/// Returns the shape of the tensor.
+	pub fn shape(&self) -> &'t Shape {
+		self.shape
+	}
+
+	/// Returns the number of strings in the tensor.
+	pub fn len(&self) -> usize {
+		self.offsets.len() - 1
+	}
+
+	/// Returns `true` if the tensor has no elements.
+	pub fn is_empty(&self) -> bool {
+		self.len() == 0
+	}
+
+	/// Returns the string at the given multi-dimensional index, or `None` if the index is out of bounds.
+	pub fn get(&self, index: &[usize]) -> Option<&str> {
+		flat_index(self.shape, index).map(|i| self.element(i))
+	}
+
+	/// Returns an iterator over all strings in the tensor, in row-major order.
+	pub fn iter(&self) -> impl ExactSizeIterator<Item = &str> + '_ {
+		(0..self.len()).map(|i| self.element(i))
+	}
+
+	fn element(&self, index: usize) -> &str {
+		&self.data[self.offsets[index]..self.offsets[index + 1]]
+	}
 }
 
 impl<T: PrimitiveTensorElementType + Debug> Tensor<T> {
@@ -1,5 +1,10 @@
+mod cast;
 mod create;
+mod display;
 mod extract;
+#[cfg(feature = "std")]
+mod summary;
+mod view;
 
 use alloc::sync::Arc;
 use core::{
@@ -9,7 +14,14 @@ use core::{
 	ptr::{self, NonNull}
 };
 
-pub use self::create::{OwnedTensorArrayData, TensorArrayData, TensorArrayDataMut, TensorArrayDataParts, ToShape};
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub use self::summary::TensorSummary;
+pub(crate) use self::{cast::cast_tensor, create::tensor_from_array};
+pub use self::{
+	create::{OwnedTensorArrayData, TensorArrayData, TensorArrayDataMut, TensorArrayDataParts, ToShape},
+	extract::StringTensorView
+};
 use super::{DowncastableTarget, DynValue, Value, ValueInner, ValueRef, ValueRefMut, ValueType, ValueTypeMarker};
 use crate::{
 	AsPointer,
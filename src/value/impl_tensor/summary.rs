@@ -0,0 +1,213 @@
+use alloc::{format, vec, vec::Vec};
+
+use super::{TensorValueTypeMarker, display::Element};
+use crate::{
+	error::{Error, ErrorCode, Result},
+	tensor::TensorElementType,
+	value::{Value, ValueType}
+};
+
+/// Summary statistics of a tensor's elements; see [`Tensor::summary`](crate::value::Tensor::summary).
+///
+/// Statistics are computed over all finite elements; NaNs & infinities are only counted.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TensorSummary {
+	len: usize,
+	min: Option<f64>,
+	max: Option<f64>,
+	mean: Option<f64>,
+	std: Option<f64>,
+	nan_count: usize,
+	inf_count: usize,
+	histogram: Vec<usize>
+}
+
+impl TensorSummary {
+	/// Returns the total number of elements in the tensor.
+	pub fn len(&self) -> usize {
+		self.len
+	}
+
+	/// Returns `true` if the tensor has no elements.
+	pub fn is_empty(&self) -> bool {
+		self.len == 0
+	}
+
+	/// Returns the smallest finite element, or `None` if there are no finite elements.
+	pub fn min(&self) -> Option<f64> {
+		self.min
+	}
+
+	/// Returns the largest finite element, or `None` if there are no finite elements.
+	pub fn max(&self) -> Option<f64> {
+		self.max
+	}
+
+	/// Returns the mean of all finite elements, or `None` if there are no finite elements.
+	pub fn mean(&self) -> Option<f64> {
+		self.mean
+	}
+
+	/// Returns the (population) standard deviation of all finite elements, or `None` if there are no finite elements.
+	pub fn std(&self) -> Option<f64> {
+		self.std
+	}
+
+	/// Returns the number of NaN elements.
+	pub fn nan_count(&self) -> usize {
+		self.nan_count
+	}
+
+	/// Returns the number of infinite elements, positive or negative.
+	pub fn inf_count(&self) -> usize {
+		self.inf_count
+	}
+
+	/// Returns the number of finite elements in each of a number of equal-width buckets spanning
+	/// [`min`](TensorSummary::min) to [`max`](TensorSummary::max), inclusive.
+	pub fn histogram(&self) -> &[usize] {
+		&self.histogram
+	}
+}
+
+impl<Type: TensorValueTypeMarker + ?Sized> Value<Type> {
+	/// Computes [summary statistics](TensorSummary) of this tensor's elements, with a 10-bucket histogram.
+	///
+	/// Works with tensors of any numeric or boolean element type (booleans are treated as `0` or `1`). Returns an error
+	/// for string & complex tensors, or if the tensor's data is not accessible from the CPU.
+	///
+	/// ```
+	/// # use ort::value::Tensor;
+	/// # fn main() -> ort::Result<()> {
+	/// let tensor = Tensor::from_array(([5usize], vec![1.0_f32, 2.0, 3.0, f32::NAN, f32::INFINITY]))?;
+	/// let summary = tensor.summary()?;
+	/// assert_eq!(summary.min(), Some(1.0));
+	/// assert_eq!(summary.max(), Some(3.0));
+	/// assert_eq!(summary.mean(), Some(2.0));
+	/// assert_eq!((summary.nan_count(), summary.inf_count()), (1, 1));
+	/// assert_eq!(summary.histogram().iter().sum::<usize>(), 3);
+	/// # 	Ok(())
+	/// # }
+	/// ```
+	pub fn summary(&self) -> Result<TensorSummary> {
+		self.summary_with_buckets(10)
+	}
+
+	/// Computes [summary statistics](TensorSummary) of this tensor's elements, with a histogram of `buckets` buckets.
+	/// See [`Tensor::summary`](crate::value::Tensor::summary).
+	pub fn summary_with_buckets(&self, buckets: usize) -> Result<TensorSummary> {
+		let ValueType::Tensor { ty, shape, .. } = self.dtype() else {
+			return Err(Error::new_with_code(ErrorCode::InvalidArgument, format!("Cannot summarize value of type {}", self.dtype())));
+		};
+		if matches!(ty, TensorElementType::String | TensorElementType::Complex64 | TensorElementType::Complex128) {
+			return Err(Error::new_with_code(ErrorCode::InvalidArgument, format!("Cannot summarize tensor of type {ty}")));
+		}
+
+		let data = self.raw_bytes()?;
+		let len = shape.num_elements();
+		let values = || (0..len).filter_map(|i| Element::decode(*ty, data, i).to_f64());
+
+		let (mut nan_count, mut inf_count) = (0, 0);
+		let (mut min, mut max) = (f64::INFINITY, f64::NEG_INFINITY);
+		// Welford's algorithm, for numerical stability
+		let (mut count, mut mean, mut m2) = (0_usize, 0.0, 0.0);
+		for value in values() {
+			if value.is_nan() {
+				nan_count += 1;
+			} else if value.is_infinite() {
+				inf_count += 1;
+			} else {
+				min = min.min(value);
+				max = max.max(value);
+				count += 1;
+				let delta = value - mean;
+				mean += delta / count as f64;
+				m2 += delta * (value - mean);
+			}
+		}
+
+		let mut histogram = vec![0; buckets];
+		if count > 0 && buckets > 0 {
+			let width = (max - min) / buckets as f64;
+			for value in values().filter(|v| v.is_finite()) {
+				let bucket = if width > 0.0 { ((value - min) / width) as usize } else { 0 };
+				// the maximum value falls into the last bucket
+				histogram[bucket.min(buckets - 1)] += 1;
+			}
+		}
+
+		let finite = |v: f64| (count > 0).then_some(v);
+		Ok(TensorSummary {
+			len,
+			min: finite(min),
+			max: finite(max),
+			mean: finite(mean),
+			std: finite((m2 / count as f64).sqrt()),
+			nan_count,
+			inf_count,
+			histogram
+		})
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use alloc::{vec, vec::Vec};
+
+	use crate::{
+		error::ErrorCode,
+		memory::Allocator,
+		tensor::TensorElementType,
+		value::{DynTensor, Tensor}
+	};
+
+	#[test]
+	fn test_empty() -> crate::Result<()> {
+		let summary = Tensor::from_array(([0_usize], Vec::<f32>::new()))?.summary()?;
+		assert!(summary.is_empty());
+		assert_eq!((summary.min(), summary.max(), summary.mean(), summary.std()), (None, None, None, None));
+		assert_eq!(summary.histogram(), [0; 10]);
+		Ok(())
+	}
+
+	#[test]
+	fn test_all_nan() -> crate::Result<()> {
+		let summary = Tensor::from_array(([3_usize], vec![f32::NAN; 3]))?.summary()?;
+		assert_eq!(summary.len(), 3);
+		assert_eq!(summary.nan_count(), 3);
+		assert_eq!((summary.min(), summary.max(), summary.mean(), summary.std()), (None, None, None, None));
+		assert_eq!(summary.histogram().iter().sum::<usize>(), 0);
+		Ok(())
+	}
+
+	#[test]
+	fn test_constant() -> crate::Result<()> {
+		// all elements fall into the first bucket when the histogram has a width of 0
+		let summary = Tensor::from_array(([4_usize], vec![2.5_f64; 4]))?.summary_with_buckets(4)?;
+		assert_eq!((summary.min(), summary.max(), summary.mean(), summary.std()), (Some(2.5), Some(2.5), Some(2.5), Some(0.0)));
+		assert_eq!(summary.histogram(), [4, 0, 0, 0]);
+		Ok(())
+	}
+
+	#[test]
+	fn test_integers_and_bools() -> crate::Result<()> {
+		let summary = Tensor::from_array(([4_usize], vec![-2_i32, 0, 2, 4]))?.summary_with_buckets(3)?;
+		assert_eq!((summary.min(), summary.max(), summary.mean()), (Some(-2.0), Some(4.0), Some(1.0)));
+		assert_eq!(summary.histogram(), [1, 1, 2]);
+
+		let summary = Tensor::from_array(([4_usize], vec![true, false, true, true]))?.summary_with_buckets(2)?;
+		assert_eq!((summary.min(), summary.max(), summary.mean()), (Some(0.0), Some(1.0), Some(0.75)));
+		assert_eq!(summary.histogram(), [1, 3]);
+		Ok(())
+	}
+
+	#[test]
+	fn test_unsupported_types() -> crate::Result<()> {
+		let strings = Tensor::from_strings([1_usize], ["a"])?;
+		assert!(matches!(strings.summary(), Err(e) if e.code() == ErrorCode::InvalidArgument));
+
+		let complex = DynTensor::new(&Allocator::default(), TensorElementType::Complex64, [1_usize])?;
+		assert!(matches!(complex.summary(), Err(e) if e.code() == ErrorCode::InvalidArgument));
+		Ok(())
+	}
+}
@@ -0,0 +1,349 @@
+use alloc::{boxed::Box, format, sync::Arc, vec, vec::Vec};
+use core::{
+	fmt::Debug,
+	ops::{Bound, RangeBounds},
+	slice
+};
+
+use super::{DynTensor, Tensor, TensorValueTypeMarker, tensor_from_array};
+use crate::{
+	error::{Error, ErrorCode, Result},
+	memory::Allocator,
+	tensor::{PrimitiveTensorElementType, Shape, TensorElementType},
+	value::{Value, ValueRef, ValueRefMut, ValueType}
+};
+
+impl<Type: TensorValueTypeMarker + ?Sized> Value<Type> {
+	/// Returns the element type & shape of this tensor, or an error if this value is not a tensor.
+	fn tensor_dtype(&self) -> Result<(TensorElementType, &Shape)> {
+		match self.dtype() {
+			ValueType::Tensor { ty, shape, .. } => Ok((*ty, shape)),
+			t => Err(Error::new_with_code(ErrorCode::InvalidArgument, format!("Cannot view value of type {t} as a tensor")))
+		}
+	}
+
+	/// Creates a new tensor of the given shape over this tensor's data, starting `offset` elements in. The new tensor
+	/// keeps this tensor alive.
+	fn view_at(&self, shape: Shape, offset: usize) -> Result<Value<Type>> {
+		let (ty, _) = self.tensor_dtype()?;
+		match ty {
+			TensorElementType::String => {
+				return Err(Error::new_with_code(ErrorCode::InvalidArgument, "Cannot create a view of a string tensor"));
+			}
+			_ if ty.is_packed() && offset % 2 != 0 => {
+				return Err(Error::new_with_code(
+					ErrorCode::InvalidArgument,
+					format!("Cannot create a view of a {ty} tensor starting at element {offset}, which is not aligned to a byte")
+				));
+			}
+			_ => {}
+		}
+
+		let data = self.data_ptr()?.cast::<u8>().cast_mut().wrapping_add(ty.byte_size(offset));
+		let element_size = if ty.is_packed() { 1 } else { ty.byte_size(1) };
+		let tensor = tensor_from_array(self.memory_info().clone(), shape, data.cast(), element_size, ty, Some(Box::new(Arc::clone(&self.inner))))?;
+		Ok(unsafe { tensor.transmute_type() })
+	}
+
+	/// Resolves the shape given to [`Tensor::reshape`], inferring the size of at most one dimension given as `-1`.
+	fn reshaped(&self, shape: Shape) -> Result<Shape> {
+		let (_, old_shape) = self.tensor_dtype()?;
+		let mut shape = shape;
+		let num_elements = old_shape.num_elements();
+		let known: usize = shape.iter().filter(|&&d| d != -1).map(|&d| d.max(0) as usize).product();
+		match shape.iter().filter(|&&d| d == -1).count() {
+			0 => {}
+			1 if known != 0 && num_elements % known == 0 => {
+				let dim = shape.iter_mut().find(|d| **d == -1).expect("shape has an inferred dimension");
+				*dim = (num_elements / known) as i64;
+			}
+			_ => return Err(Error::new_with_code(ErrorCode::InvalidArgument, format!("Cannot reshape tensor of shape {old_shape} to {shape}")))
+		}
+		if shape.iter().any(|&d| d < 0) || shape.num_elements() != num_elements {
+			return Err(Error::new_with_code(ErrorCode::InvalidArgument, format!("Cannot reshape tensor of shape {old_shape} to {shape}")));
+		}
+		Ok(shape)
+	}
+
+	/// Resolves the range given to [`Tensor::slice`] to the range of elements it spans along the outermost axis, and
+	/// the shape of the resulting view.
+	fn sliced(&self, range: (Bound<&usize>, Bound<&usize>)) -> Result<(Shape, usize)> {
+		let (_, shape) = self.tensor_dtype()?;
+		let Some(&len) = shape.first() else {
+			return Err(Error::new_with_code(ErrorCode::InvalidArgument, "Cannot slice a scalar tensor"));
+		};
+		let len = len as usize;
+		// only reachable with bounds of `usize::MAX`, which are out of bounds for any tensor
+		let overflowed = || Error::new_with_code(ErrorCode::InvalidArgument, format!("Slice is out of bounds for tensor of shape {shape}"));
+		let start = match range.0 {
+			Bound::Included(&start) => start,
+			Bound::Excluded(&start) => start.checked_add(1).ok_or_else(overflowed)?,
+			Bound::Unbounded => 0
+		};
+		let end = match range.1 {
+			Bound::Included(&end) => end.checked_add(1).ok_or_else(overflowed)?,
+			Bound::Excluded(&end) => end,
+			Bound::Unbounded => len
+		};
+		if start > end || end > len {
+			return Err(Error::new_with_code(ErrorCode::InvalidArgument, format!("Slice {start}..{end} is out of bounds for tensor of shape {shape}")));
+		}
+
+		let mut new_shape = shape.clone();
+		new_shape[0] = (end - start) as i64;
+		Ok((new_shape, start * shape[1..].iter().product::<i64>() as usize))
+	}
+
+	/// Returns a view of this tensor with a different shape, without copying its data.
+	///
+	/// The new shape must have the same number of elements as this tensor; at most one dimension may be `-1`, in which
+	/// case its size is inferred. The view can be passed to [`Session::run`](crate::session::Session::run) like any
+	/// other tensor.
+	///
+	/// ```
+	/// # use ort::value::Tensor;
+	/// # fn main() -> ort::Result<()> {
+	/// let tensor = Tensor::from_array(([2usize, 3], vec![1_i32, 2, 3, 4, 5, 6]))?;
+	/// let reshaped = tensor.reshape([3_i64, -1])?;
+	/// let (shape, data) = reshaped.extract_tensor();
+	/// assert_eq!(**shape, [3, 2]);
+	/// assert_eq!(data, [1, 2, 3, 4, 5, 6]);
+	///
+	/// assert!(tensor.reshape([4_usize]).is_err());
+	/// # 	Ok(())
+	/// # }
+	/// ```
+	///
+	/// # Errors
+	/// Returns an error if this value is not a tensor, if the element count of the new shape does not match, or if this
+	/// is a string tensor, whose elements are not stored contiguously.
+	pub fn reshape(&self, shape: impl Into<Shape>) -> Result<ValueRef<'_, Type>> {
+		let shape = self.reshaped(shape.into())?;
+		self.view_at(shape, 0).map(view_ref)
+	}
+
+	/// Returns a mutable view of this tensor with a different shape, without copying its data. Modifying data through
+	/// the view will modify this tensor as well. See [`Tensor::reshape`].
+	pub fn reshape_mut(&mut self, shape: impl Into<Shape>) -> Result<ValueRefMut<'_, Type>> {
+		let shape = self.reshaped(shape.into())?;
+		self.view_at(shape, 0).map(view_ref_mut)
+	}
+
+	/// Returns a view of a range of this tensor along its outermost axis, without copying its data.
+	///
+	/// Only the outermost axis can be sliced without copying, since ONNX Runtime requires tensor data to be contiguous.
+	/// To slice along an inner axis, [`permute`](Tensor::permute) it to the front first (which copies), or slice the
+	/// extracted data manually.
+	///
+	/// ```
+	/// # use ort::value::Tensor;
+	/// # fn main() -> ort::Result<()> {
+	/// let tensor = Tensor::from_array(([3usize, 2], vec![1_i32, 2, 3, 4, 5, 6]))?;
+	/// let slice = tensor.slice(1..)?;
+	/// let (shape, data) = slice.extract_tensor();
+	/// assert_eq!(**shape, [2, 2]);
+	/// assert_eq!(data, [3, 4, 5, 6]);
+	///
+	/// assert!(tensor.slice(1..=3).is_err());
+	/// # 	assert!(tensor.slice(..=usize::MAX).is_err());
+	/// # 	Ok(())
+	/// # }
+	/// ```
+	///
+	/// # Errors
+	/// Returns an error if this value is not a tensor, if the range is out of bounds, if this tensor is a scalar or a
+	/// string tensor, or if this is a tensor of packed 4-bit elements and the slice does not start on a byte boundary.
+	pub fn slice(&self, range: impl RangeBounds<usize>) -> Result<ValueRef<'_, Type>> {
+		let (shape, offset) = self.sliced((range.start_bound(), range.end_bound()))?;
+		self.view_at(shape, offset).map(view_ref)
+	}
+
+	/// Returns a mutable view of a range of this tensor along its outermost axis, without copying its data. Modifying
+	/// data through the view will modify this tensor as well. See [`Tensor::slice`].
+	pub fn slice_mut(&mut self, range: impl RangeBounds<usize>) -> Result<ValueRefMut<'_, Type>> {
+		let (shape, offset) = self.sliced((range.start_bound(), range.end_bound()))?;
+		self.view_at(shape, offset).map(view_ref_mut)
+	}
+
+	/// Returns an iterator over views of each sub-tensor along this tensor's outermost axis, without copying data.
+	///
+	/// ```
+	/// # use ort::value::Tensor;
+	/// # fn main() -> ort::Result<()> {
+	/// let tensor = Tensor::from_array(([2usize, 3], vec![1_i32, 2, 3, 4, 5, 6]))?;
+	/// let rows = tensor.outer_iter().collect::<ort::Result<Vec<_>>>()?;
+	/// assert_eq!(rows.len(), 2);
+	/// assert_eq!(**rows[1].extract_tensor().0, [3]);
+	/// assert_eq!(rows[1].extract_tensor().1, [4, 5, 6]);
+	/// # 	Ok(())
+	/// # }
+	/// ```
+	///
+	/// Each item is an error under the same conditions as [`Tensor::slice`].
+	pub fn outer_iter(&self) -> impl Iterator<Item = Result<ValueRef<'_, Type>>> + '_ {
+		let (error, len, inner_shape) = match self.tensor_dtype() {
+			Ok((_, shape)) => (None, shape.first().map_or(0, |&d| d as usize), Shape::from(shape.get(1..).unwrap_or_default())),
+			Err(e) => (Some(e), 0, Shape::default())
+		};
+		let stride = inner_shape.num_elements();
+		error
+			.map(Err)
+			.into_iter()
+			.chain((0..len).map(move |i| self.view_at(inner_shape.clone(), i * stride).map(view_ref)))
+	}
+
+	/// Returns a copy of this tensor with its axes reordered, such that axis `i` of the result is axis `axes[i]` of
+	/// this tensor.
+	///
+	/// A permuted tensor is not contiguous in memory, so unlike [`Tensor::reshape`] & [`Tensor::slice`], this can't
+	/// return a view and always copies this tensor's data into a new tensor, allocated on the CPU.
+	///
+	/// ```
+	/// # use ort::value::Tensor;
+	/// # fn main() -> ort::Result<()> {
+	/// let tensor = Tensor::from_array(([2usize, 3], vec![1_i32, 2, 3, 4, 5, 6]))?;
+	/// let permuted = tensor.permute(&[1, 0])?;
+	/// let (shape, data) = permuted.extract_tensor();
+	/// assert_eq!(**shape, [3, 2]);
+	/// assert_eq!(data, [1, 4, 2, 5, 3, 6]);
+	/// # 	Ok(())
+	/// # }
+	/// ```
+	///
+	/// # Errors
+	/// Returns an error if this value is not a tensor, if `axes` is not a permutation of this tensor's axes, if this is
+	/// a string tensor or a tensor of packed 4-bit elements, or if this tensor's data is not accessible from the CPU.
+	pub fn permute(&self, axes: &[usize]) -> Result<Value<Type>> {
+		let (ty, shape) = self.tensor_dtype()?;
+		let mut seen = vec![false; shape.len()];
+		if axes.len() != shape.len() || axes.iter().any(|&axis| axis >= shape.len() || core::mem::replace(&mut seen[axis], true)) {
+			return Err(Error::new_with_code(ErrorCode::InvalidArgument, format!("{axes:?} is not a permutation of the axes of a tensor of shape {shape}")));
+		}
+		if ty == TensorElementType::String || ty.is_packed() {
+			return Err(Error::new_with_code(ErrorCode::InvalidArgument, format!("Cannot permute a tensor of type {ty}")));
+		}
+
+		let source = self.raw_bytes()?;
+		let element_size = ty.byte_size(1);
+		let dims: Vec<usize> = axes.iter().map(|&axis| shape[axis] as usize).collect();
+		let source_strides = strides(shape);
+		let strides: Vec<usize> = axes.iter().map(|&axis| source_strides[axis]).collect();
+
+		let mut tensor = DynTensor::new(&Allocator::default(), ty, Shape::from(&*dims))?;
+		if !source.is_empty() {
+			let target = unsafe { slice::from_raw_parts_mut(tensor.data_ptr_mut()?.cast::<u8>(), source.len()) };
+			let mut index = vec![0; dims.len()];
+			for chunk in target.chunks_exact_mut(element_size) {
+				let offset: usize = index.iter().zip(&strides).map(|(i, stride)| i * stride).sum();
+				chunk.copy_from_slice(&source[offset * element_size..][..element_size]);
+				// increment the multi-dimensional index, last axis first
+				for (i, &dim) in index.iter_mut().zip(&dims).rev() {
+					*i += 1;
+					if *i < dim {
+						break;
+					}
+					*i = 0;
+				}
+			}
+		}
+		Ok(unsafe { tensor.transmute_type() })
+	}
+
+	/// Returns a copy of this tensor with its axes reversed, i.e. the transpose of a matrix. See [`Tensor::permute`].
+	pub fn transpose(&self) -> Result<Value<Type>> {
+		let axes: Vec<usize> = (0..self.tensor_dtype()?.1.len()).rev().collect();
+		self.permute(&axes)
+	}
+}
+
+impl<T: PrimitiveTensorElementType + Debug> Tensor<T> {
+	/// Returns the flat index of the element at `index`, or `None` if it's out of bounds.
+	fn element_index(&self, index: &[usize]) -> Option<usize> {
+		let (ty, shape) = self.tensor_dtype().ok()?;
+		if ty.is_packed() {
+			return None;
+		}
+		flat_index(shape, index)
+	}
+
+	/// Returns a reference to the element at the given multi-dimensional index, or `None` if the index is out of
+	/// bounds.
+	///
+	/// Unlike indexing with `tensor[[i, j]]`, this never panics.
+	///
+	/// ```
+	/// # use ort::value::Tensor;
+	/// # fn main() -> ort::Result<()> {
+	/// let tensor = Tensor::from_array(([2usize, 3], vec![1_i32, 2, 3, 4, 5, 6]))?;
+	/// assert_eq!(tensor.get(&[1, 2]), Some(&6));
+	/// assert_eq!(tensor.get(&[2, 0]), None);
+	/// assert_eq!(tensor.get(&[0]), None);
+	/// # 	Ok(())
+	/// # }
+	/// ```
+	///
+	/// Always returns `None` for tensors of packed 4-bit elements, or if the tensor's data is not accessible from the
+	/// CPU.
+	pub fn get(&self, index: &[usize]) -> Option<&T> {
+		let index = self.element_index(index)?;
+		self.try_extract_tensor().ok().map(|(_, data)| &data[index])
+	}
+
+	/// Returns a mutable reference to the element at the given multi-dimensional index, or `None` if the index is out
+	/// of bounds. See [`Tensor::get`].
+	pub fn get_mut(&mut self, index: &[usize]) -> Option<&mut T> {
+		let index = self.element_index(index)?;
+		self.try_extract_tensor_mut().ok().map(|(_, data)| &mut data[index])
+	}
+}
+
+fn view_ref<'v, Type: TensorValueTypeMarker + ?Sized>(view: Value<Type>) -> ValueRef<'v, Type> {
+	let mut view = ValueRef::new(view);
+	// the view aliases the original tensor's data, so it must not outlive the borrow
+	view.upgradable = false;
+	view
+}
+
+fn view_ref_mut<'v, Type: TensorValueTypeMarker + ?Sized>(view: Value<Type>) -> ValueRefMut<'v, Type> {
+	let mut view = ValueRefMut::new(view);
+	view.upgradable = false;
+	view
+}
+
+/// Returns the flat (row-major) index of the element at `index` in a tensor with the given shape, or `None` if it's out
+/// of bounds.
+pub(super) fn flat_index(shape: &[i64], index: &[usize]) -> Option<usize> {
+	if index.len() != shape.len() || index.iter().zip(shape).any(|(&i, &dim)| i as i64 >= dim) {
+		return None;
+	}
+	Some(index.iter().zip(strides(shape)).map(|(i, stride)| i * stride).sum())
+}
+
+/// Returns the row-major strides, in elements, of a tensor with the given shape.
+fn strides(shape: &[i64]) -> Vec<usize> {
+	let mut strides = vec![1; shape.len()];
+	for i in (0..shape.len().saturating_sub(1)).rev() {
+		strides[i] = strides[i + 1] * shape[i + 1] as usize;
+	}
+	strides
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{flat_index, strides};
+
+	#[test]
+	fn test_strides() {
+		assert_eq!(strides(&[2, 3, 4]), [12, 4, 1]);
+		assert_eq!(strides(&[5]), [1]);
+		assert!(strides(&[]).is_empty());
+	}
+
+	#[test]
+	fn test_flat_index() {
+		assert_eq!(flat_index(&[2, 3], &[1, 2]), Some(5));
+		assert_eq!(flat_index(&[2, 3], &[2, 0]), None);
+		assert_eq!(flat_index(&[2, 3], &[1]), None);
+		assert_eq!(flat_index(&[], &[]), Some(0));
+	}
+}
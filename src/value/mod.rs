@@ -14,7 +14,11 @@
 //! # }
 //! ```
 //!
-//! ONNX Runtime also supports [`Sequence`]s and [`Map`]s, though they are less commonly used.
+//! ONNX Runtime also supports [`Sequence`]s, [`Map`]s, [`SparseTensor`]s, and [`Optional`]s, though they are less
+//! commonly used.
+//!
+//! With the `serde` feature, tensors, sequences, and maps implement `Serialize` & `Deserialize`, converting to & from a
+//! self-describing form containing each tensor's shape, element type, and data.
 
 use alloc::{boxed::Box, format, sync::Arc};
 use core::{
@@ -27,18 +31,34 @@ use core::{
 };
 
 mod impl_map;
+mod impl_optional;
 mod impl_sequence;
+#[cfg(feature = "serde")]
+mod impl_serde;
+mod impl_sparse_tensor;
 mod impl_tensor;
+mod tensor_proto;
 pub(crate) mod r#type;
 
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub use self::impl_tensor::TensorSummary;
+pub(crate) use self::impl_tensor::{cast_tensor, tensor_from_array};
+#[cfg(feature = "mmap")]
+pub(crate) use self::tensor_proto::external_data_locations;
 pub use self::{
 	impl_map::{DynMap, DynMapRef, DynMapRefMut, DynMapValueType, Map, MapRef, MapRefMut, MapValueType, MapValueTypeMarker},
+	impl_optional::{DynOptional, Optional, OptionalRef, OptionalRefMut, OptionalValueType},
 	impl_sequence::{
 		DynSequence, DynSequenceRef, DynSequenceRefMut, DynSequenceValueType, Sequence, SequenceRef, SequenceRefMut, SequenceValueType, SequenceValueTypeMarker
 	},
+	impl_sparse_tensor::{
+		BlockSparseParts, CooParts, CsrParts, DynSparseTensor, DynSparseTensorRef, DynSparseTensorRefMut, DynSparseTensorValueType, SparseFormat, SparseTensor,
+		SparseTensorRef, SparseTensorRefMut, SparseTensorValueType, SparseTensorValueTypeMarker
+	},
 	impl_tensor::{
-		DynTensor, DynTensorRef, DynTensorRefMut, DynTensorValueType, OwnedTensorArrayData, Tensor, TensorArrayData, TensorArrayDataMut, TensorArrayDataParts,
-		TensorRef, TensorRefMut, TensorValueType, TensorValueTypeMarker, ToShape
+		DynTensor, DynTensorRef, DynTensorRefMut, DynTensorValueType, OwnedTensorArrayData, StringTensorView, Tensor, TensorArrayData, TensorArrayDataMut,
+		TensorArrayDataParts, TensorRef, TensorRefMut, TensorValueType, TensorValueTypeMarker, ToShape
 	},
 	r#type::ValueType
 };
@@ -296,6 +316,9 @@ impl MapValueTypeMarker for DynValueTypeMarker {
 impl SequenceValueTypeMarker for DynValueTypeMarker {
 	private_impl!();
 }
+impl SparseTensorValueTypeMarker for DynValueTypeMarker {
+	private_impl!();
+}
 impl TensorValueTypeMarker for DynValueTypeMarker {
 	private_impl!();
 }
@@ -319,38 +342,57 @@ impl<Type: ValueTypeMarker + ?Sized> Value<Type> {
 	///
 	/// - `ptr` must be a valid pointer to an [`ort_sys::OrtValue`].
 	/// - `session` must be `Some` for values returned from a session.
-	#[must_use]
-	pub unsafe fn from_ptr(ptr: NonNull<ort_sys::OrtValue>, session: Option<Arc<SharedSessionInner>>) -> Value<Type> {
-		let mut typeinfo_ptr = ptr::null_mut();
-		ortsys![unsafe GetTypeInfo(ptr.as_ptr(), &mut typeinfo_ptr).expect("infallible")];
-		Value {
+	///
+	/// # Errors
+	///
+	/// Returns an error if the value's type cannot be determined (e.g. if `ptr` is an empty optional value, whose type
+	/// ONNX Runtime does not report), in which case `ptr` is released.
+	pub unsafe fn from_ptr(ptr: NonNull<ort_sys::OrtValue>, session: Option<Arc<SharedSessionInner>>) -> Result<Value<Type>> {
+		unsafe { Self::from_ptr_with_type(ptr, session, None) }
+	}
+
+	/// A variant of [`Value::from_ptr`] for values belonging to a session input/output of type `expected`, which is
+	/// used to determine the type of empty optionals, since ONNX Runtime may not report one.
+	///
+	/// If the value's type cannot be determined, it is released and an error is returned.
+	pub(crate) unsafe fn from_ptr_with_type(
+		ptr: NonNull<ort_sys::OrtValue>,
+		session: Option<Arc<SharedSessionInner>>,
+		expected: Option<&ValueType>
+	) -> Result<Value<Type>> {
+		let (dtype, memory_info) = match describe_value(ptr, expected) {
+			Ok(description) => description,
+			Err(e) => {
+				ortsys![unsafe ReleaseValue(ptr.as_ptr())];
+				return Err(e);
+			}
+		};
+		Ok(Value {
 			inner: Arc::new(ValueInner {
 				ptr,
-				memory_info: MemoryInfo::from_value(ptr.as_ptr()),
-				dtype: ValueType::from_type_info(typeinfo_ptr),
+				memory_info,
+				dtype,
 				drop: true,
 				_backing: session.map(|v| Box::new(v) as Box<dyn Any>)
 			}),
 			_markers: PhantomData
-		}
+		})
 	}
 
 	/// A variant of [`Value::from_ptr`] that does not release the value upon dropping. Used in operator kernel
 	/// contexts.
-	#[must_use]
-	pub(crate) unsafe fn from_ptr_nodrop(ptr: NonNull<ort_sys::OrtValue>, session: Option<Arc<SharedSessionInner>>) -> Value<Type> {
-		let mut typeinfo_ptr = ptr::null_mut();
-		ortsys![unsafe GetTypeInfo(ptr.as_ptr(), &mut typeinfo_ptr).expect("infallible")];
-		Value {
+	pub(crate) unsafe fn from_ptr_nodrop(ptr: NonNull<ort_sys::OrtValue>, session: Option<Arc<SharedSessionInner>>) -> Result<Value<Type>> {
+		let (dtype, memory_info) = describe_value(ptr, None)?;
+		Ok(Value {
 			inner: Arc::new(ValueInner {
 				ptr,
-				memory_info: MemoryInfo::from_value(ptr.as_ptr()),
-				dtype: ValueType::from_type_info(typeinfo_ptr),
+				memory_info,
+				dtype,
 				drop: false,
 				_backing: session.map(|v| Box::new(v) as Box<dyn Any>)
 			}),
 			_markers: PhantomData
-		}
+		})
 	}
 
 	/// Create a view of this value's data.
@@ -381,6 +423,35 @@ impl<Type: ValueTypeMarker + ?Sized> Value<Type> {
 	}
 }
 
+/// Determines the type & memory info of an `OrtValue`.
+fn describe_value(ptr: NonNull<ort_sys::OrtValue>, expected: Option<&ValueType>) -> Result<(ValueType, Option<MemoryInfo>)> {
+	let mut has_value = 0;
+	ortsys![unsafe HasValue(ptr.as_ptr(), &mut has_value).expect("infallible")];
+	let mut typeinfo_ptr = ptr::null_mut();
+	ortsys![unsafe GetTypeInfo(ptr.as_ptr(), &mut typeinfo_ptr).expect("infallible")];
+	if has_value != 0 {
+		return Ok((ValueType::from_type_info(typeinfo_ptr), MemoryInfo::from_value(ptr.as_ptr())));
+	}
+
+	// This is an empty optional. ONNX Runtime reports the type of the value it would contain, if it reports a type at all.
+	let dtype = match expected {
+		Some(expected @ ValueType::Optional(_)) => {
+			if !typeinfo_ptr.is_null() {
+				ortsys![unsafe ReleaseTypeInfo(typeinfo_ptr)];
+			}
+			expected.clone()
+		}
+		_ if typeinfo_ptr.is_null() => {
+			return Err(Error::new_with_code(ErrorCode::InvalidArgument, "Could not determine the type of an empty optional value"));
+		}
+		_ => match ValueType::from_type_info(typeinfo_ptr) {
+			ty @ ValueType::Optional(_) => ty,
+			ty => ValueType::Optional(Box::new(ty))
+		}
+	};
+	Ok((dtype, None))
+}
+
 impl Value<DynValueTypeMarker> {
 	/// Returns `true` if this value is a tensor, or `false` if it is another type (sequence, map).
 	///
@@ -0,0 +1,556 @@
+//! Conversions between tensors & serialized ONNX `TensorProto` messages, as used by the `.pb` files in ONNX test data
+//! sets.
+
+use alloc::{
+	format,
+	string::{String, ToString},
+	vec::Vec
+};
+use core::{ptr, slice, str};
+
+use super::{DynTensor, Tensor, TensorValueTypeMarker, Value, ValueType};
+use crate::{
+	error::{Error, ErrorCode, Result},
+	memory::Allocator,
+	tensor::TensorElementType
+};
+
+// Field numbers of `onnx.TensorProto`.
+const DIMS: u32 = 1;
+const DATA_TYPE: u32 = 2;
+const SEGMENT: u32 = 3;
+const FLOAT_DATA: u32 = 4;
+const INT32_DATA: u32 = 5;
+const STRING_DATA: u32 = 6;
+const INT64_DATA: u32 = 7;
+const NAME: u32 = 8;
+const RAW_DATA: u32 = 9;
+const DOUBLE_DATA: u32 = 10;
+const UINT64_DATA: u32 = 11;
+const DATA_LOCATION: u32 = 14;
+
+/// `TensorProto.DataLocation.EXTERNAL`
+const DATA_LOCATION_EXTERNAL: u64 = 1;
+
+/// A field read from a protobuf message.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Field<'b> {
+	Varint(u64),
+	Fixed64([u8; 8]),
+	Bytes(&'b [u8]),
+	Fixed32([u8; 4])
+}
+
+/// A minimal reader for the protobuf wire format.
+struct Reader<'b> {
+	buf: &'b [u8]
+}
+
+impl<'b> Reader<'b> {
+	fn new(buf: &'b [u8]) -> Self {
+		Self { buf }
+	}
+
+	fn varint(&mut self) -> Result<u64> {
+		let mut value = 0;
+		for (i, &byte) in self.buf.iter().enumerate().take(10) {
+			value |= u64::from(byte & 0x7F) << (i * 7);
+			if byte & 0x80 == 0 {
+				self.buf = &self.buf[i + 1..];
+				return Ok(value);
+			}
+		}
+		Err(malformed("invalid varint"))
+	}
+
+	fn take<const N: usize>(&mut self) -> Result<[u8; N]> {
+		let bytes = self.bytes(N)?;
+		Ok(bytes.try_into().expect("slice should have length N"))
+	}
+
+	fn bytes(&mut self, len: usize) -> Result<&'b [u8]> {
+		if self.buf.len() < len {
+			return Err(malformed("unexpected end of message"));
+		}
+		let (bytes, rest) = self.buf.split_at(len);
+		self.buf = rest;
+		Ok(bytes)
+	}
+
+	/// Reads the next field, returning its field number & value, or `None` at the end of the message.
+	fn field(&mut self) -> Result<Option<(u32, Field<'b>)>> {
+		if self.buf.is_empty() {
+			return Ok(None);
+		}
+		let key = self.varint()?;
+		let field = match key & 0x7 {
+			0 => Field::Varint(self.varint()?),
+			1 => Field::Fixed64(self.take()?),
+			2 => {
+				let len = usize::try_from(self.varint()?).map_err(|_| malformed("length out of range"))?;
+				Field::Bytes(self.bytes(len)?)
+			}
+			5 => Field::Fixed32(self.take()?),
+			wire_type => return Err(malformed(&format!("unsupported wire type {wire_type}")))
+		};
+		Ok(Some(((key >> 3) as u32, field)))
+	}
+}
+
+fn malformed(reason: &str) -> Error {
+	Error::new_with_code(ErrorCode::InvalidProtobuf, format!("Malformed TensorProto: {reason}"))
+}
+
+/// Appends the values of a repeated varint field, which may be packed or unpacked.
+fn read_varints(field: Field<'_>, out: &mut Vec<u64>) -> Result<()> {
+	match field {
+		Field::Varint(value) => out.push(value),
+		Field::Bytes(packed) => {
+			let mut reader = Reader::new(packed);
+			while !reader.buf.is_empty() {
+				out.push(reader.varint()?);
+			}
+		}
+		_ => return Err(malformed("expected varint field"))
+	}
+	Ok(())
+}
+
+/// Appends the values of a repeated fixed-width field, which may be packed or unpacked.
+fn read_fixed<const N: usize>(field: Field<'_>, out: &mut Vec<[u8; N]>) -> Result<()> {
+	match field {
+		Field::Fixed32(bytes) if N == 4 => out.push(bytes[..].try_into().expect("N is 4")),
+		Field::Fixed64(bytes) if N == 8 => out.push(bytes[..].try_into().expect("N is 8")),
+		Field::Bytes(packed) if packed.len() % N == 0 => {
+			out.extend(
+				packed
+					.chunks_exact(N)
+					.map(|chunk| <[u8; N]>::try_from(chunk).expect("chunk should have length N"))
+			);
+		}
+		_ => return Err(malformed("expected fixed-width field"))
+	}
+	Ok(())
+}
+
+/// The fields of a `TensorProto` relevant to reconstructing a tensor.
+#[derive(Debug, Default)]
+struct TensorProto<'b> {
+	name: &'b str,
+	dims: Vec<i64>,
+	data_type: i32,
+	raw_data: Option<&'b [u8]>,
+	float_data: Vec<[u8; 4]>,
+	int32_data: Vec<u64>,
+	int64_data: Vec<u64>,
+	double_data: Vec<[u8; 8]>,
+	uint64_data: Vec<u64>,
+	string_data: Vec<&'b [u8]>
+}
+
+impl<'b> TensorProto<'b> {
+	fn parse(buf: &'b [u8]) -> Result<Self> {
+		let mut proto = TensorProto::default();
+		let mut dims = Vec::new();
+		let mut reader = Reader::new(buf);
+		while let Some((number, field)) = reader.field()? {
+			match (number, field) {
+				(DIMS, field) => read_varints(field, &mut dims)?,
+				(DATA_TYPE, Field::Varint(data_type)) => proto.data_type = data_type as i32,
+				(SEGMENT, _) => return Err(Error::new_with_code(ErrorCode::NotImplemented, "Segmented TensorProtos are not supported")),
+				(FLOAT_DATA, field) => read_fixed(field, &mut proto.float_data)?,
+				(INT32_DATA, field) => read_varints(field, &mut proto.int32_data)?,
+				(STRING_DATA, Field::Bytes(string)) => proto.string_data.push(string),
+				(INT64_DATA, field) => read_varints(field, &mut proto.int64_data)?,
+				(NAME, Field::Bytes(name)) => proto.name = str::from_utf8(name).map_err(|_| malformed("name is not valid UTF-8"))?,
+				(RAW_DATA, Field::Bytes(raw_data)) => proto.raw_data = Some(raw_data),
+				(DOUBLE_DATA, field) => read_fixed(field, &mut proto.double_data)?,
+				(UINT64_DATA, field) => read_varints(field, &mut proto.uint64_data)?,
+				(DATA_LOCATION, Field::Varint(DATA_LOCATION_EXTERNAL)) => {
+					return Err(Error::new_with_code(ErrorCode::NotImplemented, "TensorProtos with external data are not supported"));
+				}
+				(DATA_TYPE | STRING_DATA | NAME | RAW_DATA, _) => return Err(malformed(&format!("unexpected wire type for field {number}"))),
+				_ => {}
+			}
+		}
+		proto.dims = dims.into_iter().map(|dim| dim as i64).collect();
+		Ok(proto)
+	}
+
+	/// Returns the little-endian bytes of this tensor's data, which may be stored in `raw_data` or one of the typed
+	/// fields.
+	fn data(&self, ty: TensorElementType) -> Vec<u8> {
+		if let Some(raw_data) = self.raw_data {
+			return raw_data.to_vec();
+		}
+
+		fn narrow<const N: usize>(values: &[u64]) -> Vec<u8> {
+			// `int32_data` holds 8- & 16-bit types as (sign-extended) `int32`s; truncating keeps the relevant bits.
+			values.iter().flat_map(|value| value.to_le_bytes().into_iter().take(N)).collect()
+		}
+		match ty {
+			TensorElementType::Float32 | TensorElementType::Complex64 => self.float_data.concat(),
+			TensorElementType::Float64 | TensorElementType::Complex128 => self.double_data.concat(),
+			TensorElementType::Int32 => narrow::<4>(&self.int32_data),
+			TensorElementType::Int16 | TensorElementType::Uint16 | TensorElementType::Float16 | TensorElementType::Bfloat16 => narrow::<2>(&self.int32_data),
+			TensorElementType::Int8
+			| TensorElementType::Uint8
+			| TensorElementType::Bool
+			| TensorElementType::Float8E4M3FN
+			| TensorElementType::Float8E4M3FNUZ
+			| TensorElementType::Float8E5M2
+			| TensorElementType::Float8E5M2FNUZ
+			// 4-bit types are stored packed, with two elements per `int32`
+			| TensorElementType::Int4
+			| TensorElementType::Uint4 => narrow::<1>(&self.int32_data),
+			TensorElementType::Int64 => narrow::<8>(&self.int64_data),
+			TensorElementType::Uint32 => narrow::<4>(&self.uint64_data),
+			TensorElementType::Uint64 => narrow::<8>(&self.uint64_data),
+			TensorElementType::String | TensorElementType::Undefined => unreachable!()
+		}
+	}
+
+	fn into_tensor(self) -> Result<DynTensor> {
+		let ty = element_type(self.data_type)
+			.ok_or_else(|| Error::new_with_code(ErrorCode::NotImplemented, format!("Unsupported TensorProto data type {}", self.data_type)))?;
+		if ty == TensorElementType::String {
+			let strings = self
+				.string_data
+				.iter()
+				.map(|string| String::from_utf8(string.to_vec()))
+				.collect::<Result<Vec<_>, _>>()
+				.map_err(|_| Error::new_with_code(ErrorCode::InvalidArgument, "String tensors must contain valid UTF-8"))?;
+			return Tensor::from_string_array((self.dims, &*strings)).map(Tensor::upcast);
+		}
+
+		let bytes = self.data(ty);
+		let mut tensor = DynTensor::new(&Allocator::default(), ty, self.dims)?;
+		let expected_len = ty.byte_size(tensor.shape().num_elements());
+		if bytes.len() != expected_len {
+			return Err(Error::new_with_code(
+				ErrorCode::InvalidArgument,
+				format!("TensorProto of type {ty} and shape {} has {} bytes of data; expected {expected_len}", tensor.shape(), bytes.len())
+			));
+		}
+		if expected_len > 0 {
+			unsafe { ptr::copy_nonoverlapping(bytes.as_ptr(), tensor.data_ptr_mut()?.cast::<u8>(), expected_len) };
+		}
+		Ok(tensor)
+	}
+}
+
+/// Maps an `onnx.TensorProto.DataType` to a [`TensorElementType`].
+fn element_type(data_type: i32) -> Option<TensorElementType> {
+	Some(match data_type {
+		1 => TensorElementType::Float32,
+		2 => TensorElementType::Uint8,
+		3 => TensorElementType::Int8,
+		4 => TensorElementType::Uint16,
+		5 => TensorElementType::Int16,
+		6 => TensorElementType::Int32,
+		7 => TensorElementType::Int64,
+		8 => TensorElementType::String,
+		9 => TensorElementType::Bool,
+		10 => TensorElementType::Float16,
+		11 => TensorElementType::Float64,
+		12 => TensorElementType::Uint32,
+		13 => TensorElementType::Uint64,
+		14 => TensorElementType::Complex64,
+		15 => TensorElementType::Complex128,
+		16 => TensorElementType::Bfloat16,
+		17 => TensorElementType::Float8E4M3FN,
+		18 => TensorElementType::Float8E4M3FNUZ,
+		19 => TensorElementType::Float8E5M2,
+		20 => TensorElementType::Float8E5M2FNUZ,
+		21 => TensorElementType::Uint4,
+		22 => TensorElementType::Int4,
+		_ => return None
+	})
+}
+
+/// Maps a [`TensorElementType`] to an `onnx.TensorProto.DataType`.
+fn data_type(ty: TensorElementType) -> i32 {
+	// `ONNXTensorElementDataType` uses the same values as `TensorProto.DataType`
+	ort_sys::ONNXTensorElementDataType::from(ty) as i32
+}
+
+/// The messages of a serialized `ModelProto` which may (transitively) contain `TensorProto`s.
+#[cfg(feature = "mmap")]
+#[derive(Debug, Clone, Copy)]
+enum ModelMessage {
+	Model,
+	Function,
+	Graph,
+	Node,
+	Attribute,
+	SparseTensor,
+	Tensor
+}
+
+/// Returns the paths of the external data files referenced by the tensors of a serialized ONNX `ModelProto`, in
+/// order of first appearance. This includes initializers, constant attributes, and tensors in subgraphs & functions.
+#[cfg(feature = "mmap")]
+pub(crate) fn external_data_locations(model: &[u8]) -> Result<Vec<String>> {
+	fn visit(buf: &[u8], message: ModelMessage, locations: &mut Vec<String>) -> Result<()> {
+		let mut reader = Reader::new(buf);
+		while let Some((number, field)) = reader.field()? {
+			let Field::Bytes(bytes) = field else {
+				continue;
+			};
+			let child = match (message, number) {
+				// `ModelProto.graph`, `ModelProto.functions`
+				(ModelMessage::Model, 7) => ModelMessage::Graph,
+				(ModelMessage::Model, 25) => ModelMessage::Function,
+				// `FunctionProto.node`, `GraphProto.node`
+				(ModelMessage::Function, 7) | (ModelMessage::Graph, 1) => ModelMessage::Node,
+				// `GraphProto.initializer`, `AttributeProto.{t, tensors}`, `SparseTensorProto.{values, indices}`
+				(ModelMessage::Graph, 5) | (ModelMessage::Attribute, 5 | 10) | (ModelMessage::SparseTensor, 1 | 2) => ModelMessage::Tensor,
+				// `GraphProto.sparse_initializer`, `AttributeProto.{sparse_tensor, sparse_tensors}`
+				(ModelMessage::Graph, 15) | (ModelMessage::Attribute, 22 | 23) => ModelMessage::SparseTensor,
+				// `NodeProto.attribute`
+				(ModelMessage::Node, 5) => ModelMessage::Attribute,
+				// `AttributeProto.{g, graphs}`
+				(ModelMessage::Attribute, 6 | 11) => ModelMessage::Graph,
+				// `TensorProto.external_data`, a `StringStringEntryProto`
+				(ModelMessage::Tensor, 13) => {
+					let (mut key, mut value) = (None, None);
+					let mut entry = Reader::new(bytes);
+					while let Some((number, field)) = entry.field()? {
+						match (number, field) {
+							(1, Field::Bytes(bytes)) => key = Some(bytes),
+							(2, Field::Bytes(bytes)) => value = Some(bytes),
+							_ => {}
+						}
+					}
+					if let (Some(b"location"), Some(location)) = (key, value) {
+						let location = str::from_utf8(location).map_err(|_| malformed("external data location is not valid UTF-8"))?;
+						if !locations.iter().any(|l| l == location) {
+							locations.push(location.to_string());
+						}
+					}
+					continue;
+				}
+				_ => continue
+			};
+			visit(bytes, child, locations)?;
+		}
+		Ok(())
+	}
+
+	let mut locations = Vec::new();
+	visit(model, ModelMessage::Model, &mut locations)?;
+	Ok(locations)
+}
+
+/// A minimal writer for the protobuf wire format.
+#[derive(Default)]
+struct Writer {
+	buf: Vec<u8>
+}
+
+impl Writer {
+	fn varint(&mut self, mut value: u64) {
+		while value >= 0x80 {
+			self.buf.push((value as u8) | 0x80);
+			value >>= 7;
+		}
+		self.buf.push(value as u8);
+	}
+
+	fn varint_field(&mut self, number: u32, value: u64) {
+		self.varint(u64::from(number) << 3);
+		self.varint(value);
+	}
+
+	fn bytes_field(&mut self, number: u32, bytes: &[u8]) {
+		self.varint(u64::from(number) << 3 | 2);
+		self.varint(bytes.len() as u64);
+		self.buf.extend_from_slice(bytes);
+	}
+}
+
+/// Encodes a `TensorProto` storing non-string data in `raw_data`.
+fn encode(name: &str, dims: &[i64], ty: TensorElementType, raw_data: Option<&[u8]>, strings: &[String]) -> Vec<u8> {
+	let mut writer = Writer::default();
+	for &dim in dims {
+		writer.varint_field(DIMS, dim as u64);
+	}
+	writer.varint_field(DATA_TYPE, data_type(ty) as u64);
+	for string in strings {
+		writer.bytes_field(STRING_DATA, string.as_bytes());
+	}
+	if !name.is_empty() {
+		writer.bytes_field(NAME, name.as_bytes());
+	}
+	if let Some(raw_data) = raw_data {
+		writer.bytes_field(RAW_DATA, raw_data);
+	}
+	writer.buf
+}
+
+impl DynTensor {
+	/// Creates a tensor from a serialized ONNX `TensorProto`, such as the `input_*.pb` & `output_*.pb` files found in
+	/// ONNX test data sets. The tensor is allocated on the CPU with the default [`Allocator`].
+	///
+	/// All tensor element types are supported, with data stored either in `raw_data` or in the typed data fields.
+	/// `TensorProto`s which reference external data are not supported.
+	///
+	/// ```
+	/// # use ort::value::{Tensor, Value};
+	/// # fn main() -> ort::Result<()> {
+	/// let tensor = Tensor::from_array(([2usize, 2], vec![1.0_f32, 2.0, 3.0, 4.0]))?;
+	/// let proto = tensor.to_tensor_proto()?;
+	///
+	/// let decoded = Value::from_tensor_proto(&proto)?;
+	/// assert_eq!(**decoded.shape(), [2, 2]);
+	/// assert_eq!(decoded.try_extract_tensor::<f32>()?.1, [1.0, 2.0, 3.0, 4.0]);
+	/// # 	Ok(())
+	/// # }
+	/// ```
+	pub fn from_tensor_proto(bytes: &[u8]) -> Result<DynTensor> {
+		TensorProto::parse(bytes)?.into_tensor()
+	}
+
+	/// Creates a tensor from a serialized ONNX `TensorProto`, returning the tensor along with the `TensorProto`'s name
+	/// (which may be empty). See [`DynTensor::from_tensor_proto`].
+	pub fn from_named_tensor_proto(bytes: &[u8]) -> Result<(String, DynTensor)> {
+		let proto = TensorProto::parse(bytes)?;
+		let name = proto.name.to_string();
+		Ok((name, proto.into_tensor()?))
+	}
+}
+
+impl<Type: TensorValueTypeMarker + ?Sized> Value<Type> {
+	/// Serializes this tensor to an ONNX `TensorProto` with no name. Data is stored in `raw_data`, except for string
+	/// tensors, which use `string_data`.
+	///
+	/// # Errors
+	/// Returns an error if this value is not a tensor, or if its data is not accessible from the CPU.
+	pub fn to_tensor_proto(&self) -> Result<Vec<u8>> {
+		self.to_named_tensor_proto("")
+	}
+
+	/// Serializes this tensor to an ONNX `TensorProto` with the given name. See [`Value::to_tensor_proto`].
+	pub fn to_named_tensor_proto(&self, name: &str) -> Result<Vec<u8>> {
+		let ValueType::Tensor { ty, shape, .. } = self.dtype() else {
+			return Err(Error::new_with_code(ErrorCode::InvalidArgument, format!("Cannot serialize {} as a TensorProto", self.dtype())));
+		};
+		if *ty == TensorElementType::String {
+			let (_, strings) = self.try_extract_strings()?;
+			return Ok(encode(name, shape, *ty, None, &strings));
+		}
+
+		let raw_data = self.raw_bytes()?;
+		Ok(encode(name, shape, *ty, Some(raw_data), &[]))
+	}
+
+	/// Returns the raw bytes of this (non-string) tensor's data.
+	pub(crate) fn raw_bytes(&self) -> Result<&[u8]> {
+		let ValueType::Tensor { ty, shape, .. } = self.dtype() else {
+			return Err(Error::new_with_code(ErrorCode::InvalidArgument, format!("Cannot access data of value of type {} as a tensor", self.dtype())));
+		};
+		let memory_info = self.memory_info();
+		if !memory_info.is_cpu_accessible() {
+			return Err(Error::new(format!(
+				"Cannot access data of tensor on device `{}`, which is not CPU accessible",
+				memory_info.allocation_device().as_str()
+			)));
+		}
+		let len = ty.byte_size(shape.num_elements());
+		Ok(if len > 0 { unsafe { slice::from_raw_parts(self.data_ptr()?.cast::<u8>(), len) } } else { &[] })
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use alloc::{string::String, vec};
+
+	use super::{Reader, TensorProto, encode};
+	use crate::{
+		error::ErrorCode,
+		tensor::TensorElementType,
+		value::{Sequence, Tensor}
+	};
+
+	#[test]
+	fn test_parse_typed_fields() -> crate::Result<()> {
+		// dims: [2, 3] (unpacked), data_type: INT8, int32_data: [-1, 2] (packed), name: "x"
+		let mut bytes = vec![0x08, 0x02, 0x08, 0x03, 0x10, 0x03, 0x2A, 0x0B];
+		bytes.extend_from_slice(&[0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x01, 0x02]);
+		bytes.extend_from_slice(&[0x42, 0x01, b'x']);
+		let proto = TensorProto::parse(&bytes)?;
+		assert_eq!(proto.name, "x");
+		assert_eq!(proto.dims, [2, 3]);
+		assert_eq!(proto.data(TensorElementType::Int8), [0xFF, 0x02]);
+
+		// float_data: [1.0] (unpacked), data_type: FLOAT
+		let bytes = [0x25, 0x00, 0x00, 0x80, 0x3F, 0x10, 0x01];
+		let proto = TensorProto::parse(&bytes)?;
+		assert_eq!(proto.data(TensorElementType::Float32), 1.0_f32.to_le_bytes());
+
+		assert!(TensorProto::parse(&[0x08]).is_err());
+		assert!(TensorProto::parse(&[0x4A, 0x05, 0x00]).is_err());
+		Ok(())
+	}
+
+	#[test]
+	fn test_encode() -> crate::Result<()> {
+		let raw_data = [1, 0, 0, 0, 2, 0, 0, 0];
+		let bytes = encode("y", &[2], TensorElementType::Int32, Some(&raw_data), &[]);
+		let proto = TensorProto::parse(&bytes)?;
+		assert_eq!((proto.name, proto.data_type, &*proto.dims, proto.raw_data), ("y", 6, &[2_i64][..], Some(&raw_data[..])));
+
+		let bytes = encode("", &[1], TensorElementType::String, None, &[String::from("hello")]);
+		let proto = TensorProto::parse(&bytes)?;
+		assert_eq!(proto.string_data, [b"hello"]);
+
+		let mut reader = Reader::new(&[0xAC, 0x02]);
+		assert_eq!(reader.varint()?, 300);
+		Ok(())
+	}
+
+	#[test]
+	fn test_raw_bytes_of_non_tensor() -> crate::Result<()> {
+		let sequence = Sequence::new([Tensor::from_array(([1_usize], vec![1_i32]))?])?.into_dyn();
+		assert!(matches!(sequence.raw_bytes(), Err(e) if e.code() == ErrorCode::InvalidArgument));
+		assert!(sequence.to_tensor_proto().is_err());
+		Ok(())
+	}
+
+	#[test]
+	#[cfg(feature = "mmap")]
+	fn test_external_data_locations() -> crate::Result<()> {
+		use super::{Writer, external_data_locations};
+
+		fn message(fields: &[(u32, &[u8])]) -> Vec<u8> {
+			let mut writer = Writer::default();
+			for (number, bytes) in fields {
+				writer.bytes_field(*number, bytes);
+			}
+			writer.buf
+		}
+		fn tensor(location: &str) -> Vec<u8> {
+			let location = message(&[(1, b"location"), (2, location.as_bytes())]);
+			let offset = message(&[(1, b"offset"), (2, b"0")]);
+			message(&[(8, b"w"), (13, &location), (13, &offset)])
+		}
+
+		// an initializer, and a `Constant` node in the `then_branch` of an `If` node
+		let constant = message(&[(5, &message(&[(1, b"value"), (5, &tensor("nested/weights.bin"))]))]);
+		let subgraph = message(&[(1, &constant), (5, &tensor("weights.bin"))]);
+		let node = message(&[(4, b"If"), (5, &message(&[(1, b"then_branch"), (6, &subgraph)]))]);
+		let graph = message(&[(1, &node), (5, &tensor("weights.bin")), (2, b"location")]);
+		let mut model = Writer::default();
+		model.varint_field(1, 8);
+		model.bytes_field(7, &graph);
+		assert_eq!(external_data_locations(&model.buf)?, ["nested/weights.bin", "weights.bin"]);
+
+		// The string "location" outside of a tensor's external data is ignored.
+		let mut model = Writer::default();
+		model.bytes_field(2, &message(&[(1, b"location"), (2, b"weights.bin")]));
+		assert!(external_data_locations(&model.buf)?.is_empty());
+
+		assert!(external_data_locations(&[0x3A, 0x05, 0x00]).is_err());
+		Ok(())
+	}
+}
@@ -82,7 +82,16 @@ pub enum ValueType {
 		value: TensorElementType
 	},
 	/// An optional value, which may or may not contain a [`Value`][super::Value].
-	Optional(Box<ValueType>)
+	Optional(Box<ValueType>),
+	/// A sparse tensor; see [`SparseTensor`][super::SparseTensor].
+	SparseTensor {
+		/// Element type of the tensor.
+		ty: TensorElementType,
+		/// Shape of the dense tensor this sparse tensor represents. Like [`ValueType::Tensor`], unknown dimensions are
+		/// `-1`.
+		shape: Shape,
+		dimension_symbols: SymbolicDimensions
+	}
 }
 
 impl ValueType {
@@ -90,11 +99,19 @@ impl ValueType {
 		let mut ty: ort_sys::ONNXType = ort_sys::ONNXType::ONNX_TYPE_UNKNOWN;
 		ortsys![unsafe GetOnnxTypeFromTypeInfo(typeinfo_ptr, &mut ty).expect("infallible")];
 		let io_type = match ty {
-			ort_sys::ONNXType::ONNX_TYPE_TENSOR | ort_sys::ONNXType::ONNX_TYPE_SPARSETENSOR => {
+			ort_sys::ONNXType::ONNX_TYPE_TENSOR => {
 				let mut info_ptr: *const ort_sys::OrtTensorTypeAndShapeInfo = ptr::null_mut();
 				ortsys![unsafe CastTypeInfoToTensorInfo(typeinfo_ptr, &mut info_ptr).expect("infallible")];
 				unsafe { extract_data_type_from_tensor_info(info_ptr) }
 			}
+			ort_sys::ONNXType::ONNX_TYPE_SPARSETENSOR => {
+				let mut info_ptr: *const ort_sys::OrtTensorTypeAndShapeInfo = ptr::null_mut();
+				ortsys![unsafe CastTypeInfoToTensorInfo(typeinfo_ptr, &mut info_ptr).expect("infallible")];
+				match unsafe { extract_data_type_from_tensor_info(info_ptr) } {
+					ValueType::Tensor { ty, shape, dimension_symbols } => ValueType::SparseTensor { ty, shape, dimension_symbols },
+					_ => unreachable!()
+				}
+			}
 			ort_sys::ONNXType::ONNX_TYPE_SEQUENCE => {
 				let mut info_ptr: *const ort_sys::OrtSequenceTypeInfo = ptr::null_mut();
 				ortsys![unsafe CastTypeInfoToSequenceTypeInfo(typeinfo_ptr, &mut info_ptr).expect("infallible")];
@@ -217,30 +234,25 @@ impl ValueType {
 	pub fn is_map(&self) -> bool {
 		matches!(self, ValueType::Map { .. })
 	}
+
+	/// Returns `true` if this value type is a sparse tensor.
+	#[inline]
+	#[must_use]
+	pub fn is_sparse_tensor(&self) -> bool {
+		matches!(self, ValueType::SparseTensor { .. })
+	}
 }
 
 impl fmt::Display for ValueType {
 	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
 		match self {
 			ValueType::Tensor { ty, shape, dimension_symbols } => {
-				write!(f, "Tensor<{ty}>(")?;
-				for (i, dimension) in shape.iter().copied().enumerate() {
-					if dimension == -1 {
-						let sym = &dimension_symbols[i];
-						if sym.is_empty() {
-							f.write_str("dyn")?;
-						} else {
-							f.write_str(sym)?;
-						}
-					} else {
-						dimension.fmt(f)?;
-					}
-					if i != shape.len() - 1 {
-						f.write_str(", ")?;
-					}
-				}
-				f.write_str(")")?;
-				Ok(())
+				write!(f, "Tensor<{ty}>")?;
+				fmt_dimensions(f, shape, dimension_symbols)
+			}
+			ValueType::SparseTensor { ty, shape, dimension_symbols } => {
+				write!(f, "SparseTensor<{ty}>")?;
+				fmt_dimensions(f, shape, dimension_symbols)
 			}
 			ValueType::Map { key, value } => write!(f, "Map<{key}, {value}>"),
 			ValueType::Sequence(inner) => write!(f, "Sequence<{inner}>"),
@@ -249,6 +261,26 @@ impl fmt::Display for ValueType {
 	}
 }
 
+fn fmt_dimensions(f: &mut fmt::Formatter<'_>, shape: &Shape, dimension_symbols: &SymbolicDimensions) -> fmt::Result {
+	f.write_str("(")?;
+	for (i, dimension) in shape.iter().copied().enumerate() {
+		if dimension == -1 {
+			let sym = &dimension_symbols[i];
+			if sym.is_empty() {
+				f.write_str("dyn")?;
+			} else {
+				f.write_str(sym)?;
+			}
+		} else {
+			fmt::Display::fmt(&dimension, f)?;
+		}
+		if i != shape.len() - 1 {
+			f.write_str(", ")?;
+		}
+	}
+	f.write_str(")")
+}
+
 pub(crate) unsafe fn extract_data_type_from_tensor_info(info_ptr: *const ort_sys::OrtTensorTypeAndShapeInfo) -> ValueType {
 	let mut type_sys = ort_sys::ONNXTensorElementDataType::ONNX_TENSOR_ELEMENT_DATA_TYPE_UNDEFINED;
 	ortsys![unsafe GetTensorElementType(info_ptr, &mut type_sys).expect("infallible")];
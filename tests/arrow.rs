@@ -0,0 +1,67 @@
+#![cfg(feature = "arrow")]
+
+use std::{path::Path, sync::Arc};
+
+use arrow_array::{Array, Float32Array, Int64Array, RecordBatch, StringArray, cast::AsArray, types::Float32Type};
+use ort::{
+	session::Session,
+	value::{Tensor, TensorRef}
+};
+
+#[test]
+fn arrays() -> ort::Result<()> {
+	let floats = Float32Array::from(vec![1.0, 2.5, -3.0]);
+	let tensor = TensorRef::from_arrow(&floats)?;
+	assert_eq!(tensor.data_ptr()?, floats.values().as_ptr().cast());
+	assert_eq!(tensor.extract_tensor().1, [1.0, 2.5, -3.0]);
+
+	assert!(TensorRef::from_arrow(&Int64Array::from(vec![Some(1), None])).is_err());
+
+	let strings = StringArray::from(vec!["a", "bc"]);
+	assert_eq!(Tensor::from_arrow(&strings)?.try_extract_strings()?.1, ["a", "bc"]);
+	Ok(())
+}
+
+// `arrow_tabular.onnx` mirrors a tabular classifier with one input per column:
+// - `features = Concat(age, income)` concatenates two `float[N, 1]` columns into a `float[N, 2]` tensor;
+// - `probabilities = ZipMap(features)` converts `features` into a `seq(map(int64, float))`, with the class labels `[0,
+//   1]`;
+// - `city_out = Identity(city)` passes through a `string[N]` column.
+#[test]
+fn record_batch() -> ort::Result<()> {
+	let session = Session::builder()?.commit_from_file(
+		Path::new(env!("CARGO_MANIFEST_DIR"))
+			.join("tests")
+			.join("data")
+			.join("arrow_tabular.onnx")
+	)?;
+
+	let batch = RecordBatch::try_from_iter([
+		("city", Arc::new(StringArray::from(vec!["Lisbon", "Oslo"])) as Arc<dyn Array>),
+		("income", Arc::new(Float32Array::from(vec![Some(0.75), None])) as _),
+		("age", Arc::new(Float32Array::from(vec![0.25, 0.5])) as _),
+		("unused", Arc::new(Int64Array::from(vec![1, 2])) as _)
+	])
+	.unwrap();
+	let outputs = session.run(session.inputs_from_record_batch(&batch)?)?;
+	let predictions = outputs.to_record_batch()?;
+	assert_eq!(predictions.num_rows(), 2);
+
+	let features = predictions.column_by_name("features").unwrap().as_fixed_size_list();
+	assert_eq!(features.value_length(), 2);
+	let features = features.values().as_primitive::<Float32Type>();
+	assert_eq!(&features.values()[..3], [0.25, 0.75, 0.5]);
+	assert!(features.value(3).is_nan());
+
+	let probabilities = predictions.column_by_name("probabilities").unwrap().as_map();
+	let entries = probabilities.value(0);
+	assert_eq!(entries.column(0).as_primitive::<arrow_array::types::Int64Type>().values(), &[0, 1]);
+	assert_eq!(entries.column(1).as_primitive::<Float32Type>().values(), &[0.25, 0.75]);
+
+	let cities = predictions.column_by_name("city_out").unwrap().as_string::<i32>();
+	assert_eq!(cities.iter().flatten().collect::<Vec<_>>(), ["Lisbon", "Oslo"]);
+
+	let missing = RecordBatch::try_from_iter([("age", Arc::new(Float32Array::from(vec![1.0])) as Arc<dyn Array>)]).unwrap();
+	assert!(session.inputs_from_record_batch(&missing).is_err());
+	Ok(())
+}
@@ -0,0 +1,50 @@
+use std::path::Path;
+
+use ort::{
+	ErrorCode,
+	session::Session,
+	tensor::{Int4x2, Uint4x2},
+	value::{Tensor, TensorRef}
+};
+
+// `int4_identity.onnx` has two inputs, `signed: int4[N]` & `unsigned: uint4[N]`, which are passed through `Identity` to
+// the outputs `signed_out` & `unsigned_out`.
+fn session() -> ort::Result<Session> {
+	Session::builder()?.commit_from_file(
+		Path::new(env!("CARGO_MANIFEST_DIR"))
+			.join("tests")
+			.join("data")
+			.join("int4_identity.onnx")
+	)
+}
+
+#[test]
+fn packed_round_trip() -> ort::Result<()> {
+	let session = session()?;
+	let signed = [-8, 7, -1, 0, 3];
+	let unsigned = [15, 0, 1, 9, 4];
+	let outputs = session.run(ort::inputs! {
+		"signed" => Tensor::<Int4x2>::from_packed([5_usize], Int4x2::pack(&signed))?,
+		"unsigned" => Tensor::<Uint4x2>::from_packed([5_usize], Uint4x2::pack(&unsigned))?
+	})?;
+
+	let (shape, data) = outputs["signed_out"].try_extract_tensor::<Int4x2>()?;
+	assert_eq!(**shape, [5]);
+	assert_eq!(data.len(), 3);
+	assert_eq!(Int4x2::unpack(data, 5), signed);
+
+	let (shape, data) = outputs["unsigned_out"].try_extract_tensor::<Uint4x2>()?;
+	assert_eq!(**shape, [5]);
+	assert_eq!(Uint4x2::unpack(data, 5), unsigned);
+	Ok(())
+}
+
+#[test]
+fn from_array_rejects_packed_types() {
+	// The shape of an array of `Int4x2`s describes bytes, not 4-bit elements, so it would silently lose half the data.
+	let packed = Int4x2::pack(&[1, 2, 3, 4]);
+	let result = Tensor::from_array(([packed.len()], packed.clone()));
+	assert!(matches!(result, Err(e) if e.code() == ErrorCode::InvalidArgument));
+	let result = TensorRef::from_array_view(([packed.len()], &*packed));
+	assert!(matches!(result, Err(e) if e.code() == ErrorCode::InvalidArgument));
+}
@@ -0,0 +1,79 @@
+#![cfg(feature = "mmap")]
+
+use std::path::{Path, PathBuf};
+
+use ort::{
+	session::Session,
+	value::{Tensor, TensorRef}
+};
+
+fn data_path(path: &str) -> PathBuf {
+	Path::new(env!("CARGO_MANIFEST_DIR")).join("tests").join("data").join(path)
+}
+
+#[test]
+fn commit_from_mmap_runs_ort_model() -> ort::Result<()> {
+	let session = Session::builder()?.commit_from_mmap(data_path("upsample.ort"))?;
+	let input = ndarray::Array4::<f32>::zeros((1, 8, 8, 3));
+	let outputs = session.run(ort::inputs![TensorRef::from_array_view(&input)?])?;
+	assert_eq!(**outputs[0].shape(), [1, 16, 16, 3]);
+	Ok(())
+}
+
+#[test]
+fn commit_from_mmap_loads_external_data() -> ort::Result<()> {
+	// `model.onnx` computes `y = x + w`, where the initializer `w = [1, 2, 3]` is stored in `weights.bin`.
+	let session = Session::builder()?.commit_from_mmap(data_path("external_data/model.onnx"))?;
+	let outputs = session.run(ort::inputs![Tensor::from_array(([3], vec![1.0_f32, 1.0, 1.0]))?])?;
+	assert_eq!(outputs["y"].try_extract_tensor::<f32>()?.1, [2.0, 3.0, 4.0]);
+	Ok(())
+}
+
+/// Returns the amount of anonymous memory (i.e. memory allocated by Rust or ONNX Runtime, as opposed to mapped files)
+/// resident in this process, in bytes.
+#[cfg(target_os = "linux")]
+fn resident_anonymous_memory() -> usize {
+	let status = std::fs::read_to_string("/proc/self/status").unwrap();
+	let kilobytes = status
+		.lines()
+		.find_map(|line| line.strip_prefix("RssAnon:"))
+		.expect("`RssAnon` should be present in /proc/self/status");
+	kilobytes.trim().trim_end_matches("kB").trim().parse::<usize>().unwrap() * 1024
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn commit_from_mmap_does_not_read_external_data() -> ort::Result<()> {
+	use std::{fs, io::Write};
+
+	use ort::session::builder::GraphOptimizationLevel;
+
+	// `large.onnx` has a single 64 MiB initializer stored in `large.bin`, which is generated here rather than checked in.
+	const DATA_SIZE: usize = 64 << 20;
+	let dir = std::env::temp_dir().join(format!("ort-mmap-test-{}", std::process::id()));
+	fs::create_dir_all(&dir).unwrap();
+	fs::copy(data_path("external_data/large.onnx"), dir.join("large.onnx")).unwrap();
+	let mut data = fs::File::create(dir.join("large.bin")).unwrap();
+	let chunk = vec![1_u8; 1 << 20];
+	for _ in 0..DATA_SIZE / chunk.len() {
+		data.write_all(&chunk).unwrap();
+	}
+	drop((data, chunk));
+
+	// Commit a session first so the environment's one-time allocations (thread pools, etc.) aren't counted.
+	drop(Session::builder()?.commit_from_mmap(data_path("upsample.ort"))?);
+
+	let before = resident_anonymous_memory();
+	let session = Session::builder()?
+		.with_optimization_level(GraphOptimizationLevel::Disable)?
+		.commit_from_mmap(dir.join("large.onnx"))?;
+	let growth = resident_anonymous_memory().saturating_sub(before);
+	assert!(
+		growth < DATA_SIZE / 2,
+		"expected external data to be used from its mapping, but resident memory grew by {growth} bytes (data is {DATA_SIZE} bytes)"
+	);
+
+	drop(session);
+	fs::remove_dir_all(&dir).unwrap();
+	Ok(())
+}
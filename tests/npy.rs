@@ -0,0 +1,45 @@
+#![cfg(feature = "npy")]
+
+use std::{fs::File, io::Cursor, path::Path};
+
+use ort::{
+	tensor::{read_npz, write_npz},
+	value::{DynTensor, Tensor}
+};
+
+// `arrays.npz` is a compressed archive containing:
+// - `x`: `[[1, 2, 3], [4, 5, 6]]` as big-endian `float32`, stored in Fortran order;
+// - `s`: `['hello', 'ort']` as `<U5`.
+#[test]
+fn npz() -> ort::Result<()> {
+	let file = File::open(Path::new(env!("CARGO_MANIFEST_DIR")).join("tests").join("data").join("arrays.npz")).unwrap();
+	let arrays = read_npz(file)?;
+	assert_eq!(arrays.iter().map(|(name, _)| name.as_str()).collect::<Vec<_>>(), ["x", "s"]);
+
+	let (shape, x) = arrays[0].1.try_extract_tensor::<f32>()?;
+	assert_eq!(**shape, [2, 3]);
+	assert_eq!(x, [1.0, 2.0, 3.0, 4.0, 5.0, 6.0]);
+	let (_, s) = arrays[1].1.try_extract_strings()?;
+	assert_eq!(s, ["hello", "ort"]);
+
+	let mut buffer = Cursor::new(Vec::new());
+	write_npz(&mut buffer, arrays.iter().map(|(name, tensor)| (name, tensor)))?;
+	buffer.set_position(0);
+	let roundtrip = read_npz(buffer)?;
+	assert_eq!(roundtrip[0].1.try_extract_tensor::<f32>()?.1, x);
+	assert_eq!(roundtrip[1].1.try_extract_strings()?.1, s);
+	Ok(())
+}
+
+#[test]
+fn npy() -> ort::Result<()> {
+	let tensor = Tensor::from_array(([2usize, 2], vec![1_u16, 2, 3, 4]))?;
+	let mut npy = Vec::new();
+	tensor.write_npy(&mut npy)?;
+	assert_eq!(npy.len() % 64, 8);
+
+	assert_eq!(Tensor::<u16>::from_npy(&*npy)?.extract_tensor().1, [1, 2, 3, 4]);
+	assert!(Tensor::<i16>::from_npy(&*npy).is_err());
+	assert_eq!(DynTensor::from_npy(&*npy)?.try_extract_tensor::<u16>()?.1, [1, 2, 3, 4]);
+	Ok(())
+}
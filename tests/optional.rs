@@ -0,0 +1,44 @@
+use std::path::Path;
+
+use ort::{
+	session::Session,
+	value::{DynOptional, Optional, OptionalValueType, Tensor, TensorValueType, ValueType}
+};
+
+// `optional.onnx` has a single input `x: optional(tensor(float))`, and two outputs: `has_element`, the result of
+// `OptionalHasElement(x)`, and `y`, the result of `Identity(x)`.
+fn session() -> ort::Result<Session> {
+	Session::builder()?.commit_from_file(Path::new(env!("CARGO_MANIFEST_DIR")).join("tests").join("data").join("optional.onnx"))
+}
+
+#[test]
+fn optional_some() -> ort::Result<()> {
+	let session = session()?;
+	assert!(matches!(session.inputs[0].input_type, ValueType::Optional(_)));
+
+	let x = Optional::some(Tensor::from_array(([3usize], vec![1.0_f32, 2.0, 3.0]))?);
+	let outputs = session.run(ort::inputs![x])?;
+	assert_eq!(outputs["has_element"].try_extract_tensor::<bool>()?.1, [true]);
+
+	let y = outputs["y"].view().downcast::<OptionalValueType<TensorValueType<f32>>>()?;
+	assert!(y.is_some());
+	let y = y.as_inner().unwrap();
+	assert_eq!(y.extract_tensor().1, [1.0, 2.0, 3.0]);
+	Ok(())
+}
+
+#[test]
+fn optional_none() -> ort::Result<()> {
+	let session = session()?;
+
+	let x = DynOptional::none(&session.inputs[0].input_type)?;
+	assert!(x.is_none());
+	let outputs = session.run(ort::inputs![x])?;
+	assert_eq!(outputs["has_element"].try_extract_tensor::<bool>()?.1, [false]);
+
+	let y = outputs["y"].view().downcast::<OptionalValueType>()?;
+	assert!(y.is_none());
+	assert!(matches!(y.dtype(), ValueType::Optional(_)));
+	assert!(y.as_inner().is_none());
+	Ok(())
+}
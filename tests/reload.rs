@@ -0,0 +1,111 @@
+use std::{
+	fs,
+	path::Path,
+	sync::{
+		Arc,
+		atomic::{AtomicUsize, Ordering}
+	},
+	thread,
+	time::{Duration, Instant}
+};
+
+use ort::{
+	session::{ReloadableSession, Session},
+	value::TensorRef
+};
+
+#[test]
+fn reload_swaps_sessions() -> ort::Result<()> {
+	let data_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests").join("data");
+	let model_path = std::env::temp_dir().join(format!("ort-reload-test-{}.onnx", std::process::id()));
+	fs::copy(data_dir.join("upsample.onnx"), &model_path).unwrap();
+
+	let session = Arc::new(ReloadableSession::new(Session::builder, &model_path)?);
+	assert!(!session.reload_if_changed()?);
+
+	let old = session.session();
+	session.reload()?;
+	assert_eq!(session.generation(), 1);
+	assert!(!Arc::ptr_eq(&old, &session.session()));
+
+	// the old session remains usable after being swapped out
+	let input = ndarray::Array4::<f32>::zeros((1, 8, 8, 3));
+	let outputs = old.run(ort::inputs![TensorRef::from_array_view(&input)?])?;
+	assert_eq!(**outputs[0].shape(), [1, 16, 16, 3]);
+
+	// models with a different signature are rejected
+	fs::copy(data_dir.join("vectorizer.onnx"), &model_path).unwrap();
+	assert!(session.reload().is_err());
+	assert_eq!(session.generation(), 1);
+	assert_eq!(session.session().inputs[0].name, "up_sampling2d_input:0");
+
+	fs::remove_file(&model_path).unwrap();
+	Ok(())
+}
+
+/// Polls `condition` until it is true, panicking if it takes longer than 10 seconds.
+fn wait_for(mut condition: impl FnMut() -> bool) {
+	let start = Instant::now();
+	while !condition() {
+		assert!(start.elapsed() < Duration::from_secs(10), "timed out waiting for the watcher");
+		thread::sleep(Duration::from_millis(10));
+	}
+}
+
+/// Replaces the model at `path` by moving a copy of `model` into place, so the watcher never sees a partial file.
+fn replace_model(model: &Path, path: &Path) {
+	let temp_path = path.with_extension("tmp");
+	fs::copy(model, &temp_path).unwrap();
+	fs::rename(&temp_path, path).unwrap();
+}
+
+#[test]
+fn watch_reloads_changed_model() -> ort::Result<()> {
+	let data_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests").join("data");
+	let model_path = std::env::temp_dir().join(format!("ort-watch-test-{}.onnx", std::process::id()));
+	fs::copy(data_dir.join("upsample.onnx"), &model_path).unwrap();
+
+	let commits = Arc::new(AtomicUsize::new(0));
+	let session = Arc::new(ReloadableSession::new(
+		{
+			let commits = Arc::clone(&commits);
+			move || {
+				commits.fetch_add(1, Ordering::Relaxed);
+				Session::builder()
+			}
+		},
+		&model_path
+	)?);
+	let watcher = session.watch(Duration::from_millis(10))?;
+
+	// a model with a different signature fails to reload; the error is reported & the current session kept
+	replace_model(&data_dir.join("vectorizer.onnx"), &model_path);
+	let mut error = None;
+	wait_for(|| {
+		error = watcher.take_error();
+		error.is_some()
+	});
+	assert!(error.unwrap().message().contains("different signature"));
+	assert_eq!(session.generation(), 0);
+	assert_eq!(commits.load(Ordering::Relaxed), 2);
+
+	// the broken model isn't committed again until the file changes
+	thread::sleep(Duration::from_millis(100));
+	assert_eq!(commits.load(Ordering::Relaxed), 2);
+	assert!(watcher.take_error().is_none());
+
+	// once the file is fixed, the reload succeeds; the replaced session is dropped by whichever thread releases it last
+	let old = session.session();
+	replace_model(&data_dir.join("upsample.onnx"), &model_path);
+	wait_for(|| session.generation() == 1);
+	thread::spawn(move || drop(old)).join().unwrap();
+
+	// the watcher stops once dropped
+	drop(watcher);
+	replace_model(&data_dir.join("vectorizer.onnx"), &model_path);
+	thread::sleep(Duration::from_millis(50));
+	assert_eq!(session.generation(), 1);
+
+	fs::remove_file(&model_path).unwrap();
+	Ok(())
+}
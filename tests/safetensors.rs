@@ -0,0 +1,38 @@
+#![cfg(feature = "safetensors")]
+
+use std::path::{Path, PathBuf};
+
+use ort::{session::Session, value::TensorRef};
+
+// `add_initializer.onnx` computes `Y = X + W`, where `W` is a `float[3]` initializer of zeros.
+// `add_initializer.safetensors` contains `W = [1, 2, 3]`.
+fn data_path(name: &str) -> PathBuf {
+	Path::new(env!("CARGO_MANIFEST_DIR")).join("tests").join("data").join(name)
+}
+
+fn run(session: &Session) -> ort::Result<Vec<f32>> {
+	let x = [10.0_f32, 20.0, 30.0];
+	let outputs = session.run(ort::inputs![TensorRef::from_array_view(([3usize], &x[..]))?])?;
+	Ok(outputs["Y"].try_extract_tensor::<f32>()?.1.to_vec())
+}
+
+#[test]
+fn safetensors_initializers() -> ort::Result<()> {
+	let session = Session::builder()?
+		.with_safetensors_initializers(data_path("add_initializer.safetensors"))?
+		.commit_from_file(data_path("add_initializer.onnx"))?;
+	assert_eq!(run(&session)?, [11.0, 22.0, 33.0]);
+
+	let bytes = std::fs::read(data_path("add_initializer.safetensors")).unwrap();
+	let session = Session::builder()?
+		.with_safetensors_initializers(bytes.clone())?
+		.commit_from_file(data_path("add_initializer.onnx"))?;
+	assert_eq!(run(&session)?, [11.0, 22.0, 33.0]);
+
+	// tensors can be renamed or skipped
+	let session = Session::builder()?
+		.with_safetensors_initializers_renamed(bytes, |_| None)?
+		.commit_from_file(data_path("add_initializer.onnx"))?;
+	assert_eq!(run(&session)?, [10.0, 20.0, 30.0]);
+	Ok(())
+}
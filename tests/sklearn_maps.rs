@@ -0,0 +1,39 @@
+use std::{collections::HashMap, path::Path};
+
+use ort::{
+	memory::Allocator,
+	session::Session,
+	value::{Map, TensorRef}
+};
+
+// `sklearn_maps.onnx` mirrors the outputs of scikit-learn models converted with `skl2onnx`:
+// - `Z = ZipMap(X)` converts a `float[N, 3]` tensor of probabilities into a `seq(map(int64, float))`, with the class
+//   labels `[10, 20, 30]`;
+// - `Y = DictVectorizer(M)` converts a `map(int64, string)` into a `string[1, 3]` tensor, with the vocabulary `[1, 2,
+//   3]`.
+#[test]
+fn sklearn_maps() -> ort::Result<()> {
+	let session = Session::builder()?.commit_from_file(Path::new(env!("CARGO_MANIFEST_DIR")).join("tests").join("data").join("sklearn_maps.onnx"))?;
+
+	let probabilities = [0.1_f32, 0.2, 0.7, 0.5, 0.25, 0.25];
+	let labels = Map::<i64, String>::new([(1, "cat".to_string()), (3, "dog".to_string())])?;
+	let outputs = session.run(ort::inputs![
+		"X" => TensorRef::from_array_view(([2usize, 3], &probabilities[..]))?,
+		"M" => labels
+	])?;
+
+	let maps = outputs["Z"].try_extract_sequence_of_maps::<i64, f32>(&Allocator::default())?;
+	assert_eq!(maps, [HashMap::from([(10, 0.1), (20, 0.2), (30, 0.7)]), HashMap::from([(10, 0.5), (20, 0.25), (30, 0.25)])]);
+
+	let (shape, vectorized) = outputs["Y"].try_extract_strings()?;
+	assert_eq!(**shape, [1, 3]);
+	assert_eq!(vectorized, ["cat", "", "dog"]);
+	Ok(())
+}
+
+#[test]
+fn empty_map() -> ort::Result<()> {
+	let map = Map::<i64, f32>::new(Vec::<(i64, f32)>::new())?;
+	assert!(map.try_extract_key_values::<i64, f32>()?.is_empty());
+	Ok(())
+}
@@ -19,7 +19,7 @@ fn squeezenet_mushroom() -> ort::Result<()> {
 
 	ort::init().with_name("integration_test").commit()?;
 
-	let mut session = Session::builder()?
+	let session = Session::builder()?
 		.with_optimization_level(GraphOptimizationLevel::Level1)?
 		.with_intra_threads(1)?
 		.commit_from_url("https://cdn.pyke.io/0/pyke:ort-rs/example-models@0.0.0/squeezenet.onnx")
@@ -0,0 +1,121 @@
+use std::path::Path;
+
+use ort::{
+	session::{Session, stateful::StatefulSession},
+	value::{DynValue, Tensor}
+};
+
+// `kv_cache.onnx` mimics a single-layer decoder with a KV cache:
+// - `new_key = Unsqueeze(Cast<float>(input_ids), axes=[2])` turns the `int64[batch_size, sequence_length]` token IDs
+//   into the "keys" of the new tokens, of shape `[batch_size, sequence_length, 1]`; `new_value = Neg(new_key)`;
+// - `present.0.key = Concat(past_key_values.0.key, new_key, axis=1)`, and likewise for the values, so the cache holds
+//   every token ID seen so far;
+// - `total = ReduceSum(present.0.key)` is the sum of every token ID seen so far.
+fn kv_cache_session() -> ort::Result<Session> {
+	Session::builder()?.commit_from_file(Path::new(env!("CARGO_MANIFEST_DIR")).join("tests").join("data").join("kv_cache.onnx"))
+}
+
+fn step(decoder: &mut StatefulSession<'_>, ids: &[i64]) -> ort::Result<f32> {
+	let outputs = decoder.run(ort::inputs! {
+		"input_ids" => Tensor::from_array(([1, ids.len()], ids.to_vec()))?
+	})?;
+	assert_eq!(outputs.len(), 1, "cache outputs should not be returned");
+	outputs["total"].try_extract_scalar::<f32>()
+}
+
+fn cache_contents(value: &DynValue) -> ort::Result<Vec<f32>> {
+	let (shape, data) = value.try_extract_tensor::<f32>()?;
+	assert_eq!(shape[0], 1);
+	assert_eq!(shape[2], 1);
+	Ok(data.to_vec())
+}
+
+#[test]
+fn detects_cache_pairs() -> ort::Result<()> {
+	let session = kv_cache_session()?;
+	let decoder = StatefulSession::new(&session)?;
+	assert_eq!(decoder.sequence_length(), 0);
+	for past in ["past_key_values.0.key", "past_key_values.0.value"] {
+		let cache = decoder.cache(past).expect("cache input should be detected");
+		assert_eq!(**cache.shape(), [1, 0, 1]);
+	}
+	assert!(decoder.cache("input_ids").is_none());
+
+	assert!(
+		StatefulSession::builder(&session)
+			.with_cache("past_key_values.0.key", "missing")
+			.build()
+			.is_err()
+	);
+	assert!(StatefulSession::builder(&session).with_cache("missing", "present.0.key").build().is_err());
+	Ok(())
+}
+
+#[test]
+fn feeds_cache_back() -> ort::Result<()> {
+	let session = kv_cache_session()?;
+	let mut decoder = StatefulSession::new(&session)?;
+
+	assert_eq!(step(&mut decoder, &[1, 2, 3])?, 6.0);
+	assert_eq!(decoder.sequence_length(), 3);
+	assert_eq!(step(&mut decoder, &[4])?, 10.0);
+	assert_eq!(decoder.sequence_length(), 4);
+	assert_eq!(cache_contents(decoder.cache("past_key_values.0.key").unwrap())?, [1.0, 2.0, 3.0, 4.0]);
+	assert_eq!(cache_contents(decoder.cache("past_key_values.0.value").unwrap())?, [-1.0, -2.0, -3.0, -4.0]);
+
+	decoder.reset()?;
+	assert_eq!(decoder.sequence_length(), 0);
+	assert_eq!(step(&mut decoder, &[5])?, 5.0);
+	Ok(())
+}
+
+#[test]
+fn truncate_and_rollback() -> ort::Result<()> {
+	let session = kv_cache_session()?;
+	let mut decoder = StatefulSession::new(&session)?;
+	step(&mut decoder, &[1, 2, 3, 4])?;
+
+	decoder.rollback(1)?;
+	assert_eq!(decoder.sequence_length(), 3);
+	assert_eq!(cache_contents(decoder.cache("past_key_values.0.key").unwrap())?, [1.0, 2.0, 3.0]);
+	assert_eq!(step(&mut decoder, &[10])?, 16.0);
+
+	decoder.truncate(1)?;
+	assert_eq!(cache_contents(decoder.cache("past_key_values.0.value").unwrap())?, [-1.0]);
+	assert_eq!(step(&mut decoder, &[2])?, 3.0);
+
+	assert!(decoder.truncate(3).is_err());
+	assert!(decoder.rollback(3).is_err());
+	assert_eq!(decoder.sequence_length(), 2);
+	Ok(())
+}
+
+#[test]
+fn preallocated_cache() -> ort::Result<()> {
+	let session = kv_cache_session()?;
+	let mut decoder = StatefulSession::builder(&session).with_preallocated_cache(6).build()?;
+
+	assert_eq!(step(&mut decoder, &[1, 2, 3])?, 6.0);
+	let first_buffer = decoder.cache("past_key_values.0.key").unwrap().data_ptr()?;
+	assert_eq!(step(&mut decoder, &[4])?, 10.0);
+	let second_buffer = decoder.cache("past_key_values.0.key").unwrap().data_ptr()?;
+	assert_ne!(first_buffer, second_buffer);
+	assert_eq!(cache_contents(decoder.cache("past_key_values.0.key").unwrap())?, [1.0, 2.0, 3.0, 4.0]);
+
+	// The cache alternates between the same two buffers as it grows.
+	assert_eq!(step(&mut decoder, &[5])?, 15.0);
+	assert_eq!(decoder.cache("past_key_values.0.key").unwrap().data_ptr()?, first_buffer);
+
+	// Rolling back compacts the cache within its current buffer.
+	decoder.rollback(2)?;
+	assert_eq!(decoder.cache("past_key_values.0.key").unwrap().data_ptr()?, first_buffer);
+	assert_eq!(cache_contents(decoder.cache("past_key_values.0.value").unwrap())?, [-1.0, -2.0, -3.0]);
+	assert_eq!(step(&mut decoder, &[7, 8, 9])?, 30.0);
+	assert_eq!(decoder.sequence_length(), 6);
+
+	// Steps that would overflow the preallocated buffers are rejected without touching the cache.
+	assert!(step(&mut decoder, &[10]).is_err());
+	assert_eq!(decoder.sequence_length(), 6);
+	assert_eq!(cache_contents(decoder.cache("past_key_values.0.key").unwrap())?, [1.0, 2.0, 3.0, 7.0, 8.0, 9.0]);
+	Ok(())
+}
@@ -0,0 +1,38 @@
+use std::path::Path;
+
+use ort::{
+	session::{
+		Session,
+		test_data::{TestDataSet, Tolerance}
+	},
+	value::{DynTensor, Tensor}
+};
+
+fn data_dir() -> std::path::PathBuf {
+	Path::new(env!("CARGO_MANIFEST_DIR")).join("tests").join("data")
+}
+
+// `upsample/test_data_set_0` contains a `[1, 1, 2, 3]` input & the `[1, 2, 4, 3]` output of `upsample.onnx`, which
+// repeats each pixel twice along both spatial axes. Neither tensor is named, so they are matched by index.
+#[test]
+fn test_data_set() -> ort::Result<()> {
+	let session = Session::builder()?.commit_from_file(data_dir().join("upsample.onnx"))?;
+	let data_set = TestDataSet::load(data_dir().join("upsample").join("test_data_set_0"))?;
+	assert_eq!(data_set.inputs().len(), 1);
+	assert_eq!(data_set.outputs().len(), 1);
+	assert_eq!(**data_set.outputs()[0].1.shape(), [1, 2, 4, 3]);
+	session.run_test_data_set(&data_set, Tolerance::default())?;
+
+	let (input_name, input) = &data_set.inputs()[0];
+	let (_, expected) = data_set.outputs()[0].1.try_extract_tensor::<f32>()?;
+	let mut expected = expected.to_vec();
+	expected[5] += 0.01;
+	let wrong = TestDataSet::new(
+		vec![(input_name.clone(), DynTensor::from_tensor_proto(&input.to_tensor_proto()?)?)],
+		vec![(String::new(), Tensor::from_array(([1usize, 2, 4, 3], expected))?.upcast())]
+	);
+	let err = session.run_test_data_set(&wrong, Tolerance::default()).unwrap_err();
+	assert!(err.to_string().contains("at index 5"), "{err}");
+	session.run_test_data_set(&wrong, Tolerance::new(0.0, 0.1))?;
+	Ok(())
+}
@@ -0,0 +1,116 @@
+use std::{
+	future::Future,
+	path::Path,
+	thread,
+	time::{Duration, Instant}
+};
+
+use ort::{
+	ErrorCode,
+	session::{Session, run_options::RunOptions},
+	value::TensorRef
+};
+
+fn upsample_session() -> ort::Result<Session> {
+	Session::builder()?
+		.with_intra_threads(2)?
+		.commit_from_file(Path::new(env!("CARGO_MANIFEST_DIR")).join("tests").join("data").join("upsample.onnx"))
+}
+
+fn block_on<F: Future>(future: F) -> F::Output {
+	tokio::runtime::Builder::new_current_thread()
+		.enable_all()
+		.build()
+		.unwrap()
+		.block_on(future)
+}
+
+#[test]
+fn run_with_timeout() -> ort::Result<()> {
+	let session = upsample_session()?;
+	let input = ndarray::Array4::<f32>::zeros((1, 8, 8, 3));
+
+	let outputs = session.run_with_timeout(ort::inputs![TensorRef::from_array_view(&input)?], Duration::from_secs(60))?;
+	assert_eq!(**outputs[0].shape(), [1, 16, 16, 3]);
+	drop(outputs);
+
+	let error = session
+		.run_with_timeout(ort::inputs![TensorRef::from_array_view(&input)?], Duration::ZERO)
+		.expect_err("run should time out");
+	assert_eq!(error.code(), ErrorCode::Timeout);
+
+	// A timed out run shouldn't affect the next one.
+	let outputs = session.run_with_timeout(ort::inputs![TensorRef::from_array_view(&input)?], Duration::from_secs(60))?;
+	assert_eq!(**outputs[0].shape(), [1, 16, 16, 3]);
+	Ok(())
+}
+
+#[test]
+fn run_with_timeout_concurrently() -> ort::Result<()> {
+	let session = upsample_session()?;
+	thread::scope(|s| {
+		let threads: Vec<_> = (0..8)
+			.map(|i| {
+				let session = &session;
+				s.spawn(move || -> ort::Result<()> {
+					let input = ndarray::Array4::<f32>::zeros((1, 8, 8, 3));
+					// Interleave long & already-expired deadlines, so the watchdog has to track several at once.
+					let timeout = if i % 2 == 0 { Duration::from_secs(60) } else { Duration::ZERO };
+					for _ in 0..16 {
+						match session.run_with_timeout(ort::inputs![TensorRef::from_array_view(&input)?], timeout) {
+							Ok(_) => assert!(!timeout.is_zero()),
+							Err(e) => assert_eq!(e.code(), ErrorCode::Timeout)
+						}
+					}
+					Ok(())
+				})
+			})
+			.collect();
+		threads.into_iter().try_for_each(|t| t.join().unwrap())
+	})
+}
+
+#[test]
+fn run_async_with_deadline() -> ort::Result<()> {
+	let session = upsample_session()?;
+	let input = ndarray::Array4::<f32>::zeros((1, 8, 8, 3));
+	let options = RunOptions::new()?;
+
+	let outputs =
+		block_on(session.run_async_with_deadline(ort::inputs![TensorRef::from_array_view(&input)?], &options, Instant::now() + Duration::from_secs(60))?)?;
+	assert_eq!(**outputs[0].shape(), [1, 16, 16, 3]);
+	drop(outputs);
+
+	let error = block_on(session.run_async_with_deadline(ort::inputs![TensorRef::from_array_view(&input)?], &options, Instant::now())?)
+		.expect_err("run should time out");
+	assert_eq!(error.code(), ErrorCode::Timeout);
+
+	// The options stay terminated until they are explicitly unterminated.
+	assert!(block_on(session.run_async(ort::inputs![TensorRef::from_array_view(&input)?], &options)?).is_err());
+	options.unterminate()?;
+	let outputs = block_on(session.run_async(ort::inputs![TensorRef::from_array_view(&input)?], &options)?)?;
+	assert_eq!(**outputs[0].shape(), [1, 16, 16, 3]);
+	Ok(())
+}
+
+#[test]
+fn drop_in_flight_inference_fut() -> ort::Result<()> {
+	let session = upsample_session()?;
+	let input = ndarray::Array4::<f32>::zeros((1, 256, 256, 3));
+	let options = RunOptions::new()?;
+
+	// Dropping the future without ever polling it terminates the run, and must not return until ONNX Runtime is done
+	// with the borrowed options & inputs.
+	let future = session.run_async(ort::inputs![TensorRef::from_array_view(&input)?], &options)?;
+	drop(future);
+
+	// The same goes for a future with a deadline, whose watchdog must also be stopped.
+	let future = session.run_async_with_deadline(ort::inputs![TensorRef::from_array_view(&input)?], &options, Instant::now() + Duration::from_secs(60))?;
+	drop(future);
+
+	// The options & session remain usable afterwards.
+	options.unterminate()?;
+	let outputs = block_on(session.run_async(ort::inputs![TensorRef::from_array_view(&input)?], &options)?)?;
+	assert_eq!(**outputs[0].shape(), [1, 512, 512, 3]);
+	Ok(())
+}
@@ -0,0 +1,73 @@
+#![cfg(feature = "derive")]
+
+use std::{fmt::Debug, path::Path};
+
+use ort::{
+	session::{Session, builder::GraphOptimizationLevel},
+	tensor::IntoTensorElementType,
+	value::{DynValue, Tensor, TensorRef}
+};
+
+#[derive(ort::Inputs)]
+struct UpsampleInputs<'a> {
+	#[ort(name = "up_sampling2d_input:0", rank = 4)]
+	image: TensorRef<'a, f32>
+}
+
+// `T` isn't bounded by `'static`, so the derived `WithLifetime` must require `T` to outlive its lifetime.
+#[derive(ort::Inputs)]
+struct GenericInputs<'a, T: IntoTensorElementType + Debug> {
+	#[ort(name = "up_sampling2d_input:0", rank = 4)]
+	image: TensorRef<'a, T>
+}
+
+#[derive(ort::Outputs)]
+struct UpsampleOutputs {
+	#[ort(name = "Identity:0", rank = 4)]
+	upsampled: Tensor<f32>
+}
+
+#[derive(ort::Inputs)]
+struct WrongTypeInputs {
+	#[ort(name = "up_sampling2d_input:0")]
+	image: Tensor<i64>
+}
+
+#[derive(ort::Outputs)]
+struct MissingOutputs {
+	#[allow(unused)]
+	output: DynValue
+}
+
+#[test]
+fn typed_upsample() -> ort::Result<()> {
+	ort::init().with_name("integration_test").commit()?;
+
+	let session = Session::builder()?
+		.with_optimization_level(GraphOptimizationLevel::Level1)?
+		.with_intra_threads(1)?
+		.commit_from_file(Path::new(env!("CARGO_MANIFEST_DIR")).join("tests").join("data").join("upsample.onnx"))
+		.expect("Could not load model");
+
+	let upsample = session.typed::<UpsampleInputs, UpsampleOutputs>()?;
+	for size in [16, 32] {
+		let image = ndarray::Array4::<f32>::from_elem((1, size, size, 3), 0.5);
+		let outputs = upsample.run(UpsampleInputs {
+			image: TensorRef::from_array_view(&image)?
+		})?;
+		assert_eq!(**outputs.upsampled.shape(), [1, size as i64 * 2, size as i64 * 2, 3]);
+		assert!(outputs.upsampled.extract_tensor().1.iter().all(|&x| x == 0.5));
+	}
+
+	let generic = session.typed::<GenericInputs<f32>, UpsampleOutputs>()?;
+	let image = ndarray::Array4::<f32>::from_elem((1, 16, 16, 3), 0.5);
+	let outputs = generic.run(GenericInputs {
+		image: TensorRef::from_array_view(&image)?
+	})?;
+	assert_eq!(**outputs.upsampled.shape(), [1, 32, 32, 3]);
+
+	assert!(session.typed::<WrongTypeInputs, UpsampleOutputs>().is_err());
+	assert!(session.typed::<UpsampleInputs, MissingOutputs>().is_err());
+
+	Ok(())
+}